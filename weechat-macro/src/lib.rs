@@ -237,6 +237,7 @@ pub fn plugin(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             _plugin: *mut weechat::weechat_sys::t_weechat_plugin
         ) -> weechat::libc::c_int {
             unsafe {
+                Weechat::plugin_unloading();
                 __PLUGIN = None;
                 Weechat::free();
             }
@@ -23,3 +23,14 @@ pub const WEECHAT_CONFIG_OPTION_SET_OPTION_NOT_FOUND: c_int = -1;
 pub const WEECHAT_CONFIG_READ_OK: c_int = 0;
 pub const WEECHAT_CONFIG_READ_MEMORY_ERROR: c_int = -1;
 pub const WEECHAT_CONFIG_READ_FILE_NOT_FOUND: c_int = -2;
+
+/* types of hdata variables */
+pub const WEECHAT_HDATA_OTHER: c_int = 0;
+pub const WEECHAT_HDATA_CHAR: c_int = 1;
+pub const WEECHAT_HDATA_INTEGER: c_int = 2;
+pub const WEECHAT_HDATA_LONG: c_int = 3;
+pub const WEECHAT_HDATA_STRING: c_int = 4;
+pub const WEECHAT_HDATA_POINTER: c_int = 5;
+pub const WEECHAT_HDATA_TIME: c_int = 6;
+pub const WEECHAT_HDATA_HASHTABLE: c_int = 7;
+pub const WEECHAT_HDATA_SHARED_STRING: c_int = 8;
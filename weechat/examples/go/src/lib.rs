@@ -29,26 +29,34 @@
 //! Rewrite of the popular go.py script for Weechat
 //!
 //! This is a complete rewrite of go.py found in the Weechat scripts repo, it's
-//! using the [fuzzy-matcher] crate to provide powerful fzf-like matching of
-//! buffer names.
+//! using the fuzzy matching helpers of the weechat crate, which are based on
+//! the [fuzzy-matcher] crate, to provide powerful fzf-like matching of buffer
+//! names.
 //!
 //! [fuzzy-matcher]: https://docs.rs/fuzzy-matcher/
 
-use std::{borrow::Cow, cell::RefCell, cmp::Reverse, rc::Rc};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    fs,
+    path::PathBuf,
+    rc::Rc,
+};
 
 use weechat::{
     buffer::Buffer,
     config,
     hooks::{
-        Command, CommandCallback, CommandRun, CommandRunCallback, CommandSettings,
-        ModifierCallback, ModifierData, ModifierHook,
+        Command, CommandCallback, CommandRun, CommandRunCallback, CommandSettings, Completion,
+        CompletionHook, ModifierCallback, ModifierData, ModifierHook,
     },
-    infolist::InfolistVariable,
-    plugin, Args, Plugin, Prefix, ReturnCode, Weechat,
+    plugin,
+    util::fuzzy_indices,
+    Args, Plugin, Prefix, ReturnCode, Weechat,
 };
 
-use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
-
 config!(
     "go",
     Section look {
@@ -135,26 +143,174 @@ config!(
             "Automatically jump to a buffer when it is uniquely selected.",
             false,
         },
+
+        only_channels: bool {
+            "Only show buffers that are channels, i.e. buffers with the \
+                \"type\" local variable set to \"channel\".",
+            false,
+        },
+
+        digit_select: bool {
+            "Select the n-th result when the digit n is typed, 0 selects the \
+                tenth result. Ignored if buffer_numbers is enabled.",
+            false,
+        },
+
+        hide_hidden: bool {
+            "Don't show buffers that are hidden, e.g. using /buffer hide.",
+            false,
+        },
+
+        collapse_merged: bool {
+            "Only show the active buffer out of a group of merged buffers.",
+            false,
+        },
+
+        history_size: Integer {
+            "Number of patterns that are remembered after jumping to a buffer, \
+                the history can be browsed with the history keys while in \
+                go-mode. Set to 0 to disable the history.",
+            20,
+            0..1000,
+        },
     }
 );
 
+/// The history of patterns that were used to jump to a buffer.
+///
+/// The history is deduplicated, using a pattern again moves it to the end of
+/// the history, and it's stored in a file in the Weechat home directory so it
+/// survives a plugin reload.
+struct PatternHistory {
+    /// The patterns, the oldest pattern comes first.
+    patterns: VecDeque<String>,
+    /// The maximal number of patterns that are remembered.
+    max_size: usize,
+    /// The file the history is stored in.
+    path: PathBuf,
+}
+
+impl PatternHistory {
+    /// Load the history from the given file.
+    fn load(path: PathBuf, max_size: usize) -> Self {
+        let mut history = PatternHistory {
+            patterns: VecDeque::new(),
+            max_size,
+            path,
+        };
+
+        if let Ok(content) = fs::read_to_string(&history.path) {
+            for pattern in content.lines() {
+                history.add(pattern);
+            }
+        }
+
+        history
+    }
+
+    /// Add a pattern to the end of the history, removing any earlier
+    /// occurrence of the same pattern.
+    fn add(&mut self, pattern: &str) {
+        let pattern = pattern.trim();
+
+        if pattern.is_empty() || self.max_size == 0 {
+            return;
+        }
+
+        self.patterns.retain(|p| p != pattern);
+        self.patterns.push_back(pattern.to_owned());
+
+        while self.patterns.len() > self.max_size {
+            self.patterns.pop_front();
+        }
+    }
+
+    /// Store the history in its file.
+    fn save(&self) {
+        let content: Vec<&str> = self.patterns.iter().map(|p| p.as_str()).collect();
+
+        if let Err(e) = fs::write(&self.path, content.join("\n")) {
+            Weechat::print(&format!(
+                "{}Error writing the go history file {}: {}",
+                Weechat::prefix(Prefix::Error),
+                self.path.display(),
+                e
+            ));
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    fn get(&self, index: usize) -> Option<&str> {
+        self.patterns.get(index).map(|p| p.as_str())
+    }
+}
+
 #[allow(unused)]
 struct Go {
     command: Command,
+    completion: CompletionHook,
 }
 
 #[derive(Clone)]
 struct InnerGo {
     running_state: Rc<RefCell<Option<RunningState>>>,
     config: Rc<Config>,
+    history: Rc<RefCell<PatternHistory>>,
 }
 
 impl InnerGo {
     fn stop(&self, weechat: &Weechat, switch_buffer: bool) {
         if let Some(state) = self.running_state.borrow_mut().take() {
+            if switch_buffer {
+                self.remember_pattern(&state.last_input);
+            }
+
             state.stop(weechat, switch_buffer);
         }
     }
+
+    /// Add a pattern that was used to jump to a buffer to the history.
+    fn remember_pattern(&self, pattern: &str) {
+        let mut history = self.history.borrow_mut();
+        history.add(pattern);
+        history.save();
+    }
+
+    /// Move through the pattern history and put the selected pattern into
+    /// the input of the buffer.
+    ///
+    /// Moving past the newest pattern restores an empty input.
+    fn browse_history(&self, buffer: &Buffer, backwards: bool) {
+        let history_len = self.history.borrow().len();
+
+        let position = {
+            let mut state = self.running_state.borrow_mut();
+            let state = match state.as_mut() {
+                Some(state) => state,
+                None => return,
+            };
+
+            state.history_position = match (state.history_position, backwards) {
+                (_, _) if history_len == 0 => None,
+                (None, true) => Some(history_len - 1),
+                (Some(position), true) => Some(position.saturating_sub(1)),
+                (None, false) => None,
+                (Some(position), false) if position + 1 < history_len => Some(position + 1),
+                (Some(_), false) => None,
+            };
+
+            state.history_position
+        };
+
+        // The state isn't borrowed anymore, setting the input will run our
+        // modifier callback.
+        let history = self.history.borrow();
+        let pattern = position.and_then(|p| history.get(p)).unwrap_or("");
+        buffer.set_input(pattern);
+    }
 }
 
 #[derive(Clone)]
@@ -166,8 +322,12 @@ struct InputState {
 impl InputState {
     /// Restore the input state on the given buffer.
     fn restore_for_buffer(&self, buffer: &Buffer) {
-        buffer.set_input(&self.input_string);
-        buffer.set_input_position(self.input_position);
+        if buffer
+            .replace_input(&self.input_string, self.input_position as usize)
+            .is_err()
+        {
+            buffer.set_input(&self.input_string);
+        }
     }
 }
 
@@ -187,18 +347,48 @@ struct BufferData {
     indices: Vec<usize>,
     full_name: Rc<String>,
     short_name: Rc<String>,
+    /// The part of the full name that distinguishes this buffer from other
+    /// buffers with the same short name, e.g. the server name.
+    qualifier: Option<Rc<String>>,
+    merged: bool,
 }
 
-impl<'a> From<&Buffer<'a>> for BufferData {
-    fn from(buffer: &Buffer) -> Self {
+impl BufferData {
+    /// Create the data for a buffer with an initial score of 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer the data is created for.
+    ///
+    /// * `merged` - Is the buffer merged with other buffers.
+    fn new(buffer: &Buffer, merged: bool) -> Self {
         BufferData {
             score: 0,
             number: buffer.number(),
             indices: Vec::new(),
             full_name: Rc::new(buffer.full_name().to_string()),
-            short_name: Rc::new(buffer.short_name().to_string()),
+            short_name: Rc::new(buffer.short_name_or_name().to_string()),
+            qualifier: None,
+            merged,
         }
     }
+
+    /// Get the part of the full name that precedes the short name, e.g.
+    /// `irc.libera` for `irc.libera.#general`.
+    fn full_name_prefix(&self) -> &str {
+        self.full_name
+            .strip_suffix(self.short_name.as_str())
+            .and_then(|prefix| prefix.strip_suffix('.'))
+            .unwrap_or(&self.full_name)
+    }
+
+    /// Get the label that is shown next to the short name, if this buffer
+    /// shares its short name with other buffers, e.g. `(libera)`.
+    fn qualifier_label(&self) -> Option<String> {
+        self.qualifier
+            .as_ref()
+            .map(|qualifier| format!("({})", qualifier))
+    }
 }
 
 #[derive(Clone)]
@@ -216,31 +406,52 @@ struct BufferList {
 impl BufferList {
     /// Create a new buffer list.
     ///
-    /// This will fetch all the buffers from the Weechat info-list and set an
-    /// initial score of 0 for every buffer.
+    /// This will fetch all the buffers from Weechat and set an initial score
+    /// of 0 for every buffer.
     fn new(weechat: &Weechat, config: Rc<Config>) -> Self {
-        let info_list = weechat
-            .get_infolist("buffer", None)
-            .expect("Can't get buffer infolist");
+        let all_buffers: Vec<Buffer> = weechat.buffers().collect();
+        let mut buffers = Vec::with_capacity(all_buffers.len());
 
-        let mut buffers = Vec::new();
+        // Merged buffers share their number, count the buffers per number
+        // once instead of searching the whole list for every buffer.
+        let mut numbers: HashMap<i32, usize> = HashMap::new();
 
-        for item in info_list {
-            let buffer = item.get("pointer").expect("Infolist doesn't have a buffer");
+        for b in &all_buffers {
+            *numbers.entry(b.number()).or_default() += 1;
+        }
 
-            if let InfolistVariable::Buffer(b) = buffer {
-                let mut buffer_data = BufferData::from(&b);
+        // The options can't change while the list is built, look them up
+        // once instead of once per buffer.
+        let only_channels = config.behaviour().only_channels();
+        let hide_hidden = config.behaviour().hide_hidden();
+        let collapse_merged = config.behaviour().collapse_merged();
+        let use_core_instead_weechat = config.look().use_core_instead_weechat();
 
-                if config.look().use_core_instead_weechat()
-                    && buffer_data.short_name.as_str() == "weechat"
-                {
-                    buffer_data.short_name = Rc::new("core".to_string());
-                }
+        for b in all_buffers {
+            if only_channels && b.get_localvar("type").as_deref() != Some("channel") {
+                continue;
+            }
+
+            if hide_hidden && b.is_hidden() {
+                continue;
+            }
+
+            if collapse_merged && !b.is_active() {
+                continue;
+            }
 
-                buffers.push(buffer_data);
+            let merged = numbers.get(&b.number()).map_or(false, |&count| count > 1);
+            let mut buffer_data = BufferData::new(&b, merged);
+
+            if use_core_instead_weechat && buffer_data.short_name.as_str() == "weechat" {
+                buffer_data.short_name = Rc::new("core".to_string());
             }
+
+            buffers.push(buffer_data);
         }
 
+        BufferList::disambiguate(&mut buffers);
+
         BufferList {
             config,
             buffers,
@@ -248,36 +459,79 @@ impl BufferList {
         }
     }
 
+    /// Set the qualifier of buffers that share their short name with other
+    /// buffers.
+    ///
+    /// The qualifier is the last part of the full name before the short name,
+    /// usually the server name, e.g. `libera` for `irc.libera.#general`. If
+    /// that isn't enough to tell the buffers apart the whole part before the
+    /// short name is used, e.g. `irc.libera`.
+    fn disambiguate(buffers: &mut [BufferData]) {
+        let mut by_short_name: HashMap<Rc<String>, Vec<usize>> = HashMap::new();
+
+        for (i, buffer) in buffers.iter().enumerate() {
+            by_short_name
+                .entry(buffer.short_name.clone())
+                .or_default()
+                .push(i);
+        }
+
+        for indices in by_short_name.values().filter(|i| i.len() > 1) {
+            let short_qualifiers: Vec<&str> = indices
+                .iter()
+                .map(|&i| {
+                    let prefix = buffers[i].full_name_prefix();
+                    prefix.rsplit('.').next().unwrap_or(prefix)
+                })
+                .collect();
+
+            let mut unique = short_qualifiers.clone();
+            unique.sort_unstable();
+            unique.dedup();
+
+            let qualifiers: Vec<String> = if unique.len() == short_qualifiers.len() {
+                short_qualifiers.iter().map(|q| q.to_string()).collect()
+            } else {
+                indices
+                    .iter()
+                    .map(|&i| buffers[i].full_name_prefix().to_string())
+                    .collect()
+            };
+
+            for (&i, qualifier) in indices.iter().zip(qualifiers) {
+                buffers[i].qualifier = Some(Rc::new(qualifier));
+            }
+        }
+    }
+
     /// Filter our list using a fuzzy matcher with the given pattern.
     ///
     /// Returns a new list of buffers that only contains buffers that match the
     /// given pattern, the score is adjusted to signal how well a buffer matches
     /// the pattern.
     fn filter(&self, pattern: &str) -> Self {
-        let matcher = SkimMatcherV2::default().smart_case();
+        let buffer_numbers = self.config.behaviour().buffer_numbers();
 
         let mut buffers: Vec<BufferData> = self
             .buffers
             .iter()
             .filter_map(|buffer_data| {
-                let buffer_name = if self.config.behaviour().buffer_numbers() {
-                    format!("{}{}", buffer_data.number, buffer_data.short_name)
+                let buffer_name = if buffer_numbers {
+                    Cow::from(format!("{}{}", buffer_data.number, buffer_data.short_name))
                 } else {
-                    buffer_data.short_name.to_string()
+                    Cow::from(buffer_data.short_name.as_str())
                 };
 
-                matcher
-                    .fuzzy_indices(&buffer_name, &pattern)
-                    .map(|(score, indices)| {
-                        let mut new_buffer = buffer_data.clone();
-                        new_buffer.score = score;
-                        new_buffer.indices = indices;
-                        new_buffer
-                    })
+                fuzzy_indices(pattern, &buffer_name).map(|(score, indices)| {
+                    let mut new_buffer = buffer_data.clone();
+                    new_buffer.score = score;
+                    new_buffer.indices = indices;
+                    new_buffer
+                })
             })
             .collect();
 
-        buffers.sort_by_key(|b| Reverse(b.score));
+        BufferList::sort(&mut buffers);
 
         BufferList {
             config: self.config.clone(),
@@ -286,6 +540,14 @@ impl BufferList {
         }
     }
 
+    /// Sort the buffers by their score, the best match first.
+    ///
+    /// Buffers with the same score keep the order of their numbers, merged
+    /// buffers share a number and are ordered by their full name.
+    fn sort(buffers: &mut [BufferData]) {
+        buffers.sort_by_key(|b| (Reverse(b.score), b.number, b.full_name.clone()));
+    }
+
     /// Set the next buffer as our selected buffer.
     ///
     /// This will wrap if we reach the end of our buffer list, e.g. if we're at
@@ -316,6 +578,24 @@ impl BufferList {
         }
     }
 
+    /// Set the buffer with the given index as our selected buffer.
+    ///
+    /// Returns false and leaves the selection untouched if the index is out of
+    /// bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index, starting at 0, of the buffer that should be
+    ///     selected.
+    fn select_index(&mut self, index: usize) -> bool {
+        if index < self.buffers.len() {
+            self.selected_buffer = index;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Get our selected buffer if there is one.
     fn get_selected_buffer(&self) -> Option<&BufferData> {
         self.buffers.get(self.selected_buffer)
@@ -411,7 +691,23 @@ impl std::fmt::Display for BufferList {
                     Weechat::color("reset")
                 );
 
-                format!("{}{}", buffer_number, buffer_name)
+                // Mark merged buffers, they share their number with other
+                // buffers.
+                let merged = if buffer_data.merged {
+                    format!("{}+{}", number_color, Weechat::color("reset"))
+                } else {
+                    "".to_string()
+                };
+
+                // Show where the buffer belongs to if other buffers share its
+                // name.
+                let qualifier = if let Some(label) = buffer_data.qualifier_label() {
+                    format!("{}{}{}", name_color, label, Weechat::color("reset"))
+                } else {
+                    "".to_string()
+                };
+
+                format!("{}{}{}{}", buffer_number, buffer_name, qualifier, merged)
             })
             .collect();
 
@@ -467,6 +763,11 @@ struct RunningState {
     saved_input: InputState,
     /// Our stored input while in go-mode.
     last_input: String,
+    /// The input containing a digit that selected a result, the digit is
+    /// removed from the input on the next main loop iteration.
+    digit_input: Option<String>,
+    /// The position in the pattern history if the user is browsing it.
+    history_position: Option<usize>,
     /// The current list of buffers we are presenting, will initially contain
     /// all buffers but will get filtered down as we input patterns.
     buffers: BufferList,
@@ -477,6 +778,8 @@ impl RunningState {
         RunningState {
             hooks: Hooks::new(inner_go),
             last_input: "".to_owned(),
+            digit_input: None,
+            history_position: None,
             saved_input: InputState::from(buffer),
             buffers: BufferList::new(weechat, inner_go.config.clone()),
         }
@@ -531,9 +834,32 @@ impl ModifierCallback for InnerGo {
         // colors and trim out whitespace at the beginning.
         let current_input = Weechat::remove_color(string.trim_start());
 
-        // If our input changed generate a new buffer list, if the input isn't
-        // an empty string filter our buffers with the input.
-        if state_borrow.last_input != current_input {
+        // If a digit was typed select the matching result and remove the
+        // digit from the input again, the list of buffers stays as it is.
+        let typed_digit = if self.config.behaviour().digit_select()
+            && !self.config.behaviour().buffer_numbers()
+            && current_input.len() == state_borrow.last_input.len() + 1
+        {
+            current_input
+                .strip_prefix(state_borrow.last_input.as_str())
+                .and_then(|typed| typed.parse::<usize>().ok())
+        } else {
+            None
+        };
+
+        if state_borrow.digit_input.as_ref() == Some(&current_input) {
+            // The input got redrawn before the digit was removed, the digit
+            // was already handled and removing it again would remove a
+            // character the user typed.
+        } else if let Some(digit) = typed_digit {
+            let index = if digit == 0 { 9 } else { digit - 1 };
+            state_borrow.buffers.select_index(index);
+            state_borrow.digit_input = Some(current_input);
+
+            run_command(&buffer, "/wait 1ms /input delete_previous_char");
+        } else if state_borrow.last_input != current_input {
+            // If our input changed generate a new buffer list, if the input
+            // isn't an empty string filter our buffers with the input.
             let buffers = BufferList::new(weechat, self.config.clone());
 
             let buffers = match current_input.as_ref() {
@@ -543,17 +869,23 @@ impl ModifierCallback for InnerGo {
 
             state_borrow.last_input = current_input;
             state_borrow.buffers = buffers;
+        } else {
+            state_borrow.digit_input = None;
         };
 
         if state_borrow.buffers.has_only_one_result() && self.config.behaviour().autojump() {
-            buffer
-                .run_command("/wait 1ms /input return")
-                .expect("Can't run command");
+            run_command(&buffer, "/wait 1ms /input return");
             None
         } else {
+            let history_indicator = state_borrow
+                .history_position
+                .map(|p| format!("({}/{}) ", p + 1, self.history.borrow().len()))
+                .unwrap_or_default();
+
             Some(format!(
-                "{}{}  {}",
+                "{}{}{}  {}",
                 self.config.look().prompt(),
+                history_indicator,
                 string,
                 state_borrow.buffers
             ))
@@ -563,7 +895,7 @@ impl ModifierCallback for InnerGo {
 
 /// Callback for our `/input` command override.
 impl CommandRunCallback for InnerGo {
-    fn callback(&mut self, weechat: &Weechat, _: &Buffer, command: Cow<str>) -> ReturnCode {
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, command: Cow<str>) -> ReturnCode {
         if command.starts_with("/input search_text") || command.starts_with("/input jump") {
             return ReturnCode::OkEat;
         }
@@ -579,8 +911,18 @@ impl CommandRunCallback for InnerGo {
                 if let Some(state) = state.as_mut() {
                     state.buffers.select_next_buffer();
                 }
-                Weechat::hook_signal_send("input_text_changed", "");
+                Weechat::send_signal_deferred("input_text_changed", "").ok();
+
+                ReturnCode::OkEat
+            }
 
+            "/input history_previous" | "/input history_global_previous" => {
+                self.browse_history(buffer, true);
+                ReturnCode::OkEat
+            }
+
+            "/input history_next" | "/input history_global_next" => {
+                self.browse_history(buffer, false);
                 ReturnCode::OkEat
             }
 
@@ -589,7 +931,7 @@ impl CommandRunCallback for InnerGo {
                 if let Some(state) = state.as_mut() {
                     state.buffers.select_prev_buffer();
                 }
-                Weechat::hook_signal_send("input_text_changed", "");
+                Weechat::send_signal_deferred("input_text_changed", "").ok();
 
                 ReturnCode::OkEat
             }
@@ -615,6 +957,7 @@ impl CommandCallback for InnerGo {
                 BufferList::new(weechat, self.config.clone())
                     .filter(&pattern)
                     .switch_to_selected_buffer(weechat);
+                self.remember_pattern(&pattern);
             } else {
                 *self.running_state.borrow_mut() = Some(RunningState::new(self, weechat, buffer));
                 buffer.set_input("");
@@ -625,6 +968,18 @@ impl CommandCallback for InnerGo {
     }
 }
 
+/// Run a command, printing an error instead of aborting if it fails.
+fn run_command(buffer: &Buffer, command: &str) {
+    if let Err(e) = buffer.run_command(command) {
+        Weechat::print(&format!(
+            "{}Error running the command {}: {}",
+            Weechat::prefix(Prefix::Error),
+            command,
+            e
+        ));
+    }
+}
+
 impl Plugin for Go {
     fn init(_: &Weechat, _args: Args) -> Result<Self, ()> {
         let config = Config::new()?;
@@ -638,27 +993,53 @@ impl Plugin for Go {
             return Err(());
         }
 
+        let history = PatternHistory::load(
+            Weechat::home_dir().join("go_history"),
+            config.behaviour().history_size() as usize,
+        );
+
         let inner_go = InnerGo {
             running_state: Rc::new(RefCell::new(None)),
             config: Rc::new(config),
+            history: Rc::new(RefCell::new(history)),
         };
 
         let command_settings = CommandSettings::new("go")
             .description("Quickly jump to a buffer using fuzzy search.")
             .add_argument("[name]")
+            .add_completion("%(go_buffers)")
             .arguments_description(
                 "name: directly jump to a buffer by name (without this \
-                argument an interactive mode is entered).\n\n\
+                argument an interactive mode is entered), buffer names \
+                can be tab completed.\n\n\
 
                 You can bind this command to a key, for example:\n    \
                 /key bind meta-g /go\n\n\
 
                 You can use tab completion to select the next/previous buffer \
-                in the interactive go-mode.",
+                in the interactive go-mode. If go.behaviour.digit_select is \
+                enabled typing a digit selects the result with that number. \
+                The history keys browse previously used patterns.",
             );
+
+        let config = inner_go.config.clone();
+        let completion = CompletionHook::new(
+            "go_buffers",
+            "Names of the buffers go can jump to",
+            move |weechat: &Weechat, _: &Buffer, _: Cow<str>, completion: &Completion| {
+                for buffer in BufferList::new(weechat, config.clone()).buffers {
+                    completion.add(&buffer.short_name);
+                }
+                Ok(())
+            },
+        )?;
+
         let command = Command::new(command_settings, inner_go)?;
 
-        Ok(Go { command })
+        Ok(Go {
+            command,
+            completion,
+        })
     }
 }
 
@@ -670,3 +1051,100 @@ plugin!(
     version: "0.1.0",
     license: "GPL3"
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(number: i32, full_name: &str, short_name: &str) -> BufferData {
+        BufferData {
+            score: 0,
+            number,
+            indices: Vec::new(),
+            full_name: Rc::new(full_name.to_string()),
+            short_name: Rc::new(short_name.to_string()),
+            qualifier: None,
+            merged: false,
+        }
+    }
+
+    fn qualifiers(buffers: &[BufferData]) -> Vec<Option<&str>> {
+        buffers
+            .iter()
+            .map(|b| b.qualifier.as_ref().map(|q| q.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn colliding_names_are_qualified_by_server() {
+        let mut buffers = vec![
+            buffer(2, "irc.libera.#rust", "#rust"),
+            buffer(3, "irc.oftc.#rust", "#rust"),
+            buffer(4, "irc.rizon.#rust", "#rust"),
+            buffer(5, "irc.libera.#weechat", "#weechat"),
+        ];
+
+        BufferList::disambiguate(&mut buffers);
+
+        assert_eq!(
+            qualifiers(&buffers),
+            vec![Some("libera"), Some("oftc"), Some("rizon"), None]
+        );
+    }
+
+    #[test]
+    fn colliding_servers_use_the_whole_prefix() {
+        let mut buffers = vec![
+            buffer(2, "irc.libera.#rust", "#rust"),
+            buffer(3, "matrix.libera.#rust", "#rust"),
+            buffer(4, "irc.oftc.#rust", "#rust"),
+        ];
+
+        BufferList::disambiguate(&mut buffers);
+
+        assert_eq!(
+            qualifiers(&buffers),
+            vec![Some("irc.libera"), Some("matrix.libera"), Some("irc.oftc")]
+        );
+    }
+
+    #[test]
+    fn qualifier_label() {
+        let mut buffers = vec![
+            buffer(1, "core.weechat", "weechat"),
+            buffer(2, "irc.libera.#rust", "#rust"),
+            buffer(3, "irc.oftc.#rust", "#rust"),
+        ];
+
+        BufferList::disambiguate(&mut buffers);
+
+        assert_eq!(buffers[0].qualifier_label(), None);
+        assert_eq!(buffers[1].qualifier_label().as_deref(), Some("(libera)"));
+        assert_eq!(buffers[2].qualifier_label().as_deref(), Some("(oftc)"));
+    }
+
+    #[test]
+    fn sort_order_is_stable() {
+        let mut buffers = vec![
+            buffer(4, "irc.rizon.#rust", "#rust"),
+            buffer(3, "irc.oftc.#rust", "#rust"),
+            buffer(3, "irc.libera.#rust", "#rust"),
+            buffer(2, "irc.libera.#weechat", "#weechat"),
+        ];
+        buffers[3].score = 10;
+
+        BufferList::sort(&mut buffers);
+
+        let names: Vec<&str> = buffers.iter().map(|b| b.full_name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "irc.libera.#weechat",
+                "irc.libera.#rust",
+                "irc.oftc.#rust",
+                "irc.rizon.#rust",
+            ]
+        );
+    }
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{borrow::Cow, cell::RefCell, rc::Rc};
+use std::{borrow::Cow, cell::RefCell, rc::Rc, time::SystemTime};
 
 use chrono::{offset::Utc, DateTime};
 use itertools::sorted;
@@ -77,21 +77,25 @@ impl InnerInfolist {
         };
 
         buffer.clear();
-        buffer.print_date_tags(
-            0,
-            &["no_filter"],
-            &format!(
-                "Infolist {} with arguments '{}':",
-                infolist_name,
-                infolist_args.unwrap_or_default()
-            ),
-        );
+        buffer
+            .print_date_tags(
+                SystemTime::now(),
+                &["no_filter"],
+                &format!(
+                    "Infolist {} with arguments '{}':",
+                    infolist_name,
+                    infolist_args.unwrap_or_default()
+                ),
+            )
+            .unwrap();
 
         let mut infolist = infolist.peekable();
 
         if infolist.peek().is_none() {
             buffer.print("");
-            buffer.print_date_tags(0, &["no_filter"], "Empty infolist.");
+            buffer
+                .print_date_tags(SystemTime::now(), &["no_filter"], "Empty infolist.")
+                .unwrap();
         } else {
             for (count, item) in infolist.enumerate() {
                 buffer.print("");
@@ -145,19 +149,21 @@ impl InnerInfolist {
                         ),
                     };
 
-                    buffer.print_date_tags(
-                        0,
-                        &["no_filter"],
-                        &format!(
-                            "{}{:.<30} {}{}{} {}",
-                            prefix,
-                            name,
-                            Weechat::color("brown"),
-                            value_type,
-                            Weechat::color("chat"),
-                            value
-                        ),
-                    );
+                    buffer
+                        .print_date_tags(
+                            SystemTime::now(),
+                            &["no_filter"],
+                            &format!(
+                                "{}{:.<30} {}{}{} {}",
+                                prefix,
+                                name,
+                                Weechat::color("brown"),
+                                value_type,
+                                Weechat::color("chat"),
+                                value
+                            ),
+                        )
+                        .unwrap();
 
                     prefix = "".to_string();
                 }
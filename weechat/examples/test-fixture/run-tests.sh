@@ -0,0 +1,139 @@
+#!/bin/sh
+# Load the fixture plugin into a headless Weechat, trigger its callbacks and
+# check the recorded events using `/fixture expect`.
+#
+# Needs weechat-headless, the plugin is built against the plugin header of the
+# installed Weechat, see the weechat-sys crate.
+#
+# usage: run-tests.sh [path to weechat-headless]
+
+set -eu
+# The patterns must not be globbed.
+set -f
+
+WEECHAT=${1:-weechat-headless}
+FIXTURE_DIR=$(cd "$(dirname "$0")" && pwd)
+TARGET_DIR=${CARGO_TARGET_DIR:-$FIXTURE_DIR/../../../target}
+
+cargo build --manifest-path "$FIXTURE_DIR/Cargo.toml"
+
+HOME_DIR=$(mktemp -d)
+trap 'rm -rf "$HOME_DIR"' EXIT
+
+mkdir -p "$HOME_DIR/plugins"
+cp "$TARGET_DIR/debug/libtest_fixture.so" "$HOME_DIR/plugins/test_fixture.so"
+
+LOG="$HOME_DIR/fixture.log"
+
+# Every line is a command and a pattern that the command needs to record,
+# either of them may be empty. The expectations are checked after the main
+# loop ran a couple of times so the timer and FD hooks had a chance to fire.
+#
+# Without a terminal no key presses reach the focus hooks, moving the cursor
+# calls them though while cursor debugging is enabled.
+CHECKS='
+/fixture hello world|command args=hello\sworld
+/fixture_run test|command_run command=/fixture_run\stest
+/fixture modify abc|modifier_result string=ABC
+/fixture decode abc|decode_result string=abc
+/fixture fd data|fd data=data
+/set fixture.test.integer 5|option name=test.integer value=5
+/set weechat.look.day_change off|config_hook name=weechat.look.day_change value=off
+/fixture info abc|info_result found=true value=cba
+/fixture has_item fixture|has_item name=fixture found=true
+/fixture bar|bar name=fixture_tmp created=true removed=true deleted=true
+/fixture unhook|unhook hooked=true inner_called=false
+/secure set fixture_secret hunter2|
+/fixture secure fixture_secret hunter2|secure name=fixture_secret listed=true matches=true
+/fixture secure fixture_secret hunter3|secure name=fixture_secret listed=true matches=false
+/fixture secure fixture_missing hunter2|secure name=fixture_missing listed=false matches=false
+/fixture hotlist notify_none|hotlist tag=notify_none added=false
+/fixture hotlist notify_message|hotlist tag=notify_message added=true
+/fixture charset iso-8859-1|charset charset=iso-8859-1 decoded=café round_trip=true
+/fixture hdata|hdata name=buffer number=1
+/fixture title red Connected|title raw_matches=true plain=Connected
+/debug cursor|
+/cursor go chat|focus has_buffer=true
+/cursor stop|
+/debug cursor|
+/buffer test_fixture.fixture_test|
+/buffer set title first|
+/buffer set title second|property name=title buffer=test_fixture.fixture_test old=first new=second
+/buffer setvar fixture_var one|property name=fixture_var buffer=test_fixture.fixture_test new=one
+/buffer setvar fixture_var two|property name=fixture_var buffer=test_fixture.fixture_test old=one new=two
+/input insert hello|
+/input return|input buffer=test_fixture.fixture_test text=hello
+|line message=hello
+/buffer close test_fixture.fixture_test|close buffer=test_fixture.fixture_test
+|timer remaining=0
+'
+
+COMMANDS=""
+EXPECTED=0
+
+add_command() {
+    if [ -n "$COMMANDS" ]; then
+        COMMANDS="$COMMANDS;$1"
+    else
+        COMMANDS="$1"
+    fi
+}
+
+OLD_IFS=$IFS
+IFS='
+'
+for check in $CHECKS; do
+    command=${check%%|*}
+
+    if [ -n "$command" ]; then
+        add_command "$command"
+    fi
+done
+
+for check in $CHECKS; do
+    pattern=${check#*|}
+
+    if [ -n "$pattern" ]; then
+        add_command "/wait 1 /fixture expect $pattern"
+        EXPECTED=$((EXPECTED + 1))
+    fi
+done
+IFS=$OLD_IFS
+
+add_command "/wait 2 /fixture dump $LOG"
+add_command "/wait 3 /quit"
+
+# Weechat quits while the plugin still has its hooks, bar items and config
+# registered, the plugin has to unload them without crashing Weechat. Point
+# WEECHAT to a build with AddressSanitizer enabled to catch invalid frees.
+STATUS=0
+timeout 60 "$WEECHAT" --dir "$HOME_DIR" --run-command "$COMMANDS" > /dev/null ||
+    STATUS=$?
+
+if [ "$STATUS" -eq 124 ]; then
+    echo "Weechat didn't quit within 60 seconds" >&2
+    exit 1
+elif [ "$STATUS" -ne 0 ]; then
+    echo "Weechat exited with status $STATUS" >&2
+    exit 1
+fi
+
+if [ ! -f "$LOG" ]; then
+    echo "The fixture didn't write its event log" >&2
+    exit 1
+fi
+
+PASSED=$(grep -c '^assert result=ok ' "$LOG" || true)
+
+if grep '^assert result=' "$LOG" | grep -v '^assert result=ok '; then
+    echo "$PASSED of $EXPECTED expectations passed, the event log contains:" >&2
+    cat "$LOG" >&2
+    exit 1
+fi
+
+if [ "$PASSED" -ne "$EXPECTED" ]; then
+    echo "Only $PASSED of $EXPECTED expectations were checked" >&2
+    exit 1
+fi
+
+echo "All $EXPECTED expectations passed"
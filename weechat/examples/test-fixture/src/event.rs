@@ -0,0 +1,388 @@
+//! The line format of the fixture buffer.
+//!
+//! Every callback invocation is recorded as an [`Event`] and printed as a
+//! single line into the fixture buffer. A line consists of the kind of the
+//! event followed by a space separated list of `key=value` fields:
+//!
+//! ```text
+//! command args=hello\sworld
+//! option name=test.integer value=5
+//! ```
+//!
+//! Backslashes, spaces, tabs, newlines and equal signs inside of keys and
+//! values are escaped with a backslash so a line can always be split on
+//! spaces.
+
+use std::{fmt, str::FromStr};
+
+/// A single recorded callback invocation.
+///
+/// # Example
+///
+/// ```
+/// use test_fixture::event::Event;
+///
+/// let event = Event::new("input").field("text", "hello world");
+/// let line = event.to_string();
+///
+/// assert_eq!(line, "input text=hello\\sworld");
+/// assert_eq!(line.parse::<Event>(), Ok(event));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    kind: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Event {
+    /// Create a new event without any fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of the event, e.g. `command` or `timer`.
+    pub fn new(kind: &str) -> Self {
+        Event {
+            kind: kind.to_owned(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a field to the event.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the field.
+    ///
+    /// * `value` - The value of the field.
+    pub fn field(mut self, key: &str, value: impl ToString) -> Self {
+        self.fields.push((key.to_owned(), value.to_string()));
+        self
+    }
+
+    /// Get the kind of the event.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Get the value of a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the field.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Check if the event matches the given pattern.
+    ///
+    /// An event matches if it has the same kind as the pattern and every
+    /// field of the pattern has the same value in the event, fields that
+    /// aren't part of the pattern are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The event that should be matched against.
+    pub fn matches(&self, pattern: &Event) -> bool {
+        self.kind == pattern.kind
+            && pattern
+                .fields
+                .iter()
+                .all(|(key, value)| self.get(key) == Some(value.as_str()))
+    }
+}
+
+fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ' ' => escaped.push_str("\\s"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '=' => escaped.push_str("\\e"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(string: &str) -> Result<String, ()> {
+    let mut unescaped = String::with_capacity(string.len());
+    let mut chars = string.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let c = match chars.next().ok_or(())? {
+                '\\' => '\\',
+                's' => ' ',
+                't' => '\t',
+                'n' => '\n',
+                'e' => '=',
+                _ => return Err(()),
+            };
+            unescaped.push(c);
+        } else {
+            unescaped.push(c);
+        }
+    }
+
+    Ok(unescaped)
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&escape(&self.kind))?;
+
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", escape(key), escape(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Event {
+    type Err = ();
+
+    /// Parse a line of the fixture buffer.
+    ///
+    /// Fails if the line is empty, a field is missing its `=` or contains an
+    /// unknown escape sequence.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut parts = line.split(' ').filter(|p| !p.is_empty());
+
+        let kind = unescape(parts.next().ok_or(())?)?;
+        let fields = parts
+            .map(|field| {
+                let mut field = field.splitn(2, '=');
+                let key = unescape(field.next().ok_or(())?)?;
+                let value = unescape(field.next().ok_or(())?)?;
+                Ok((key, value))
+            })
+            .collect::<Result<_, ()>>()?;
+
+        Ok(Event { kind, fields })
+    }
+}
+
+/// The list of events that were recorded by the fixture.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventLog {
+    events: Vec<Event>,
+}
+
+impl EventLog {
+    /// Create a new empty event log.
+    pub fn new() -> Self {
+        EventLog::default()
+    }
+
+    /// Parse the contents of the fixture buffer, one event per line.
+    ///
+    /// Empty lines are skipped.
+    pub fn parse(lines: &str) -> Result<Self, ()> {
+        let events = lines
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(Event::from_str)
+            .collect::<Result<_, ()>>()?;
+
+        Ok(EventLog { events })
+    }
+
+    /// Add an event to the end of the log.
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// Remove all events from the log.
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Get the recorded events in the order they were recorded.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Find the first event that matches the given pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern the event should match, see
+    ///     `Event::matches()`.
+    pub fn find(&self, pattern: &Event) -> Option<&Event> {
+        self.events.iter().find(|e| e.matches(pattern))
+    }
+
+    /// Count the events that match the given pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern the events should match, see
+    ///     `Event::matches()`.
+    pub fn count(&self, pattern: &Event) -> usize {
+        self.events.iter().filter(|e| e.matches(pattern)).count()
+    }
+
+    /// Assert that an event matching the given pattern was recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the full log if no event matches the pattern.
+    pub fn assert_contains(&self, pattern: &Event) {
+        assert!(
+            self.find(pattern).is_some(),
+            "No event matching `{}` was recorded, the log contains:\n{}",
+            pattern,
+            self
+        );
+    }
+
+    /// Assert that events matching the given patterns were recorded in the
+    /// given order, other events may be recorded in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the full log if the events weren't recorded in order.
+    pub fn assert_sequence(&self, patterns: &[Event]) {
+        let mut events = self.events.iter();
+
+        for pattern in patterns {
+            assert!(
+                events.any(|e| e.matches(pattern)),
+                "No event matching `{}` was recorded in order, the log \
+                 contains:\n{}",
+                pattern,
+                self
+            );
+        }
+    }
+}
+
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_characters_are_escaped() {
+        let event = Event::new("input").field("text", "a\\b c\td\ne=f");
+
+        assert_eq!(event.to_string(), "input text=a\\\\b\\sc\\td\\ne\\ef");
+    }
+
+    #[test]
+    fn keys_and_kinds_are_escaped() {
+        let event = Event::new("my event").field("a key=", "value");
+
+        assert_eq!(event.to_string(), "my\\sevent a\\skey\\e=value");
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let events = vec![
+            Event::new("timer"),
+            Event::new("command").field("args", ""),
+            Event::new("input")
+                .field("buffer", "test_fixture.fixture_test")
+                .field("text", "hello world\n\\= \t"),
+            Event::new("info_result")
+                .field("found", true)
+                .field("value", "cba"),
+        ];
+
+        for event in events {
+            assert_eq!(event.to_string().parse::<Event>(), Ok(event));
+        }
+    }
+
+    #[test]
+    fn invalid_lines_are_refused() {
+        assert_eq!("".parse::<Event>(), Err(()));
+        assert_eq!("   ".parse::<Event>(), Err(()));
+        assert_eq!("command args".parse::<Event>(), Err(()));
+        assert_eq!("command args=a\\x".parse::<Event>(), Err(()));
+        assert_eq!("command args=a\\".parse::<Event>(), Err(()));
+    }
+
+    #[test]
+    fn values_may_contain_unescaped_equal_signs() {
+        let event = "command args=a=b".parse::<Event>().unwrap();
+
+        assert_eq!(event.get("args"), Some("a=b"));
+    }
+
+    #[test]
+    fn patterns_match_a_subset_of_the_fields() {
+        let event = Event::new("option")
+            .field("name", "test.integer")
+            .field("value", 5);
+
+        assert!(event.matches(&Event::new("option")));
+        assert!(event.matches(&Event::new("option").field("value", 5)));
+        assert!(!event.matches(&Event::new("option").field("value", 6)));
+        assert!(!event.matches(&Event::new("option").field("missing", "")));
+        assert!(!event.matches(&Event::new("command")));
+    }
+
+    #[test]
+    fn logs_round_trip() {
+        let mut log = EventLog::new();
+        log.push(Event::new("command").field("args", "hello world"));
+        log.push(Event::new("timer").field("remaining", 0));
+
+        let parsed = EventLog::parse(&log.to_string()).unwrap();
+
+        assert_eq!(parsed, log);
+        assert_eq!(parsed.events().len(), 2);
+    }
+
+    #[test]
+    fn empty_lines_are_skipped_when_parsing_logs() {
+        let log = EventLog::parse("\ncommand args=a\n\n  \ntimer\n").unwrap();
+
+        assert_eq!(log.events().len(), 2);
+        assert!(EventLog::parse("command args=a\ninvalid line").is_err());
+    }
+
+    #[test]
+    fn logs_can_be_searched() {
+        let log = EventLog::parse("fd data=a\nfd data=b\nfd data=a\n").unwrap();
+
+        assert_eq!(log.count(&Event::new("fd")), 3);
+        assert_eq!(log.count(&Event::new("fd").field("data", "a")), 2);
+        assert!(log.find(&Event::new("timer")).is_none());
+
+        log.assert_contains(&Event::new("fd").field("data", "b"));
+        log.assert_sequence(&[
+            Event::new("fd").field("data", "b"),
+            Event::new("fd").field("data", "a"),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No event matching `fd data=b`")]
+    fn sequences_need_to_be_in_order() {
+        let log = EventLog::parse("fd data=b\nfd data=a\n").unwrap();
+
+        log.assert_sequence(&[
+            Event::new("fd").field("data", "a"),
+            Event::new("fd").field("data", "b"),
+        ]);
+    }
+}
@@ -0,0 +1,800 @@
+//! A Weechat plugin that registers one of every kind of hook and records
+//! every callback invocation.
+//!
+//! The recorded events are printed, one per line, into a dedicated `fixture`
+//! buffer using the format described in the [`event`] module. An integration
+//! harness can load the plugin into a headless Weechat, drive it with
+//! commands and either assert on the recorded events using `/fixture expect`
+//! or dump them into a file using `/fixture dump` and parse them with
+//! [`EventLog::parse`].
+//!
+//! The following callbacks are recorded:
+//!
+//! | Event          | Triggered by                                        |
+//! |----------------|-----------------------------------------------------|
+//! | `command`      | `/fixture [args]`                                   |
+//! | `command_run`  | `/fixture_run [args]`                               |
+//! | `modifier`     | `/fixture modify <string>`                          |
+//! | `timer`        | A single call shortly after the plugin was loaded   |
+//! | `fd`           | `/fixture fd <string>`                              |
+//! | `bar_item`     | Building the `fixture` bar item, it is displayed in |
+//! |                | the `fixture` bar                                   |
+//! | `option`       | Changing an option of the `fixture.test` section    |
+//! | `config_reload`| `/reload fixture`                                   |
+//! | `config_hook`  | Changing an option matching `weechat.look.*`        |
+//! | `focus`        | A mouse or cursor action in the chat area           |
+//! | `info`         | `/fixture info [args]` or `${info:fixture,args}`    |
+//! | `bar`          | `/fixture bar [name]`, creating and dropping a bar  |
+//! | `unhook`       | `/fixture unhook`, dropping a hook in a callback    |
+//! | `secure`       | `/fixture secure <name> <value>`, comparing a       |
+//! |                | secured data entry without recording its value      |
+//! | `hotlist`      | `/fixture hotlist <tag>`, printing a line with the  |
+//! |                | tag and checking if it triggered the hotlist        |
+//! | `charset`      | `/fixture charset <charset>`, decoding and encoding |
+//! |                | `caf\xe9` with the charset set on a buffer          |
+//! | `hdata`        | `/fixture hdata`, reading the number of the core    |
+//! |                | buffer from the `buffer` hdata                      |
+//! | `title`        | `/fixture title <color> <text>`, setting a colored  |
+//! |                | title and reading the raw title back                |
+//! | `input`        | Input in the `fixture_test` buffer                  |
+//! | `line`         | A line added to the `fixture_test` buffer, its      |
+//! |                | prefix is replaced with `fixture`                   |
+//! | `close`        | Closing the `fixture_test` buffer                   |
+//! | `property`     | Changing the title or the `fixture_var` local       |
+//! |                | variable of a buffer                                |
+
+pub mod event;
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    fs,
+    io::{Read, Write},
+    os::unix::{
+        io::{AsRawFd, RawFd},
+        net::UnixStream,
+    },
+    rc::Rc,
+    time::Duration,
+};
+
+use weechat::{
+    buffer::{Buffer, BufferBuilder, BufferHandle},
+    config::{
+        BooleanOptionSettings, ColorOptionSettings, Conf, Config, ConfigSectionSettings,
+        IntegerOptionSettings, StringOptionSettings,
+    },
+    hooks::{
+        Bar, BarItem, BarPosition, BarSettings, BarType, BufferProperty, BufferPropertyHook,
+        Command, CommandRun, CommandSettings, ConfigHook, FdHook, FdHookMode, FocusHook, InfoHook,
+        LineHook, ModifierData, ModifierHook, RemainingCalls, SignalData, SignalHook, TimerHook,
+    },
+    infolist::InfolistVariable,
+    plugin, Args, Hashtable, Plugin, ReturnCode, Weechat,
+};
+
+use crate::event::{Event, EventLog};
+
+/// Records events and mirrors them into the fixture buffer.
+#[derive(Clone)]
+struct Recorder {
+    log: Rc<RefCell<EventLog>>,
+    buffer: BufferHandle,
+}
+
+impl Recorder {
+    fn record(&self, event: Event) {
+        if let Ok(buffer) = self.buffer.upgrade() {
+            buffer.print(&event.to_string());
+        }
+
+        self.log.borrow_mut().push(event);
+    }
+}
+
+#[allow(unused)]
+struct TestFixture {
+    command: Command,
+    command_run: CommandRun,
+    modifier: ModifierHook,
+    timer: TimerHook,
+    fd: FdHook<RawFd>,
+    bar_item: BarItem,
+    bar: Bar,
+    config: Config,
+    config_hook: ConfigHook,
+    line: LineHook,
+    focus: FocusHook,
+    info: InfoHook,
+    title_watch: BufferPropertyHook,
+    localvar_watch: BufferPropertyHook,
+    buffer: BufferHandle,
+}
+
+impl TestFixture {
+    fn bar_exists(weechat: &Weechat, name: &str) -> bool {
+        weechat
+            .get_infolist("bar", Some(name))
+            .map(|infolist| {
+                infolist
+                    .into_iter()
+                    .any(|item| item.get_string("name").as_deref() == Some(name))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Create a bar and check that it's removed when the handle is dropped,
+    /// both if the bar still exists and if the user deleted it beforehand.
+    fn check_bar(weechat: &Weechat, recorder: &Recorder, name: &str) {
+        let settings = BarSettings::new(name).add_item("fixture");
+
+        let created = match Bar::new(settings.clone()) {
+            Ok(bar) => {
+                let created = TestFixture::bar_exists(weechat, name);
+                drop(bar);
+                created
+            }
+            Err(_) => false,
+        };
+        let removed = !TestFixture::bar_exists(weechat, name);
+
+        let deleted = match Bar::new(settings) {
+            Ok(bar) => {
+                let _ = weechat
+                    .current_buffer()
+                    .run_command(&format!("/bar del {}", name));
+                let failed = bar.show().is_err();
+                drop(bar);
+                failed
+            }
+            Err(_) => false,
+        };
+
+        recorder.record(
+            Event::new("bar")
+                .field("name", name)
+                .field("created", created)
+                .field("removed", removed)
+                .field("deleted", deleted),
+        );
+    }
+
+    /// Drop a hook from inside the callback of another hook and check that
+    /// the dropped hook isn't called anymore.
+    fn check_unhook(recorder: &Recorder) {
+        let inner_called = Rc::new(Cell::new(false));
+
+        let called = inner_called.clone();
+        let inner = SignalHook::new(
+            "fixture_unhook_inner",
+            move |_: &Weechat, _: &str, _: Option<SignalData>| {
+                called.set(true);
+                ReturnCode::Ok
+            },
+        )
+        .ok();
+
+        let inner = Rc::new(RefCell::new(inner));
+        let hooked = inner.borrow().is_some();
+
+        let outer = SignalHook::new(
+            "fixture_unhook_outer",
+            move |_: &Weechat, _: &str, _: Option<SignalData>| {
+                inner.borrow_mut().take();
+                Weechat::hook_signal_send("fixture_unhook_inner", "");
+                ReturnCode::Ok
+            },
+        );
+
+        Weechat::hook_signal_send("fixture_unhook_outer", "");
+        drop(outer);
+
+        recorder.record(
+            Event::new("unhook")
+                .field("hooked", hooked)
+                .field("inner_called", inner_called.get()),
+        );
+    }
+
+    fn in_hotlist(weechat: &Weechat, buffer: &Buffer) -> bool {
+        weechat
+            .get_infolist("hotlist", None)
+            .map(|infolist| {
+                infolist
+                    .into_iter()
+                    .any(|item| match item.get("buffer_pointer") {
+                        Some(InfolistVariable::Buffer(b)) => b.full_name() == buffer.full_name(),
+                        _ => false,
+                    })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Print a line with the tag into a new buffer and check if the buffer
+    /// was added to the hotlist.
+    fn check_hotlist(weechat: &Weechat, recorder: &Recorder, tag: &str) {
+        let added = match BufferBuilder::new("fixture_hotlist").build() {
+            Ok(handle) => match handle.upgrade() {
+                Ok(buffer) => {
+                    let added = buffer.print_tags(&[tag], "hotlist").is_ok()
+                        && TestFixture::in_hotlist(weechat, &buffer);
+                    buffer.close();
+                    added
+                }
+                Err(_) => false,
+            },
+            Err(_) => false,
+        };
+
+        recorder.record(
+            Event::new("hotlist")
+                .field("tag", tag)
+                .field("added", added),
+        );
+    }
+
+    /// Set the charset on the fixture buffer and convert `caf\xe9` with it
+    /// in both directions.
+    fn check_charset(recorder: &Recorder, charset: &str) {
+        let bytes = b"caf\xe9";
+
+        if let Ok(buffer) = recorder.buffer.upgrade() {
+            buffer.set_charset(Some(charset), Some(charset));
+            let decoded = buffer.decode(bytes);
+            let encoded = buffer.encode(&decoded);
+            buffer.set_charset(None, None);
+
+            recorder.record(
+                Event::new("charset")
+                    .field("charset", charset)
+                    .field("decoded", decoded)
+                    .field("round_trip", encoded == bytes),
+            );
+        }
+    }
+
+    /// Set a title containing color codes on the fixture buffer and check
+    /// that the title is read back unmodified.
+    fn check_title(recorder: &Recorder, color: &str, text: &str) {
+        if let Ok(buffer) = recorder.buffer.upgrade() {
+            let old_title = buffer.title().into_owned();
+            let title = format!(
+                "{}{}{}",
+                Weechat::color(color),
+                text,
+                Weechat::color("reset")
+            );
+
+            buffer.set_title(&title);
+            let raw = buffer.title().into_owned();
+            buffer.set_title(&old_title);
+
+            recorder.record(
+                Event::new("title")
+                    .field("raw_matches", raw == title)
+                    .field("plain", Weechat::remove_color(&raw)),
+            );
+        }
+    }
+
+    /// Read the number of the core buffer, the first buffer in the
+    /// `gui_buffers` list, using the `buffer` hdata.
+    fn check_hdata(weechat: &Weechat, recorder: &Recorder) {
+        let number = weechat.hdata("buffer").and_then(|hdata| {
+            let core = hdata.get_list("gui_buffers")?;
+            // The core buffer can't be closed while it's read.
+            unsafe { hdata.integer(&core, "number") }
+        });
+
+        recorder.record(
+            Event::new("hdata")
+                .field("name", "buffer")
+                .field("number", number.unwrap_or(-1)),
+        );
+    }
+
+    /// Record the old and the new value of the property whenever it changes
+    /// on any buffer.
+    fn watch_property(
+        recorder: &Recorder,
+        property: BufferProperty,
+        name: &str,
+    ) -> Result<BufferPropertyHook, ()> {
+        let r = recorder.clone();
+        let name = name.to_owned();
+
+        Weechat::watch_buffer_property(
+            property,
+            move |_: &Weechat, buffer: &Buffer, old: Option<&str>, new: Option<&str>| {
+                let mut event = Event::new("property")
+                    .field("name", &name)
+                    .field("buffer", buffer.full_name());
+
+                if let Some(old) = old {
+                    event = event.field("old", old);
+                }
+
+                if let Some(new) = new {
+                    event = event.field("new", new);
+                }
+
+                r.record(event);
+            },
+        )
+    }
+
+    fn fixture_command(
+        weechat: &Weechat,
+        recorder: &Recorder,
+        writer: &UnixStream,
+        mut arguments: Args,
+    ) {
+        arguments.next();
+        let arguments: Vec<String> = arguments.collect();
+
+        match arguments.first().map(|a| a.as_str()) {
+            Some("expect") => {
+                let pattern = arguments[1..].join(" ");
+
+                let event = match pattern.parse::<Event>() {
+                    Ok(pattern) => {
+                        let found = recorder.log.borrow().find(&pattern).is_some();
+                        Event::new("assert")
+                            .field("result", if found { "ok" } else { "failed" })
+                            .field("pattern", pattern)
+                    }
+                    Err(_) => Event::new("assert")
+                        .field("result", "invalid")
+                        .field("pattern", pattern),
+                };
+
+                recorder.record(event);
+            }
+            Some("dump") => {
+                let path = arguments[1..].join(" ");
+                let log = recorder.log.borrow().to_string();
+
+                let result = if fs::write(&path, log).is_ok() {
+                    "ok"
+                } else {
+                    "failed"
+                };
+
+                recorder.record(
+                    Event::new("dump")
+                        .field("path", path)
+                        .field("result", result),
+                );
+            }
+            Some("clear") => {
+                recorder.log.borrow_mut().clear();
+
+                if let Ok(buffer) = recorder.buffer.upgrade() {
+                    buffer.clear();
+                }
+            }
+            Some("modify") => {
+                let string = arguments[1..].join(" ");
+                let result = Weechat::exec_modifier("fixture", "data", &string);
+
+                recorder.record(Event::new("modifier_result").field("string", result));
+            }
+            Some("decode") => {
+                let string = arguments[1..].join(" ");
+                let result = Weechat::exec_modifier("irc_color_decode", "0", &string);
+
+                recorder.record(Event::new("decode_result").field("string", result));
+            }
+            Some("info") => {
+                let arguments = arguments[1..].join(" ");
+                let value = Weechat::info_get("fixture", &arguments);
+
+                recorder.record(
+                    Event::new("info_result")
+                        .field("found", value.is_some())
+                        .field("value", value.unwrap_or_default()),
+                );
+            }
+            Some("has_item") => {
+                let name = arguments.get(1).map(|a| a.as_str()).unwrap_or_default();
+                let found = weechat
+                    .get_infolist("bar_item", Some(name))
+                    .map(|infolist| {
+                        infolist
+                            .into_iter()
+                            .any(|item| item.get_string("name").as_deref() == Some(name))
+                    })
+                    .unwrap_or(false);
+
+                recorder.record(
+                    Event::new("has_item")
+                        .field("name", name)
+                        .field("found", found),
+                );
+            }
+            Some("bar") => {
+                let name = arguments
+                    .get(1)
+                    .map(|a| a.as_str())
+                    .unwrap_or("fixture_tmp");
+                TestFixture::check_bar(weechat, recorder, name);
+            }
+            Some("unhook") => TestFixture::check_unhook(recorder),
+            Some("secure") => {
+                let name = arguments.get(1).map(|a| a.as_str()).unwrap_or_default();
+                let value = arguments.get(2..).unwrap_or_default().join(" ");
+
+                // Only compare the secret, it ends up in the event log.
+                recorder.record(
+                    Event::new("secure")
+                        .field("name", name)
+                        .field(
+                            "listed",
+                            Weechat::secure_data_keys().iter().any(|k| k == name),
+                        )
+                        .field("matches", Weechat::secure_get(name) == Some(value)),
+                );
+            }
+            Some("hotlist") => {
+                let tag = arguments.get(1).map(|a| a.as_str()).unwrap_or_default();
+                TestFixture::check_hotlist(weechat, recorder, tag);
+            }
+            Some("charset") => {
+                let charset = arguments.get(1).map(|a| a.as_str()).unwrap_or_default();
+                TestFixture::check_charset(recorder, charset);
+            }
+            Some("hdata") => TestFixture::check_hdata(weechat, recorder),
+            Some("title") => {
+                let color = arguments.get(1).map(|a| a.as_str()).unwrap_or_default();
+                let text = arguments.get(2..).unwrap_or_default().join(" ");
+                TestFixture::check_title(recorder, color, &text);
+            }
+            Some("fd") => {
+                let string = arguments[1..].join(" ");
+                let mut writer = writer;
+                let _ = writer.write_all(string.as_bytes());
+            }
+            _ => recorder.record(Event::new("command").field("args", arguments.join(" "))),
+        }
+    }
+
+    fn create_config(recorder: &Recorder) -> Result<Config, ()> {
+        let reload_recorder = recorder.clone();
+
+        let mut config = Config::new_with_callback("fixture", move |_: &Weechat, _: &Conf| {
+            reload_recorder.record(Event::new("config_reload"));
+        })?;
+
+        {
+            let mut section = config.new_section(ConfigSectionSettings::new("test"))?;
+
+            let r = recorder.clone();
+            section.new_boolean_option(
+                BooleanOptionSettings::new("boolean")
+                    .description("A boolean option")
+                    .default_value(false)
+                    .set_change_callback(move |_, option| {
+                        r.record(
+                            Event::new("option")
+                                .field("name", "test.boolean")
+                                .field("value", option.value()),
+                        )
+                    }),
+            )?;
+
+            let r = recorder.clone();
+            section.new_integer_option(
+                IntegerOptionSettings::new("integer")
+                    .description("An integer option")
+                    .default_value(0)
+                    .min(0)
+                    .max(100)
+                    .set_change_callback(move |_, option| {
+                        r.record(
+                            Event::new("option")
+                                .field("name", "test.integer")
+                                .field("value", option.value()),
+                        )
+                    }),
+            )?;
+
+            let r = recorder.clone();
+            section.new_integer_option(
+                IntegerOptionSettings::new("enum")
+                    .description("An integer option with string values")
+                    .string_values(vec!["first", "second", "third"])
+                    .default_value(0)
+                    .set_change_callback(move |_, option| {
+                        r.record(
+                            Event::new("option")
+                                .field("name", "test.enum")
+                                .field("value", option.value()),
+                        )
+                    }),
+            )?;
+
+            let r = recorder.clone();
+            section.new_string_option(
+                StringOptionSettings::new("string")
+                    .description("A string option")
+                    .default_value("")
+                    .set_change_callback(move |_, option| {
+                        r.record(
+                            Event::new("option")
+                                .field("name", "test.string")
+                                .field("value", option.value()),
+                        )
+                    }),
+            )?;
+
+            let r = recorder.clone();
+            section.new_color_option(
+                ColorOptionSettings::new("color")
+                    .description("A color option")
+                    .default_value("default")
+                    .set_change_callback(move |_, option| {
+                        r.record(
+                            Event::new("option")
+                                .field("name", "test.color")
+                                .field("value", option.value()),
+                        )
+                    }),
+            )?;
+        }
+
+        Ok(config)
+    }
+}
+
+impl Plugin for TestFixture {
+    fn init(_: &Weechat, _args: Args) -> Result<Self, ()> {
+        let fixture_buffer = BufferBuilder::new("fixture").build().map_err(|_| ())?;
+
+        let recorder = Recorder {
+            log: Rc::new(RefCell::new(EventLog::new())),
+            buffer: fixture_buffer.clone(),
+        };
+
+        let (writer, mut reader) = UnixStream::pair().map_err(|_| ())?;
+        reader.set_nonblocking(true).map_err(|_| ())?;
+
+        let r = recorder.clone();
+        let fd = FdHook::new(
+            reader.as_raw_fd(),
+            FdHookMode::Read,
+            move |_: &Weechat, _: &mut RawFd| {
+                let mut data = Vec::new();
+                let _ = reader.read_to_end(&mut data);
+
+                r.record(Event::new("fd").field("data", String::from_utf8_lossy(&data)));
+            },
+        )?;
+
+        let r = recorder.clone();
+        let command = Command::new(
+            CommandSettings::new("fixture")
+                .description("Record callbacks of the test fixture.")
+                .add_argument("expect <kind> [<key>=<value>...]")
+                .add_argument("|| dump <path>")
+                .add_argument("|| clear")
+                .add_argument("|| modify <string>")
+                .add_argument("|| decode <string>")
+                .add_argument("|| info [<args>]")
+                .add_argument("|| has_item <name>")
+                .add_argument("|| bar [<name>]")
+                .add_argument("|| unhook")
+                .add_argument("|| secure <name> <value>")
+                .add_argument("|| hotlist <tag>")
+                .add_argument("|| charset <charset>")
+                .add_argument("|| hdata")
+                .add_argument("|| title <color> <text>")
+                .add_argument("|| fd <string>")
+                .add_argument("|| <args>")
+                .arguments_description(
+                    "  expect: check that a matching event was recorded\n    \
+                     dump: write all recorded events to a file\n   \
+                     clear: remove all recorded events\n  \
+                     modify: run the fixture modifier on the string\n  \
+                     decode: remove IRC colors from the string\n    \
+                     info: query the fixture info with the arguments\n\
+                     has_item: check if a bar item with the name exists\n     \
+                     bar: check that a bar is removed when it's dropped\n  \
+                     unhook: check that a hook dropped in a callback is unhooked\n  \
+                     secure: check that a secured data entry has the value\n \
+                     hotlist: check if a line with the tag triggers the hotlist\n \
+                     charset: convert a string using the charset of a buffer\n   \
+                     hdata: read the number of the core buffer using hdata\n   \
+                     title: set a colored title and read it back\n      \
+                     fd: write the string to the hooked socket\n    \
+                     args: record the arguments as a command event",
+                )
+                .add_completion(
+                    "expect|dump|clear|modify|decode|info|has_item|bar|unhook|secure|hotlist|charset|hdata|title|fd",
+                ),
+            move |weechat: &Weechat, _: &Buffer, arguments: Args| {
+                TestFixture::fixture_command(weechat, &r, &writer, arguments)
+            },
+        )?;
+
+        let r = recorder.clone();
+        let command_run = CommandRun::new(
+            "/fixture_run",
+            move |_: &Weechat, buffer: &Buffer, command: Cow<str>| {
+                r.record(
+                    Event::new("command_run")
+                        .field("buffer", buffer.full_name())
+                        .field("command", command),
+                );
+                ReturnCode::OkEat
+            },
+        )?;
+
+        let r = recorder.clone();
+        let modifier = ModifierHook::new(
+            "fixture",
+            move |_: &Weechat, _: &str, data: Option<ModifierData>, string: Cow<str>| {
+                let data = match data {
+                    Some(ModifierData::String(data)) => data.into_owned(),
+                    Some(ModifierData::Buffer(buffer)) => buffer.full_name().into_owned(),
+                    None => String::new(),
+                };
+
+                r.record(
+                    Event::new("modifier")
+                        .field("data", data)
+                        .field("string", &string),
+                );
+
+                Some(string.to_uppercase())
+            },
+        )?;
+
+        let r = recorder.clone();
+        let timer = TimerHook::new(
+            Duration::from_millis(1),
+            0,
+            1,
+            move |_: &Weechat, remaining: RemainingCalls| {
+                let remaining = match remaining {
+                    RemainingCalls::Infinite => -1,
+                    RemainingCalls::Finite(r) => r,
+                };
+                r.record(Event::new("timer").field("remaining", remaining));
+            },
+        )?;
+
+        let r = recorder.clone();
+        let bar_item = BarItem::new("fixture", move |_: &Weechat, buffer: &Buffer| {
+            r.record(Event::new("bar_item").field("buffer", buffer.full_name()));
+            "fixture".to_owned()
+        })
+        .map_err(|_| ())?;
+
+        // The bar is dropped after the bar item since struct fields are
+        // dropped in declaration order.
+        let bar = Bar::new(
+            BarSettings::new("fixture")
+                .bar_type(BarType::Root)
+                .position(BarPosition::Bottom)
+                .size(1)
+                .add_item("fixture"),
+        )?;
+
+        let config = TestFixture::create_config(&recorder)?;
+
+        if config.read().is_err() {
+            recorder.record(Event::new("config_read").field("result", "failed"));
+        }
+
+        let r = recorder.clone();
+        let config_hook = ConfigHook::new(
+            "weechat.look.*",
+            move |_: &Weechat, option_name: &str, value: Option<&str>| {
+                let mut event = Event::new("config_hook").field("name", option_name);
+
+                if let Some(value) = value {
+                    event = event.field("value", value);
+                }
+
+                r.record(event);
+            },
+        )?;
+
+        let input_recorder = recorder.clone();
+        let close_recorder = recorder.clone();
+
+        let buffer = BufferBuilder::new("fixture_test")
+            .input_callback(move |_: &Weechat, buffer: &Buffer, input: Cow<str>| {
+                input_recorder.record(
+                    Event::new("input")
+                        .field("buffer", buffer.full_name())
+                        .field("text", &input),
+                );
+                buffer.print(&input);
+                Ok(())
+            })
+            .close_callback(move |_: &Weechat, buffer: &Buffer| {
+                close_recorder.record(Event::new("close").field("buffer", buffer.full_name()));
+                Ok(())
+            })
+            .build()
+            .map_err(|_| ())?;
+
+        let r = recorder.clone();
+        let line = LineHook::new(
+            None,
+            Some("test_fixture.fixture_test"),
+            &[],
+            move |_: &Weechat, line: &Hashtable| {
+                r.record(
+                    Event::new("line")
+                        .field("prefix", line.get("prefix").unwrap_or_default())
+                        .field("message", line.get("message").unwrap_or_default()),
+                );
+
+                let mut update = Hashtable::new();
+                update.set("prefix", "fixture").ok()?;
+                Some(update)
+            },
+        )?;
+
+        let r = recorder.clone();
+        let focus = FocusHook::new("chat", move |_: &Weechat, info: &mut Hashtable| {
+            r.record(
+                Event::new("focus")
+                    .field("buffer", info.get("_buffer_full_name").unwrap_or_default())
+                    .field("has_buffer", info.contains_key("_buffer")),
+            );
+        })?;
+
+        // The info reverses its arguments, it has no value without arguments.
+        let r = recorder.clone();
+        let info = InfoHook::new(
+            "fixture",
+            "Arguments of the query reversed",
+            move |_: &Weechat, arguments: Cow<str>| {
+                r.record(Event::new("info").field("args", &arguments));
+
+                if arguments.is_empty() {
+                    None
+                } else {
+                    Some(arguments.chars().rev().collect())
+                }
+            },
+        )?;
+
+        let title_watch = TestFixture::watch_property(&recorder, BufferProperty::Title, "title")?;
+        let localvar_watch = TestFixture::watch_property(
+            &recorder,
+            BufferProperty::LocalVariable("fixture_var".to_owned()),
+            "fixture_var",
+        )?;
+
+        Ok(TestFixture {
+            command,
+            command_run,
+            modifier,
+            timer,
+            fd,
+            bar_item,
+            bar,
+            config,
+            config_hook,
+            line,
+            focus,
+            info,
+            title_watch,
+            localvar_watch,
+            buffer,
+        })
+    }
+}
+
+plugin!(
+    TestFixture,
+    name: "test_fixture",
+    author: "Damir Jelić <poljar@termina.org.uk>",
+    description: "Records callback invocations for integration tests",
+    version: "0.1.0",
+    license: "MIT"
+);
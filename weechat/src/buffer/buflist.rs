@@ -0,0 +1,138 @@
+use super::Buffer;
+use crate::Weechat;
+
+/// Helper to show plugin specific data in the buflist.
+///
+/// The buflist plugin displays buffers using the evaluated `buflist.format.*`
+/// options, which can reference the localvars of a buffer using
+/// `${buffer.local_variables.name}`. This helper stores values in localvars
+/// that are prefixed with a namespace, usually the name of the plugin, so
+/// they don't clash with the localvars of other plugins.
+///
+/// Besides the plugin specific localvars, the buflist formats and the sort
+/// order often use the conventional localvars listed as constants of this
+/// struct, `BufferKind` sets them when a buffer is created.
+///
+/// # Example
+///
+/// ```no_run
+/// # use weechat::buffer::{BufferBuilder, BuflistIntegration};
+/// # let buffer_handle = BufferBuilder::new("#rust").build().unwrap();
+/// # let buffer = buffer_handle.upgrade().unwrap();
+/// let buflist = BuflistIntegration::new("matrix");
+/// buflist.mark_buffer(&buffer, "mentions", "3");
+///
+/// // The user can now show the number of mentions using
+/// // /set buflist.format.name "${name} ${buffer.local_variables.matrix_mentions}"
+/// assert_eq!(
+///     buflist.expression("mentions"),
+///     "${buffer.local_variables.matrix_mentions}"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct BuflistIntegration {
+    namespace: String,
+}
+
+impl BuflistIntegration {
+    /// The type of the buffer, e.g. `server`, `channel` or `private`. The
+    /// default `buflist.format.indent` indents channel and private buffers.
+    pub const TYPE: &'static str = "type";
+
+    /// The name of the server the buffer belongs to.
+    pub const SERVER: &'static str = "server";
+
+    /// The name of the channel or the remote nick of a private buffer.
+    pub const CHANNEL: &'static str = "channel";
+
+    /// The nick of the user in the buffer.
+    pub const NICK: &'static str = "nick";
+
+    /// The names of the bar items the buflist plugin provides.
+    const BAR_ITEMS: [&'static str; 3] = ["buflist", "buflist2", "buflist3"];
+
+    /// Create a new buflist helper.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The prefix of the localvars, usually the name of the
+    ///     plugin.
+    pub fn new(namespace: &str) -> Self {
+        BuflistIntegration {
+            namespace: namespace.to_owned(),
+        }
+    }
+
+    /// Get the name of the localvar that stores the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The plugin specific key, e.g. `mentions`.
+    pub fn localvar_name(&self, key: &str) -> String {
+        format!("{}_{}", self.namespace, key)
+    }
+
+    /// Get the expression that can be used in a `buflist.format.*` option to
+    /// display the value of the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The plugin specific key, e.g. `mentions`.
+    pub fn expression(&self, key: &str) -> String {
+        format!("${{buffer.local_variables.{}}}", self.localvar_name(key))
+    }
+
+    /// Store a value for the buflist in the localvars of the buffer.
+    ///
+    /// The buflist is refreshed automatically when a localvar changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer that should be marked.
+    ///
+    /// * `key` - The plugin specific key, e.g. `mentions`.
+    ///
+    /// * `value` - The value that should be displayed.
+    pub fn mark_buffer(&self, buffer: &Buffer, key: &str, value: &str) {
+        buffer.set_localvar(&self.localvar_name(key), value);
+    }
+
+    /// Remove a value that was stored using `mark_buffer()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer that should be unmarked.
+    ///
+    /// * `key` - The plugin specific key, e.g. `mentions`.
+    pub fn unmark_buffer(&self, buffer: &Buffer, key: &str) {
+        buffer.del_localvar(&self.localvar_name(key));
+    }
+
+    /// Get a value that was stored using `mark_buffer()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer whose value should be fetched.
+    ///
+    /// * `key` - The plugin specific key, e.g. `mentions`.
+    pub fn value(&self, buffer: &Buffer, key: &str) -> Option<String> {
+        buffer
+            .get_localvar(&self.localvar_name(key))
+            .map(|v| v.into_owned())
+    }
+
+    /// Redraw the buflist.
+    ///
+    /// This is only needed if the formats depend on data that isn't stored
+    /// in the buffer, e.g. on a plugin specific info, changes of localvars
+    /// refresh the buflist automatically.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn refresh(&self) {
+        for item in BuflistIntegration::BAR_ITEMS.iter() {
+            Weechat::bar_item_update(item);
+        }
+    }
+}
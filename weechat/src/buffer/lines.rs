@@ -1,6 +1,10 @@
-use std::{borrow::Cow, collections::HashMap, ffi::c_void, marker::PhantomData};
+use std::{borrow::Cow, collections::HashMap, ffi::c_void, marker::PhantomData, time::SystemTime};
 
-use crate::{buffer::Buffer, Weechat};
+use crate::{
+    buffer::{join_tags, Buffer},
+    time::{FromWeechatTime, ToWeechatTime},
+    Weechat,
+};
 use weechat_sys::{t_hdata, t_weechat_plugin};
 
 /// An iterator that steps over the lines of the buffer.
@@ -82,8 +86,8 @@ impl<'a> DoubleEndedIterator for BufferLines<'a> {
 pub struct LineData<'a> {
     pub prefix: Option<&'a str>,
     pub message: Option<&'a str>,
-    pub date: Option<i64>,
-    pub date_printed: Option<i64>,
+    pub date: Option<SystemTime>,
+    pub date_printed: Option<SystemTime>,
     pub tags: Option<&'a [&'a str]>,
 }
 
@@ -116,6 +120,11 @@ impl<'a> BufferLine<'a> {
         }
     }
 
+    /// Get the prefix of the line with the color codes removed.
+    pub fn prefix_without_colors(&self) -> String {
+        Weechat::remove_color(&self.prefix())
+    }
+
     /// Set the prefix to the given new value.
     ///
     /// # Arguments
@@ -135,6 +144,14 @@ impl<'a> BufferLine<'a> {
         }
     }
 
+    /// Get the message of the line with the color codes removed.
+    ///
+    /// This is the text as the user sees it, which is usually what should be
+    /// searched or copied.
+    pub fn message_without_colors(&self) -> String {
+        Weechat::remove_color(&self.message())
+    }
+
     /// Set the message to the given new value.
     ///
     /// # Arguments
@@ -148,11 +165,13 @@ impl<'a> BufferLine<'a> {
     }
 
     /// Get the date of the line.
-    pub fn date(&self) -> i64 {
-        unsafe {
+    pub fn date(&self) -> SystemTime {
+        let date = unsafe {
             self.weechat
                 .hdata_time(self.hdata(), self.line_data_pointer, "date")
-        }
+        };
+
+        SystemTime::from_weechat_time(date)
     }
 
     /// Set the date to the given new value.
@@ -160,19 +179,21 @@ impl<'a> BufferLine<'a> {
     /// # Arguments
     ///
     /// * `new_value` - The new date that should be set on the line.
-    pub fn set_date(&self, new_value: i64) {
+    pub fn set_date(&self, new_value: impl ToWeechatTime) {
         let mut hashmap = HashMap::new();
-        let date = new_value.to_string();
+        let date = new_value.to_weechat_time().to_string();
         hashmap.insert("date", date.as_ref());
         self.update_line(hashmap);
     }
 
     /// Get the date the line was printed.
-    pub fn date_printed(&self) -> i64 {
-        unsafe {
+    pub fn date_printed(&self) -> SystemTime {
+        let date = unsafe {
             self.weechat
                 .hdata_time(self.hdata(), self.line_data_pointer, "date_printed")
-        }
+        };
+
+        SystemTime::from_weechat_time(date)
     }
 
     /// Set the date the line was printed to the given new value.
@@ -180,13 +201,24 @@ impl<'a> BufferLine<'a> {
     /// # Arguments
     ///
     /// * `new_value` - The new date that should be set on the line.
-    pub fn set_date_printed(&self, new_value: &str) {
+    pub fn set_date_printed(&self, new_value: impl ToWeechatTime) {
         let mut hashmap = HashMap::new();
-        let date = new_value.to_string();
+        let date = new_value.to_weechat_time().to_string();
         hashmap.insert("date_printed", date.as_ref());
         self.update_line(hashmap);
     }
 
+    /// Get the line number of the line in a buffer with free content.
+    ///
+    /// Lines of buffers with formatted content don't have a line number,
+    /// the returned value is meaningless for those.
+    pub fn y(&self) -> i32 {
+        unsafe {
+            self.weechat
+                .hdata_integer(self.hdata(), self.line_data_pointer, "y")
+        }
+    }
+
     /// Is the line highlighted.
     pub fn highlighted(&self) -> bool {
         unsafe {
@@ -196,6 +228,17 @@ impl<'a> BufferLine<'a> {
         }
     }
 
+    /// Is the line displayed.
+    ///
+    /// Lines that are hidden by a filter aren't displayed.
+    pub fn displayed(&self) -> bool {
+        unsafe {
+            self.weechat
+                .hdata_char(self.hdata(), self.line_data_pointer, "displayed")
+                != 0
+        }
+    }
+
     /// Get the list of tags of the line.
     pub fn tags(&self) -> Vec<Cow<str>> {
         unsafe {
@@ -225,11 +268,15 @@ impl<'a> BufferLine<'a> {
     /// # Arguments
     ///
     /// * `new_value` - The new tags that should be set on the line.
-    pub fn set_tags(&self, new_value: &[&str]) {
+    ///
+    /// Returns an error if one of the tags contains a comma, the tags of the
+    /// line aren't changed in that case.
+    pub fn set_tags(&self, new_value: &[&str]) -> Result<(), ()> {
         let mut hashmap = HashMap::new();
-        let tags = new_value.join(",");
+        let tags = join_tags(new_value)?;
         hashmap.insert("tags_array", tags.as_ref());
         self.update_line(hashmap);
+        Ok(())
     }
 
     /// Update multiple fields of the line at once.
@@ -238,6 +285,9 @@ impl<'a> BufferLine<'a> {
     /// * `data` - `LineData` that contains new values that should be set on the
     ///     line.
     ///
+    /// Returns an error if one of the tags contains a comma, the line isn't
+    /// updated in that case.
+    ///
     /// # Example
     /// ```no_run
     /// # use weechat::Weechat;
@@ -255,14 +305,14 @@ impl<'a> BufferLine<'a> {
     ///     .. Default::default()
     /// };
     ///
-    /// line.update(new_line)
+    /// line.update(new_line).unwrap();
     /// ```
-    pub fn update(&self, data: LineData<'a>) {
+    pub fn update(&self, data: LineData<'a>) -> Result<(), ()> {
         let mut hashmap = HashMap::new();
 
-        let tags = data.tags.map(|t| t.join(","));
-        let date = data.date.map(|d| d.to_string());
-        let date_printed = data.date_printed.map(|d| d.to_string());
+        let tags = data.tags.map(join_tags).transpose()?;
+        let date = data.date.map(|d| d.to_weechat_time().to_string());
+        let date_printed = data.date_printed.map(|d| d.to_weechat_time().to_string());
 
         if let Some(message) = data.message {
             hashmap.insert("message", message);
@@ -285,5 +335,6 @@ impl<'a> BufferLine<'a> {
         }
 
         self.update_line(hashmap);
+        Ok(())
     }
 }
@@ -1,5 +1,6 @@
 //! Weechat Buffer module containing Buffer and Nick types.
 
+mod buflist;
 mod lines;
 mod nick;
 mod nickgroup;
@@ -9,30 +10,454 @@ use std::{
     borrow::Cow,
     cmp::{Ord, Ordering},
     ffi::{c_void, CStr},
+    fmt,
     marker::PhantomData,
     ptr,
+    time::SystemTime,
 };
 
 use std::{cell::Cell, rc::Rc};
 
+#[cfg(feature = "async")]
+use async_task::FallibleTask;
 #[cfg(feature = "async")]
 use async_trait::async_trait;
 #[cfg(feature = "async")]
 use futures::future::LocalBoxFuture;
 
-use crate::{LossyCString, Weechat};
+use crate::{
+    config::ConfigOption,
+    time::{FromWeechatTime, ToWeechatTime},
+    util::{check_name, NameError, NameKind},
+    LossyCString, Weechat,
+};
 use libc::{c_char, c_int};
 use weechat_sys::{
-    t_gui_buffer, t_gui_nick, t_hdata, t_weechat_plugin, WEECHAT_RC_ERROR, WEECHAT_RC_OK,
+    t_gui_buffer, t_gui_nick, t_gui_nick_group, t_hdata, t_weechat_plugin, WEECHAT_RC_ERROR,
+    WEECHAT_RC_OK, WEECHAT_RC_OK_EAT,
 };
 
 pub use crate::buffer::{
+    buflist::BuflistIntegration,
     lines::{BufferLine, BufferLines, LineData},
-    nick::{Nick, NickSettings},
-    nickgroup::NickGroup,
+    nick::{Nick, NickSettings, Nicks},
+    nickgroup::{NickGroup, NickGroups},
     window::Window,
 };
 
+/// The priority of a buffer in the hotlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HotlistPriority {
+    /// Low priority, e.g. join and part messages.
+    Low,
+    /// Message priority, a message was sent to the buffer.
+    Message,
+    /// Private priority, a private message was received.
+    Private,
+    /// Highlight priority, the user was highlighted.
+    Highlight,
+}
+
+impl HotlistPriority {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HotlistPriority::Low => "0",
+            HotlistPriority::Message => "1",
+            HotlistPriority::Private => "2",
+            HotlistPriority::Highlight => "3",
+        }
+    }
+
+    fn from_raw(priority: i32) -> Option<Self> {
+        match priority {
+            0 => Some(HotlistPriority::Low),
+            1 => Some(HotlistPriority::Message),
+            2 => Some(HotlistPriority::Private),
+            3 => Some(HotlistPriority::Highlight),
+            _ => None,
+        }
+    }
+}
+
+/// The type of a buffer.
+///
+/// The type of a buffer decides how lines are added to it, it can be set
+/// when the buffer is created using `BufferBuilder::buffer_type()` or
+/// afterwards using `Buffer::set_buffer_type()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BufferType {
+    /// A buffer with formatted content, lines are appended to the end of the
+    /// buffer. This is the default type.
+    #[default]
+    Formatted,
+    /// A buffer with free content, lines are written to a specific line
+    /// number of the buffer using `Buffer::print_y()`.
+    Free,
+}
+
+impl BufferType {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            BufferType::Formatted => "formatted",
+            BufferType::Free => "free",
+        }
+    }
+}
+
+/// The notify level of a buffer.
+///
+/// The notify level decides which messages add the buffer to the hotlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum NotifyLevel {
+    /// The buffer is never added to the hotlist.
+    None,
+    /// The buffer is only added to the hotlist for highlights.
+    Highlight,
+    /// The buffer is added to the hotlist for highlights and messages.
+    Message,
+    /// The buffer is added to the hotlist for all messages, this is the
+    /// default.
+    #[default]
+    All,
+}
+
+impl NotifyLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            NotifyLevel::None => "0",
+            NotifyLevel::Highlight => "1",
+            NotifyLevel::Message => "2",
+            NotifyLevel::All => "3",
+        }
+    }
+}
+
+impl From<i32> for NotifyLevel {
+    fn from(level: i32) -> Self {
+        match level {
+            0 => NotifyLevel::None,
+            1 => NotifyLevel::Highlight,
+            2 => NotifyLevel::Message,
+            _ => NotifyLevel::All,
+        }
+    }
+}
+
+/// Error type for commands that Weechat failed to run, returned by
+/// `Buffer::run_command()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandError {
+    /// Weechat rejected the command, e.g. because the command doesn't exist
+    /// or its arguments were invalid. The return code of Weechat is
+    /// attached.
+    WeechatError(i32),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::WeechatError(code) => {
+                write!(
+                    f,
+                    "Weechat failed to run the command (return code {})",
+                    code
+                )
+            }
+        }
+    }
+}
+
+/// The kind of a buffer, used to set the conventional properties of a
+/// buffer.
+///
+/// Other plugins and scripts, e.g. buflist, smart filters or scripts that
+/// jump between buffers, use the local variables of a buffer to find out
+/// what it represents. Setting the kind of a buffer sets the same
+/// properties the IRC plugin sets for its buffers, so buffers of a Rust
+/// plugin are treated like native ones.
+///
+/// # Example
+///
+/// ```no_run
+/// # use weechat::buffer::{BufferBuilder, BufferKind};
+/// let buffer_handle = BufferBuilder::new("matrix.org.#rust")
+///     .kind(BufferKind::Channel {
+///         server: "matrix.org".to_owned(),
+///         channel: "#rust".to_owned(),
+///         nick: "alice".to_owned(),
+///     })
+///     .build()
+///     .expect("Can't create new buffer");
+///
+/// let buffer = buffer_handle.upgrade().unwrap();
+/// assert_eq!(buffer.get_localvar("type").as_deref(), Some("channel"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BufferKind {
+    /// The buffer of a server connection.
+    ///
+    /// Sets the local variables `type` to `server`, `server` and `channel`
+    /// to the name of the server.
+    Server {
+        /// The name of the server.
+        server: String,
+    },
+    /// The buffer of a channel.
+    ///
+    /// Sets the local variables `type` to `channel`, `server`, `channel` and
+    /// `nick`, enables the nicklist and adds the nick of the user to the
+    /// highlight words.
+    Channel {
+        /// The name of the server the channel belongs to.
+        server: String,
+        /// The name of the channel.
+        channel: String,
+        /// The nick of the user in the channel.
+        nick: String,
+    },
+    /// The buffer of a private conversation.
+    ///
+    /// Sets the local variables `type` to `private`, `server`, `channel` to
+    /// the nick of the other user and `nick`, and disables the nicklist.
+    Private {
+        /// The name of the server the conversation belongs to.
+        server: String,
+        /// The nick of the other user.
+        remote_nick: String,
+        /// The nick of the user.
+        nick: String,
+    },
+    /// A buffer containing debug output, e.g. raw protocol messages.
+    ///
+    /// Sets the local variables `type` to `debug` and `no_log` to `1`,
+    /// disables the nicklist, highlights and notifications.
+    Debug,
+    /// A buffer containing a list, e.g. a list of channels of a server.
+    ///
+    /// Sets the local variables `type` to `list` and `no_log` to `1`,
+    /// disables the nicklist, notifications and the time of the lines.
+    List,
+}
+
+/// Policy that decides how multi-line input is passed to the input callback
+/// of a buffer.
+///
+/// The buffer only receives multi-line input if multiline support is enabled
+/// for it, setting a policy with the buffer builder enables it.
+///
+/// Before the policy is applied to multi-line input, CRLF line endings are
+/// converted to LF and trailing newlines are removed, so pasting a block of
+/// text that ends with a newline doesn't produce a trailing empty line. Input
+/// that only consists of newlines is dropped.
+#[derive(Default)]
+pub enum MultilinePolicy {
+    /// Pass the input as a single string containing newlines to the input
+    /// callback. This is the default.
+    #[default]
+    SendAsOne,
+    /// Call the input callback once for every line of the input, empty lines
+    /// are skipped.
+    SplitLines,
+    /// Ask the callback if multi-line input should be sent.
+    ///
+    /// The callback is only called if the input contains more than one line.
+    /// If it returns true the input is passed as a single string to the input
+    /// callback, otherwise the input is discarded.
+    Confirm(Box<dyn BufferMultilineCallback>),
+}
+
+impl std::fmt::Debug for MultilinePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultilinePolicy::SendAsOne => f.write_str("SendAsOne"),
+            MultilinePolicy::SplitLines => f.write_str("SplitLines"),
+            MultilinePolicy::Confirm(_) => f.write_str("Confirm"),
+        }
+    }
+}
+
+impl MultilinePolicy {
+    /// Create a new `Confirm` policy from a callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback that decides if multi-line input should
+    ///     be sent.
+    pub fn confirm(callback: impl BufferMultilineCallback) -> Self {
+        MultilinePolicy::Confirm(Box::new(callback))
+    }
+}
+
+/// Trait for the callback of the `MultilinePolicy::Confirm` policy.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait BufferMultilineCallback: 'static {
+    /// Callback that will be called if the buffer receives input that
+    /// contains multiple lines.
+    ///
+    /// Returns true if the input should be passed to the input callback,
+    /// false if it should be discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `buffer` - The buffer that received the input.
+    ///
+    /// * `input` - The normalized input, lines are separated by LF.
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, input: &str) -> bool;
+}
+
+impl<T: FnMut(&Weechat, &Buffer, &str) -> bool + 'static> BufferMultilineCallback for T {
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, input: &str) -> bool {
+        self(weechat, buffer, input)
+    }
+}
+
+/// Trait for the callback that is called if the input of a buffer exceeds
+/// the maximum input length.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait BufferInputLengthCallback: 'static {
+    /// Callback that will be called instead of the input callback if the
+    /// input is longer than the configured maximum length.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `buffer` - The buffer that received the input.
+    ///
+    /// * `input` - The input that was rejected.
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, input: &str);
+}
+
+impl<T: FnMut(&Weechat, &Buffer, &str) + 'static> BufferInputLengthCallback for T {
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, input: &str) {
+        self(weechat, buffer, input)
+    }
+}
+
+pub(crate) struct InputLengthGuard {
+    max_length: usize,
+    callback: Box<dyn BufferInputLengthCallback>,
+}
+
+/// Join a list of line tags into the comma separated list Weechat expects.
+///
+/// Weechat can't escape commas in tags, an error is returned if one of the
+/// tags contains a comma.
+pub(crate) fn join_tags(tags: &[&str]) -> Result<String, ()> {
+    if tags.iter().any(|tag| tag.contains(',')) {
+        Err(())
+    } else {
+        Ok(tags.join(","))
+    }
+}
+
+/// Escape input so Weechat sends it as text instead of running it as a
+/// command.
+///
+/// Weechat runs lines that start with a `/` or one of the characters in the
+/// `weechat.look.command_chars` option as commands, doubling the first
+/// character tells Weechat to send the line as text without the first
+/// character.
+///
+/// # Arguments
+///
+/// * `text` - The text that should be escaped, may contain multiple lines.
+///
+/// * `command_chars` - The value of the `weechat.look.command_chars` option.
+fn escape_input(text: &str, command_chars: &str) -> String {
+    text.split('\n')
+        .map(|line| match line.chars().next() {
+            Some(c) if c == '/' || command_chars.contains(c) => format!("{}{}", c, line),
+            _ => line.to_owned(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Apply the multi-line policy and the length guard to some input.
+///
+/// Returns the messages that should be passed to the input callback.
+fn prepare_input(
+    weechat: &Weechat,
+    buffer: &Buffer,
+    policy: &mut MultilinePolicy,
+    length_guard: &mut Option<InputLengthGuard>,
+    input: &str,
+) -> Vec<String> {
+    split_input(
+        policy,
+        length_guard,
+        input,
+        |cb, input| cb.callback(weechat, buffer, input),
+        |cb, message| cb.callback(weechat, buffer, message),
+    )
+}
+
+/// Split input into messages using the multi-line policy and drop the
+/// messages that are too long.
+///
+/// # Arguments
+///
+/// * `confirm` - Runs the callback of a `MultilinePolicy::Confirm` policy
+///     with the normalized input.
+///
+/// * `reject` - Runs the callback of the length guard with a message that
+///     is too long.
+fn split_input(
+    policy: &mut MultilinePolicy,
+    length_guard: &mut Option<InputLengthGuard>,
+    input: &str,
+    mut confirm: impl FnMut(&mut dyn BufferMultilineCallback, &str) -> bool,
+    mut reject: impl FnMut(&mut dyn BufferInputLengthCallback, &str),
+) -> Vec<String> {
+    let messages = if !input.contains('\n') {
+        vec![input.to_owned()]
+    } else {
+        let input = input.replace("\r\n", "\n");
+        let input = input.trim_end_matches('\n');
+
+        match policy {
+            _ if input.is_empty() => Vec::new(),
+            _ if !input.contains('\n') => vec![input.to_owned()],
+            MultilinePolicy::SendAsOne => vec![input.to_owned()],
+            MultilinePolicy::SplitLines => input
+                .split('\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| line.to_owned())
+                .collect(),
+            MultilinePolicy::Confirm(cb) => {
+                if confirm(cb.as_mut(), input) {
+                    vec![input.to_owned()]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    };
+
+    if let Some(guard) = length_guard.as_mut() {
+        messages
+            .into_iter()
+            .filter(|message| {
+                if message.len() > guard.max_length {
+                    reject(guard.callback.as_mut(), message);
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect()
+    } else {
+        messages
+    }
+}
+
 /// A Weechat buffer.
 ///
 /// A buffer contains the data displayed on the screen.
@@ -149,6 +574,29 @@ impl BufferHandle {
             Ok(buffer)
         }
     }
+
+    /// Close the buffer this handle points to, consuming the handle.
+    ///
+    /// This is a nop if the buffer has already been closed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// let buffer_handle = BufferBuilder::new("test_buffer")
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    ///
+    /// let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.print("Hello world");
+    /// buffer.clear();
+    ///
+    /// buffer_handle.close();
+    /// ```
+    pub fn close(self) {
+        if let Ok(buffer) = self.upgrade() {
+            buffer.close();
+        }
+    }
 }
 
 #[cfg(feature = "async")]
@@ -157,6 +605,9 @@ pub(crate) struct BufferPointersAsync {
     pub(crate) input_cb: Option<Box<dyn BufferInputCallbackAsync>>,
     pub(crate) close_cb: Option<Box<dyn BufferCloseCallback>>,
     pub(crate) buffer_cell: Option<Rc<Cell<*mut t_gui_buffer>>>,
+    pub(crate) last_input: Option<FallibleTask<()>>,
+    pub(crate) multiline_policy: MultilinePolicy,
+    pub(crate) length_guard: Option<InputLengthGuard>,
 }
 
 pub(crate) struct BufferPointers {
@@ -164,6 +615,8 @@ pub(crate) struct BufferPointers {
     pub(crate) input_cb: Option<Box<dyn BufferInputCallback>>,
     pub(crate) close_cb: Option<Box<dyn BufferCloseCallback>>,
     pub(crate) buffer_cell: Option<Rc<Cell<*mut t_gui_buffer>>>,
+    pub(crate) multiline_policy: MultilinePolicy,
+    pub(crate) length_guard: Option<InputLengthGuard>,
 }
 
 /// Trait for the buffer input callback
@@ -260,15 +713,28 @@ impl<T: FnMut(BufferHandle, String) -> LocalBoxFuture<'static, ()> + 'static>
 /// Builder for the creation of a buffer.
 pub struct BufferBuilderAsync {
     pub(crate) name: String,
+    pub(crate) buffer_type: BufferType,
     pub(crate) input_callback: Option<Box<dyn BufferInputCallbackAsync>>,
     pub(crate) close_callback: Option<Box<dyn BufferCloseCallback>>,
+    pub(crate) multiline_policy: Option<MultilinePolicy>,
+    pub(crate) length_guard: Option<InputLengthGuard>,
+    pub(crate) kind: Option<BufferKind>,
 }
 
 /// Builder for the creation of a buffer.
+///
+/// Buffers created with the builder are owned by the plugin, the builder
+/// returns a `BufferHandle` that can be upgraded to a `Buffer` to access all
+/// the buffer methods. The buffer stays open until it's closed by the user or
+/// by calling `Buffer::close()`, the close callback is run in either case.
 pub struct BufferBuilder {
     pub(crate) name: String,
+    pub(crate) buffer_type: BufferType,
     pub(crate) input_callback: Option<Box<dyn BufferInputCallback>>,
     pub(crate) close_callback: Option<Box<dyn BufferCloseCallback>>,
+    pub(crate) multiline_policy: Option<MultilinePolicy>,
+    pub(crate) length_guard: Option<InputLengthGuard>,
+    pub(crate) kind: Option<BufferKind>,
 }
 
 #[cfg(feature = "async")]
@@ -279,9 +745,10 @@ impl BufferBuilderAsync {
     /// # Arguments
     ///
     /// * `name` - The name of the new buffer. Needs to be unique across a
-    /// plugin, otherwise the buffer creation will fail.
+    /// plugin and valid according to `Weechat::validate_name()`, otherwise
+    /// the buffer creation will fail with a `NameError`.
     ///
-    /// Returns a Buffer if one has been created, otherwise an empty Error.
+    /// Returns a Buffer if one has been created, otherwise a `NameError`.
     ///
     /// # Panics
     ///
@@ -317,8 +784,12 @@ impl BufferBuilderAsync {
     pub fn new(name: &str) -> Self {
         BufferBuilderAsync {
             name: name.to_owned(),
+            buffer_type: BufferType::default(),
             input_callback: None,
             close_callback: None,
+            multiline_policy: None,
+            length_guard: None,
+            kind: None,
         }
     }
 
@@ -328,11 +799,86 @@ impl BufferBuilderAsync {
     ///
     /// * `callback` - An async function that will be called once a user inputs
     ///     data into the buffer input line.
+    ///
+    /// The futures returned by the callback are run on the Weechat main
+    /// thread one after another, in the order the input was entered. A
+    /// future only starts running once the future of the previous input
+    /// finished, so messages the user typed quickly in succession are
+    /// processed in order even if the processing of the first one is still
+    /// awaiting.
+    ///
+    /// The callback receives a `BufferHandle` instead of a `Buffer` since the
+    /// buffer might be closed while the future is awaiting, the handle needs
+    /// to be upgraded after every await point.
     pub fn input_callback(mut self, callback: impl BufferInputCallbackAsync) -> Self {
         self.input_callback = Some(Box::new(callback));
         self
     }
 
+    /// Set the type of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_type` - The type of the buffer, defaults to a buffer with
+    ///     formatted content.
+    pub fn buffer_type(mut self, buffer_type: BufferType) -> Self {
+        self.buffer_type = buffer_type;
+        self
+    }
+
+    /// Set the kind of the buffer.
+    ///
+    /// This sets the conventional local variables and properties for the
+    /// kind of buffer, see `BufferKind` for the properties that are set.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of the buffer.
+    pub fn kind(mut self, kind: BufferKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Set the policy for multi-line input.
+    ///
+    /// Setting a policy enables multiline support for the buffer, see
+    /// `MultilinePolicy` for the available policies.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy that decides how multi-line input is passed
+    ///     to the input callback.
+    pub fn multiline_policy(mut self, policy: MultilinePolicy) -> Self {
+        self.multiline_policy = Some(policy);
+        self
+    }
+
+    /// Set a maximum length for the input of the buffer.
+    ///
+    /// Input that is longer than the limit isn't passed to the input
+    /// callback, the given callback is called instead, e.g. to tell the user
+    /// that the message exceeds the limit of the protocol instead of
+    /// silently sending a truncated message. If the input is split into
+    /// lines by the multi-line policy the limit applies to every line.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_length` - The maximum length of the input in bytes.
+    ///
+    /// * `callback` - The callback that is called with input that is too
+    ///     long.
+    pub fn max_input_length(
+        mut self,
+        max_length: usize,
+        callback: impl BufferInputLengthCallback,
+    ) -> Self {
+        self.length_guard = Some(InputLengthGuard {
+            max_length,
+            callback: Box::new(callback),
+        });
+        self
+    }
+
     /// Set the close callback.
     ///
     /// # Arguments
@@ -345,7 +891,7 @@ impl BufferBuilderAsync {
     }
 
     /// Build the configured buffer.
-    pub fn build(self) -> Result<BufferHandle, ()> {
+    pub fn build(self) -> Result<BufferHandle, NameError> {
         Weechat::buffer_new_with_async(self)
     }
 }
@@ -357,13 +903,14 @@ impl BufferBuilder {
     /// # Arguments
     ///
     /// * `name` - The name of the new buffer. Needs to be unique across a
-    /// plugin, otherwise the buffer creation will fail.
+    /// plugin and valid according to `Weechat::validate_name()`, otherwise
+    /// the buffer creation will fail with a `NameError`.
     ///
     /// # Panics
     ///
     /// Panics if the method is not called from the main Weechat thread.
     ///
-    /// Returns a Buffer if one has been created, otherwise an empty Error.
+    /// Returns a Buffer if one has been created, otherwise a `NameError`.
     ///
     /// # Example
     /// ```no_run
@@ -393,8 +940,12 @@ impl BufferBuilder {
     pub fn new(name: &str) -> Self {
         BufferBuilder {
             name: name.to_owned(),
+            buffer_type: BufferType::default(),
             input_callback: None,
             close_callback: None,
+            multiline_policy: None,
+            length_guard: None,
+            kind: None,
         }
     }
 
@@ -403,24 +954,124 @@ impl BufferBuilder {
     /// # Arguments
     ///
     /// * `callback` - A function or a struct that implements the
-    /// BufferCloseCallback trait.
+    /// BufferInputCallback trait.
     pub fn input_callback(mut self, callback: impl BufferInputCallback + 'static) -> Self {
         self.input_callback = Some(Box::new(callback));
         self
     }
 
+    /// Set the type of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_type` - The type of the buffer, defaults to a buffer with
+    ///     formatted content.
+    pub fn buffer_type(mut self, buffer_type: BufferType) -> Self {
+        self.buffer_type = buffer_type;
+        self
+    }
+
+    /// Set the kind of the buffer.
+    ///
+    /// This sets the conventional local variables and properties for the
+    /// kind of buffer, see `BufferKind` for the properties that are set.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of the buffer.
+    pub fn kind(mut self, kind: BufferKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Set the policy for multi-line input.
+    ///
+    /// Setting a policy enables multiline support for the buffer, see
+    /// `MultilinePolicy` for the available policies.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy that decides how multi-line input is passed
+    ///     to the input callback.
+    pub fn multiline_policy(mut self, policy: MultilinePolicy) -> Self {
+        self.multiline_policy = Some(policy);
+        self
+    }
+
+    /// Set a maximum length for the input of the buffer.
+    ///
+    /// Input that is longer than the limit isn't passed to the input
+    /// callback, the given callback is called instead, e.g. to tell the user
+    /// that the message exceeds the limit of the protocol instead of
+    /// silently sending a truncated message. If the input is split into
+    /// lines by the multi-line policy the limit applies to every line.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_length` - The maximum length of the input in bytes.
+    ///
+    /// * `callback` - The callback that is called with input that is too
+    ///     long.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::borrow::Cow;
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::{Buffer, BufferBuilder, MultilinePolicy};
+    /// let buffer_handle = BufferBuilder::new("irc_like")
+    ///     .input_callback(|_: &Weechat, buffer: &Buffer, input: Cow<str>| {
+    ///         buffer.print(&input);
+    ///         Ok(())
+    ///     })
+    ///     .multiline_policy(MultilinePolicy::SplitLines)
+    ///     .max_input_length(400, |_: &Weechat, buffer: &Buffer, input: &str| {
+    ///         buffer.print(&format!(
+    ///             "Message is too long ({} bytes), it wasn't sent",
+    ///             input.len()
+    ///         ));
+    ///     })
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    /// ```
+    pub fn max_input_length(
+        mut self,
+        max_length: usize,
+        callback: impl BufferInputLengthCallback,
+    ) -> Self {
+        self.length_guard = Some(InputLengthGuard {
+            max_length,
+            callback: Box::new(callback),
+        });
+        self
+    }
+
     /// Set the close callback.
     ///
     /// # Arguments
     ///
     /// * `callback` - The callback that should be called before a buffer is
+    ///     closed.
     pub fn close_callback(mut self, callback: impl BufferCloseCallback + 'static) -> Self {
         self.close_callback = Some(Box::new(callback));
         self
     }
 
     /// Build the configured buffer.
-    pub fn build(self) -> Result<BufferHandle, ()> {
+    ///
+    /// Returns an error if the buffer couldn't be created, e.g. if a buffer
+    /// with the same name already exists for this plugin.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// let buffer_handle = BufferBuilder::new("test_buffer")
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    ///
+    /// // Buffer names are unique per plugin.
+    /// assert!(BufferBuilder::new("test_buffer").build().is_err());
+    /// ```
+    pub fn build(self) -> Result<BufferHandle, NameError> {
         Weechat::buffer_new(self)
     }
 }
@@ -454,32 +1105,154 @@ impl Weechat {
         }
     }
 
-    pub(crate) fn buffer_from_ptr(&self, buffer_ptr: *mut t_gui_buffer) -> Buffer {
-        Buffer {
-            inner: InnerBuffers::BorrowedBuffer(InnerBuffer {
-                weechat: self,
-                ptr: buffer_ptr,
-                closing: Rc::new(Cell::new(false)),
-            }),
-        }
-    }
+    /// Get an iterator over all the buffers that are currently open.
+    ///
+    /// The buffers are returned in the order of their numbers, merged buffers
+    /// share a number and are returned one after another.
+    ///
+    /// The list of buffers is fetched when this method is called, buffers
+    /// that are opened while iterating aren't returned. Buffers that are
+    /// closed while iterating are skipped, every buffer is checked to still
+    /// exist before it's returned. A buffer that was already returned
+    /// shouldn't be used after it was closed.
+    ///
+    /// Since the list is fetched up front the iterator can be reversed, e.g.
+    /// to start with the buffer with the highest number. Its length is the
+    /// number of remaining buffers that are still open.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// for buffer in weechat.buffers() {
+    ///     Weechat::print(&format!("{} {}", buffer.number(), buffer.full_name()));
+    /// }
+    ///
+    /// Weechat::print(&format!("{} buffers are open", weechat.buffers().len()));
+    ///
+    /// if let Some(last) = weechat.buffers().next_back() {
+    ///     last.switch_to();
+    /// }
+    /// ```
+    pub fn buffers(&self) -> impl DoubleEndedIterator<Item = Buffer<'_>> + ExactSizeIterator {
+        let mut pointers = Vec::new();
 
-    /// Get the currently open buffer
-    pub fn current_buffer(&self) -> Buffer {
-        let buffer_search = self.get().buffer_search.unwrap();
+        let hdata = unsafe {
+            let hdata = self.hdata_get("buffer");
+            let mut buffer = self.hdata_get_list(hdata, "gui_buffers");
 
-        let buf_ptr = unsafe { buffer_search(ptr::null(), ptr::null()) };
-        if buf_ptr.is_null() {
-            panic!("No open buffer found");
-        } else {
-            self.buffer_from_ptr(buf_ptr)
+            while !buffer.is_null() {
+                pointers.push(buffer as *mut t_gui_buffer);
+                buffer = self.hdata_move(hdata, buffer, 1);
+            }
+
+            hdata
+        };
+
+        Buffers {
+            weechat: self,
+            hdata,
+            pointers: pointers.into_iter(),
         }
     }
 
-    /// Get the main/core buffer.
-    pub fn core_buffer(&self) -> Buffer {
-        let buffer_search = self.get().buffer_search_main.unwrap();
-
+    /// Search all the buffers whose full name matches a regular expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex` - The regular expression the full name of the buffers, e.g.
+    ///     `irc.libera.#weechat`, is matched against.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use regex::Regex;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let regex = Regex::new(r"^irc\.libera\.#").unwrap();
+    ///
+    /// for buffer in weechat.buffer_search_regex(&regex) {
+    ///     buffer.print("Hello channel");
+    /// }
+    /// ```
+    #[cfg(feature = "regex")]
+    #[cfg_attr(feature = "docs", doc(cfg(regex)))]
+    pub fn buffer_search_regex(&self, regex: &regex::Regex) -> Vec<Buffer<'_>> {
+        self.buffers()
+            .filter(|buffer| regex.is_match(&buffer.full_name()))
+            .collect()
+    }
+
+    /// Get the global command history, newest entry first.
+    ///
+    /// The global history contains the input of all buffers, its size is
+    /// limited by the `weechat.history.max_commands` option.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximal number of entries that should be returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// if let Some(last_command) = weechat.global_command_history(1).first() {
+    ///     weechat.current_buffer().run_command(last_command).ok();
+    /// }
+    /// ```
+    pub fn global_command_history(&self, limit: usize) -> Vec<String> {
+        unsafe {
+            let hdata = self.hdata_get("history");
+            let first = self.hdata_get_list(hdata, "gui_history");
+
+            self.history_entries(hdata, first, limit)
+        }
+    }
+
+    unsafe fn history_entries(
+        &self,
+        hdata: *mut t_hdata,
+        mut entry: *mut c_void,
+        limit: usize,
+    ) -> Vec<String> {
+        let mut entries = Vec::new();
+
+        // New entries are added at the start of the history list, moving
+        // forward goes to older entries.
+        while !entry.is_null() && entries.len() < limit {
+            entries.push(self.hdata_string(hdata, entry, "text").into_owned());
+            entry = self.hdata_move(hdata, entry, 1);
+        }
+
+        entries
+    }
+
+    pub(crate) fn buffer_from_ptr(&self, buffer_ptr: *mut t_gui_buffer) -> Buffer {
+        Buffer {
+            inner: InnerBuffers::BorrowedBuffer(InnerBuffer {
+                weechat: self,
+                ptr: buffer_ptr,
+                closing: Rc::new(Cell::new(false)),
+            }),
+        }
+    }
+
+    /// Get the currently open buffer
+    pub fn current_buffer(&self) -> Buffer {
+        let buffer_search = self.get().buffer_search.unwrap();
+
+        let buf_ptr = unsafe { buffer_search(ptr::null(), ptr::null()) };
+        if buf_ptr.is_null() {
+            panic!("No open buffer found");
+        } else {
+            self.buffer_from_ptr(buf_ptr)
+        }
+    }
+
+    /// Get the main/core buffer.
+    pub fn core_buffer(&self) -> Buffer {
+        let buffer_search = self.get().buffer_search_main.unwrap();
+
         let buf_ptr = unsafe { buffer_search() };
 
         if buf_ptr.is_null() {
@@ -489,9 +1262,74 @@ impl Weechat {
         }
     }
 
+    /// Get the spell checking dictionaries that are used for a buffer.
+    ///
+    /// The dictionaries are configured by the user per buffer using the spell
+    /// plugin, e.g. with `/spell setdict`.
+    ///
+    /// Returns `None` if the spell plugin isn't loaded, an empty list if no
+    /// dictionary is used for the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer for which the dictionaries should be fetched.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # let weechat = unsafe { Weechat::weechat() };
+    /// let buffer = weechat.current_buffer();
+    ///
+    /// if let Some(dicts) = weechat.spell_dicts(&buffer) {
+    ///     Weechat::print(&format!("Dictionaries: {}", dicts.join(", ")));
+    /// }
+    /// ```
+    pub fn spell_dicts(&self, buffer: &Buffer) -> Option<Vec<String>> {
+        // The spell plugin returns no info at all if the buffer doesn't use a
+        // dictionary, look at the list of plugins to tell the two apart.
+        if !self.plugin_loaded("spell") {
+            return None;
+        }
+
+        let dicts = Weechat::info_get("spell_dict", &buffer.full_name()).unwrap_or_default();
+
+        Some(
+            dicts
+                .split(',')
+                .filter(|d| !d.is_empty())
+                .map(|d| d.to_owned())
+                .collect(),
+        )
+    }
+
+    /// Check if a plugin with the given name is loaded.
+    fn plugin_loaded(&self, name: &str) -> bool {
+        unsafe {
+            let hdata = self.hdata_get("plugin");
+            let mut plugin = self.hdata_get_list(hdata, "weechat_plugins");
+
+            while !plugin.is_null() {
+                if self.hdata_string(hdata, plugin, "name") == name {
+                    return true;
+                }
+
+                plugin = self.hdata_move(hdata, plugin, 1);
+            }
+        }
+
+        false
+    }
+
+    /// Remove all buffers from the hotlist, e.g. to mark all buffers as
+    /// read.
+    pub fn hotlist_clear_all(&self) -> Result<(), CommandError> {
+        self.core_buffer().run_command("/input hotlist_clear")
+    }
+
     #[cfg(feature = "async")]
     #[cfg_attr(feature = "docs", doc(cfg(r#async)))]
-    fn buffer_new_with_async(builder: BufferBuilderAsync) -> Result<BufferHandle, ()> {
+    fn buffer_new_with_async(builder: BufferBuilderAsync) -> Result<BufferHandle, NameError> {
         unsafe extern "C" fn c_input_cb(
             pointer: *const c_void,
             _data: *mut c_void,
@@ -517,9 +1355,30 @@ impl Weechat {
                 buffer_ptr: buffer_cell,
                 closing: Rc::new(Cell::new(false)),
             };
-            if let Some(cb) = pointers.input_cb.as_mut() {
-                let future = cb.callback(buffer_handle, input_data.to_string());
-                Weechat::spawn_buffer_cb(buffer.full_name().to_string(), future).detach();
+            let messages = prepare_input(
+                &weechat,
+                &buffer,
+                &mut pointers.multiline_policy,
+                &mut pointers.length_guard,
+                &input_data,
+            );
+
+            if let (Some(cb), false) = (pointers.input_cb.as_mut(), messages.is_empty()) {
+                // Chain the input futures so they run one after another, in
+                // the order the user entered the input.
+                let previous = pointers.last_input.take();
+                let future = async move {
+                    if let Some(previous) = previous {
+                        previous.await;
+                    }
+
+                    for message in messages {
+                        cb.callback(buffer_handle.clone(), message).await;
+                    }
+                };
+
+                let task = Weechat::spawn_buffer_cb(buffer.full_name().to_string(), future);
+                pointers.last_input = Some(task.fallible());
             }
 
             WEECHAT_RC_OK
@@ -535,7 +1394,6 @@ impl Weechat {
             let pointers = Box::from_raw(pointer as *mut BufferPointersAsync);
             let weechat = Weechat::from_ptr(pointers.weechat);
             let buffer = weechat.buffer_from_ptr(buffer);
-            buffer.mark_as_closing();
 
             let ret = if let Some(mut cb) = pointers.close_cb {
                 cb.callback(&weechat, &buffer).is_ok()
@@ -543,6 +1401,10 @@ impl Weechat {
                 true
             };
 
+            // Only mark the buffer as closing once the callback returned,
+            // the callback should still be able to access the buffer.
+            buffer.mark_as_closing();
+
             // Invalidate the buffer pointer now.
             pointers
                 .buffer_cell
@@ -560,6 +1422,10 @@ impl Weechat {
         Weechat::check_thread();
         let weechat = unsafe { Weechat::weechat() };
 
+        Weechat::validate_name(NameKind::Buffer, &builder.name)?;
+
+        let enable_multiline = builder.multiline_policy.is_some();
+
         let c_input_cb: Option<WeechatInputCbT> = match builder.input_callback {
             Some(_) => Some(c_input_cb),
             None => None,
@@ -573,12 +1439,15 @@ impl Weechat {
             input_cb: builder.input_callback,
             close_cb: builder.close_callback,
             buffer_cell: None,
+            last_input: None,
+            multiline_policy: builder.multiline_policy.unwrap_or_default(),
+            length_guard: builder.length_guard,
         });
 
         let buffer_pointers_ref = Box::leak(buffer_pointers);
 
         let buf_new = weechat.get().buffer_new.unwrap();
-        let c_name = LossyCString::new(builder.name);
+        let c_name = LossyCString::new(&builder.name);
 
         let buf_ptr = unsafe {
             buf_new(
@@ -593,9 +1462,11 @@ impl Weechat {
             )
         };
 
+        // Weechat only refuses a valid name if the plugin already has a buffer
+        // with the same name.
         if buf_ptr.is_null() {
             unsafe { Box::from_raw(buffer_pointers_ref) };
-            return Err(());
+            return Err(NameError::AlreadyExists(builder.name));
         }
 
         let pointers: &mut BufferPointersAsync =
@@ -604,6 +1475,18 @@ impl Weechat {
         let buffer = weechat.buffer_from_ptr(buf_ptr);
         let buffer_cell = Rc::new(Cell::new(buf_ptr));
 
+        if builder.buffer_type != BufferType::Formatted {
+            buffer.set("type", builder.buffer_type.as_str());
+        }
+
+        if enable_multiline {
+            buffer.enable_multiline();
+        }
+
+        if let Some(kind) = &builder.kind {
+            buffer.set_kind(kind);
+        }
+
         pointers.buffer_cell = Some(buffer_cell.clone());
 
         Ok(BufferHandle {
@@ -614,7 +1497,7 @@ impl Weechat {
         })
     }
 
-    fn buffer_new(builder: BufferBuilder) -> Result<BufferHandle, ()> {
+    fn buffer_new(builder: BufferBuilder) -> Result<BufferHandle, NameError> {
         unsafe extern "C" fn c_input_cb(
             pointer: *const c_void,
             _data: *mut c_void,
@@ -628,8 +1511,20 @@ impl Weechat {
             let weechat = Weechat::from_ptr(pointers.weechat);
             let buffer = weechat.buffer_from_ptr(buffer);
 
+            let messages = prepare_input(
+                &weechat,
+                &buffer,
+                &mut pointers.multiline_policy,
+                &mut pointers.length_guard,
+                &input_data,
+            );
+
+            // Stop at the first line the callback fails to handle, the
+            // remaining lines aren't passed to the callback.
             let ret = if let Some(ref mut cb) = pointers.input_cb.as_mut() {
-                cb.callback(&weechat, &buffer, input_data).is_ok()
+                messages
+                    .into_iter()
+                    .all(|message| cb.callback(&weechat, &buffer, message.into()).is_ok())
             } else {
                 true
             };
@@ -651,7 +1546,6 @@ impl Weechat {
             let pointers = Box::from_raw(pointer as *mut BufferPointers);
             let weechat = Weechat::from_ptr(pointers.weechat);
             let buffer = weechat.buffer_from_ptr(buffer);
-            buffer.mark_as_closing();
 
             let ret = if let Some(mut cb) = pointers.close_cb {
                 cb.callback(&weechat, &buffer).is_ok()
@@ -659,6 +1553,10 @@ impl Weechat {
                 true
             };
 
+            // Only mark the buffer as closing once the callback returned,
+            // the callback should still be able to access the buffer.
+            buffer.mark_as_closing();
+
             // Invalidate the buffer pointer now.
             pointers
                 .buffer_cell
@@ -676,6 +1574,10 @@ impl Weechat {
         Weechat::check_thread();
         let weechat = unsafe { Weechat::weechat() };
 
+        Weechat::validate_name(NameKind::Buffer, &builder.name)?;
+
+        let enable_multiline = builder.multiline_policy.is_some();
+
         let c_input_cb: Option<WeechatInputCbT> = match builder.input_callback {
             Some(_) => Some(c_input_cb),
             None => None,
@@ -689,11 +1591,13 @@ impl Weechat {
             input_cb: builder.input_callback,
             close_cb: builder.close_callback,
             buffer_cell: None,
+            multiline_policy: builder.multiline_policy.unwrap_or_default(),
+            length_guard: builder.length_guard,
         });
         let buffer_pointers_ref = Box::leak(buffer_pointers);
 
         let buf_new = weechat.get().buffer_new.unwrap();
-        let c_name = LossyCString::new(builder.name);
+        let c_name = LossyCString::new(&builder.name);
 
         let buf_ptr = unsafe {
             buf_new(
@@ -708,9 +1612,11 @@ impl Weechat {
             )
         };
 
+        // Weechat only refuses a valid name if the plugin already has a buffer
+        // with the same name.
         if buf_ptr.is_null() {
             unsafe { Box::from_raw(buffer_pointers_ref) };
-            return Err(());
+            return Err(NameError::AlreadyExists(builder.name));
         }
 
         let pointers: &mut BufferPointers =
@@ -719,6 +1625,18 @@ impl Weechat {
         let buffer = weechat.buffer_from_ptr(buf_ptr);
         let buffer_cell = Rc::new(Cell::new(buf_ptr));
 
+        if builder.buffer_type != BufferType::Formatted {
+            buffer.set("type", builder.buffer_type.as_str());
+        }
+
+        if enable_multiline {
+            buffer.enable_multiline();
+        }
+
+        if let Some(kind) = &builder.kind {
+            buffer.set_kind(kind);
+        }
+
         pointers.buffer_cell = Some(buffer_cell.clone());
 
         Ok(BufferHandle {
@@ -730,6 +1648,61 @@ impl Weechat {
     }
 }
 
+/// Iterator over the buffers that were open when it was created, buffers that
+/// got closed in the meantime are skipped.
+struct Buffers<'a> {
+    weechat: &'a Weechat,
+    hdata: *mut t_hdata,
+    pointers: std::vec::IntoIter<*mut t_gui_buffer>,
+}
+
+impl Buffers<'_> {
+    fn is_open(&self, buffer: *mut t_gui_buffer) -> bool {
+        let hdata_check_pointer = self.weechat.get().hdata_check_pointer.unwrap();
+
+        unsafe { hdata_check_pointer(self.hdata, ptr::null_mut(), buffer as *mut c_void) == 1 }
+    }
+}
+
+impl<'a> Iterator for Buffers<'a> {
+    type Item = Buffer<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(buffer) = self.pointers.next() {
+            if self.is_open(buffer) {
+                return Some(self.weechat.buffer_from_ptr(buffer));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let open = self
+            .pointers
+            .as_slice()
+            .iter()
+            .filter(|buffer| self.is_open(**buffer))
+            .count();
+
+        (open, Some(open))
+    }
+}
+
+impl DoubleEndedIterator for Buffers<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some(buffer) = self.pointers.next_back() {
+            if self.is_open(buffer) {
+                return Some(self.weechat.buffer_from_ptr(buffer));
+            }
+        }
+
+        None
+    }
+}
+
+impl ExactSizeIterator for Buffers<'_> {}
+
 pub(crate) type WeechatInputCbT = unsafe extern "C" fn(
     pointer: *const c_void,
     data: *mut c_void,
@@ -747,7 +1720,13 @@ impl Buffer<'_> {
 
     pub(crate) fn ptr(&self) -> *mut t_gui_buffer {
         match &self.inner {
-            InnerBuffers::BorrowedBuffer(b) => b.ptr,
+            InnerBuffers::BorrowedBuffer(b) => {
+                if b.closing.get() {
+                    panic!("Buffer has been closed.")
+                } else {
+                    b.ptr
+                }
+            }
             InnerBuffers::OwnedBuffer(b) => {
                 let ptr = b.buffer_handle.buffer_ptr.get();
 
@@ -769,6 +1748,11 @@ impl Buffer<'_> {
     }
 
     /// Display a message on the buffer.
+    ///
+    /// Weechat doesn't redraw the buffer for every printed message, the
+    /// window is only marked to be refreshed and redrawn once the current
+    /// callback returned to the main loop. Printing many messages in a single
+    /// callback results in a single redraw.
     pub fn print(&self, message: &str) {
         let weechat = self.weechat();
         let printf_date_tags = weechat.get().printf_date_tags.unwrap();
@@ -791,30 +1775,238 @@ impl Buffer<'_> {
     ///
     /// # Arguments
     ///
-    /// * `date` - A unix time-stamp representing the date of the message, 0
-    ///     means now.
+    /// * `date` - The date of the message, e.g. a `SystemTime`. The unix epoch
+    ///     itself means now.
     ///
     /// * `tags` - A list of tags that will be applied to the printed line.
     ///
     /// * `message` - The message that will be displayed.
-    pub fn print_date_tags(&self, date: i64, tags: &[&str], message: &str) {
+    ///
+    /// Returns an error if one of the tags contains a comma, Weechat uses
+    /// commas to separate the tags and can't escape them. Nothing is printed
+    /// in that case.
+    ///
+    /// Weechat appends the line to the end of the buffer no matter which date
+    /// is given, lines with a date in the past aren't sorted into the existing
+    /// lines. Plugins that import a backlog should print it oldest line first,
+    /// before any new lines are printed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::{Duration, SystemTime};
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// let now = SystemTime::now();
+    /// let backlog = vec![
+    ///     (now - Duration::from_secs(120), "Hello"),
+    ///     (now - Duration::from_secs(60), "How are you?"),
+    /// ];
+    ///
+    /// for (date, message) in backlog {
+    ///     buffer
+    ///         .print_date_tags(date, &["no_log", "notify_none"], message)
+    ///         .unwrap();
+    /// }
+    ///
+    /// let dates: Vec<SystemTime> = buffer.lines().map(|line| line.date()).collect();
+    /// assert!(dates.windows(2).all(|pair| pair[0] <= pair[1]));
+    ///
+    /// assert!(buffer
+    ///     .print_date_tags(SystemTime::now(), &["nick_alice,bob"], "Hi")
+    ///     .is_err());
+    /// ```
+    pub fn print_date_tags(
+        &self,
+        date: impl ToWeechatTime,
+        tags: &[&str],
+        message: &str,
+    ) -> Result<(), ()> {
         let weechat = self.weechat();
         let printf_date_tags = weechat.get().printf_date_tags.unwrap();
 
         let fmt_str = LossyCString::new("%s");
-        let tags = tags.join(",");
-        let tags = LossyCString::new(tags);
+        let tags = LossyCString::new(join_tags(tags)?);
         let message = LossyCString::new(message);
 
         unsafe {
             printf_date_tags(
                 self.ptr(),
-                date,
+                date.to_weechat_time(),
                 tags.as_ptr(),
                 fmt_str.as_ptr(),
                 message.as_ptr(),
             )
         }
+
+        Ok(())
+    }
+
+    /// Display a message on the buffer with attached tags.
+    ///
+    /// The message is printed with the current date.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - A list of tags that will be applied to the printed line.
+    ///
+    /// * `message` - The message that will be displayed.
+    ///
+    /// Returns an error if one of the tags contains a comma, nothing is
+    /// printed in that case.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer
+    ///     .print_tags(&["notify_highlight", "nick_alice"], "alice: ping")
+    ///     .unwrap();
+    /// ```
+    pub fn print_tags(&self, tags: &[&str], message: &str) -> Result<(), ()> {
+        self.print_date_tags(SystemTime::UNIX_EPOCH, tags, message)
+    }
+
+    /// Display a message on the given line of a buffer with free content.
+    ///
+    /// Writing to a line that already contains a message replaces the
+    /// message.
+    ///
+    /// Weechat doesn't redraw the buffer for every call, the window is only
+    /// marked to be refreshed and redrawn once the current callback returned
+    /// to the main loop. Rewriting many lines at once, e.g. the rows of a
+    /// table on every tick, results in a single redraw.
+    ///
+    /// Returns an error if the buffer doesn't have free content, see
+    /// `BufferType::Free`, Weechat would silently discard the message
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The line number, starting at 0, on which the message should be
+    ///     displayed. Negative values are passed to Weechat unmodified, newer
+    ///     Weechat versions use them for placement relative to the end of
+    ///     the buffer, e.g. `-1` to add a line after the last one.
+    ///
+    /// * `message` - The message that will be displayed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::{BufferBuilder, BufferType};
+    /// let buffer_handle = BufferBuilder::new("table")
+    ///     .buffer_type(BufferType::Free)
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    /// let buffer = buffer_handle.upgrade().unwrap();
+    ///
+    /// buffer.print_y(0, "name    value").unwrap();
+    /// buffer.print_y(1, "cpu     10%").unwrap();
+    /// buffer.print_y(2, "memory  512M").unwrap();
+    ///
+    /// // Replace the second row.
+    /// buffer.print_y(1, "cpu     12%").unwrap();
+    /// ```
+    pub fn print_y(&self, y: i32, message: &str) -> Result<(), ()> {
+        if self.buffer_type() != BufferType::Free {
+            return Err(());
+        }
+
+        let weechat = self.weechat();
+        let printf_y = weechat.get().printf_y.unwrap();
+
+        let fmt_str = LossyCString::new("%s");
+        let message = LossyCString::new(message);
+
+        unsafe { printf_y(self.ptr(), y, fmt_str.as_ptr(), message.as_ptr()) };
+
+        Ok(())
+    }
+
+    /// Clear the given line of a buffer with free content.
+    ///
+    /// Returns an error if the buffer doesn't have free content.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The line number, starting at 0, of the line that should be
+    ///     cleared.
+    pub fn clear_line_y(&self, y: i32) -> Result<(), ()> {
+        self.print_y(y, "")
+    }
+
+    /// Display a message with attached date and tags on the given line of a
+    /// buffer with free content.
+    ///
+    /// Returns an error if the buffer doesn't have free content, see
+    /// `BufferType::Free`, or if one of the tags contains a comma.
+    ///
+    /// The plugin API this crate is built against has no
+    /// `printf_y_date_tags()` function, the message is printed using
+    /// `print_y()` and the date and tags are set on the new line afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The line number, starting at 0, on which the message should be
+    ///     displayed. Negative values are passed to Weechat unmodified, see
+    ///     `print_y()`.
+    ///
+    /// * `date` - The date of the message, e.g. a `SystemTime`. The unix epoch
+    ///     itself means now.
+    ///
+    /// * `tags` - A list of tags that will be applied to the printed line.
+    ///
+    /// * `message` - The message that will be displayed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::SystemTime;
+    /// # use weechat::buffer::{BufferBuilder, BufferType};
+    /// let buffer_handle = BufferBuilder::new("scripts")
+    ///     .buffer_type(BufferType::Free)
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    /// let buffer = buffer_handle.upgrade().unwrap();
+    ///
+    /// buffer
+    ///     .print_y_date_tags(0, SystemTime::now(), &["script_go"], "go.py")
+    ///     .unwrap();
+    /// ```
+    pub fn print_y_date_tags(
+        &self,
+        y: i32,
+        date: impl ToWeechatTime,
+        tags: &[&str],
+        message: &str,
+    ) -> Result<(), ()> {
+        join_tags(tags)?;
+        self.print_y(y, message)?;
+
+        let line = if y < 0 {
+            self.lines().next_back()
+        } else {
+            self.lines().find(|line| line.y() == y)
+        };
+
+        if let Some(line) = line {
+            let date = date.to_weechat_time();
+
+            line.update(LineData {
+                date: if date == 0 {
+                    None
+                } else {
+                    Some(SystemTime::from_weechat_time(date))
+                },
+                tags: Some(tags),
+                ..Default::default()
+            })?;
+        }
+
+        Ok(())
     }
 
     /// Search for a nicklist group by name
@@ -882,6 +2074,30 @@ impl Buffer<'_> {
         unsafe { nicklist_search_nick(buffer_ptr, group_ptr, nick.as_ptr()) }
     }
 
+    /// Get an iterator over all the nicks in the nicklist of the buffer.
+    ///
+    /// Nicks of all groups are returned, `NickGroup::nicks()` can be used to
+    /// only iterate over the nicks of a single group. The iterator walks the
+    /// nicklist lazily, nicks must not be removed from the nicklist while
+    /// iterating, collect the names of the nicks first if that's necessary.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// # let away_users = vec!["alice"];
+    /// for nick in buffer.nicks() {
+    ///     if away_users.contains(&nick.name().as_ref()) {
+    ///         nick.set_color("weechat.color.nicklist_away");
+    ///     }
+    /// }
+    /// ```
+    pub fn nicks(&self) -> Nicks {
+        Nicks::new(self.ptr(), self.weechat().ptr, None)
+    }
+
     /// Create and add a new nick to the buffer nicklist.
     ///
     /// This will add the nick to the root nick group.
@@ -892,7 +2108,29 @@ impl Buffer<'_> {
     ///     added.
     ///
     /// Returns the newly created nick if one is created successfully, an empty
-    /// error otherwise.
+    /// error otherwise. Adding a nick that is already in the nicklist fails,
+    /// use `search_nick()` and the `Nick` setters to update an existing nick.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::{BufferBuilder, NickSettings};
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.enable_nicklist();
+    ///
+    /// let ops = buffer
+    ///     .add_nicklist_group("000|o", "weechat.color.nicklist_group", true, None)
+    ///     .expect("Can't add the ops group");
+    ///
+    /// let settings = NickSettings::new("alice").set_prefix("@").set_prefix_color("lightgreen");
+    ///
+    /// if ops.add_nick(settings).is_err() {
+    ///     if let Some(nick) = buffer.search_nick("alice") {
+    ///         nick.set_prefix("@");
+    ///     }
+    /// }
+    /// ```
     pub fn add_nick(&self, nick_settings: NickSettings) -> Result<Nick, ()> {
         let weechat = self.weechat();
         let nick_ptr = Buffer::add_nick_helper(&weechat, self.ptr(), nick_settings, None);
@@ -942,17 +2180,9 @@ impl Buffer<'_> {
     ///
     /// Returns `true` if a nick was found and removed, `false` otherwise.
     pub fn remove_nick(&self, nick: &str) -> bool {
-        let weechat = self.weechat();
-
-        let nick = self.search_nick(nick);
-
-        match nick {
+        match self.search_nick(nick) {
             Some(nick) => {
-                let nicklist_remove_nick = weechat.get().nicklist_remove_nick.unwrap();
-
-                unsafe {
-                    nicklist_remove_nick(self.ptr(), nick.ptr);
-                }
+                nick.remove();
                 true
             }
             None => false,
@@ -990,6 +2220,29 @@ impl Buffer<'_> {
         }
     }
 
+    /// Get the root group of the nicklist.
+    ///
+    /// Every buffer has a root group, which isn't displayed, groups and nicks
+    /// that are added without a parent group are added to it.
+    pub fn nicklist_root(&self) -> NickGroup {
+        let weechat = self.weechat();
+
+        let group_ptr = unsafe {
+            weechat.hdata_pointer(
+                self.hdata_pointer(),
+                self.ptr() as *mut c_void,
+                "nicklist_root",
+            )
+        };
+
+        NickGroup {
+            ptr: group_ptr as *mut t_gui_nick_group,
+            buf_ptr: self.ptr(),
+            weechat_ptr: weechat.ptr,
+            buffer: PhantomData,
+        }
+    }
+
     /// Create and add a new nicklist group to the buffers nicklist.
     ///
     /// * `name` - Name of the new group.
@@ -1080,6 +2333,9 @@ impl Buffer<'_> {
 
     /// Get the value of a buffer localvar
     ///
+    /// Returns `None` if the localvar isn't set, a localvar that is set to an
+    /// empty string returns an empty string.
+    ///
     /// # Arguments
     ///
     /// * `property` - The name of the property for which the value should be
@@ -1099,6 +2355,199 @@ impl Buffer<'_> {
         self.set(&format!("localvar_set_{}", property), value)
     }
 
+    /// Set the charsets that should be used to decode incoming and encode
+    /// outgoing messages of this buffer.
+    ///
+    /// The charsets are stored in the `charset_decode` and `charset_encode`
+    /// localvars of the buffer, passing `None` removes the localvar again.
+    /// Use the [`decode()`] and [`encode()`] methods to convert messages
+    /// using the configured charsets.
+    ///
+    /// # Arguments
+    ///
+    /// * `decode` - The charset incoming messages are encoded in, e.g.
+    ///     `iso-8859-1`.
+    ///
+    /// * `encode` - The charset outgoing messages should be encoded in.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.set_charset(Some("iso-8859-1"), Some("iso-8859-1"));
+    ///
+    /// let message = buffer.decode(b"caf\xe9");
+    /// assert_eq!(message, "café");
+    /// assert_eq!(buffer.encode(&message), b"caf\xe9");
+    /// ```
+    ///
+    /// [`decode()`]: #method.decode
+    /// [`encode()`]: #method.encode
+    pub fn set_charset(&self, decode: Option<&str>, encode: Option<&str>) {
+        let set_or_delete = |name: &str, charset: Option<&str>| match charset {
+            Some(charset) => self.set_localvar(name, charset),
+            None => self.set(&format!("localvar_del_{}", name), ""),
+        };
+
+        set_or_delete("charset_decode", decode);
+        set_or_delete("charset_encode", encode);
+    }
+
+    /// Get the charset that is used to decode incoming messages of this
+    /// buffer.
+    pub fn charset_decode(&self) -> Option<Cow<str>> {
+        self.get_localvar("charset_decode")
+    }
+
+    /// Get the charset that is used to encode outgoing messages of this
+    /// buffer.
+    pub fn charset_encode(&self) -> Option<Cow<str>> {
+        self.get_localvar("charset_encode")
+    }
+
+    /// Decode an incoming message using the decode charset of the buffer.
+    ///
+    /// If the conversion fails Weechat returns the message unconverted, with
+    /// invalid UTF-8 sequences replaced by `?`, see
+    /// `Weechat::iconv_to_internal()`. If no charset is configured, or the
+    /// message contains a NUL byte, the message is decoded as UTF-8 and
+    /// invalid sequences are replaced with `U+FFFD`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The raw bytes of the message.
+    pub fn decode(&self, message: &[u8]) -> String {
+        self.charset_decode()
+            .filter(|c| !c.is_empty())
+            .and_then(|charset| Weechat::iconv_to_internal(&charset, message).ok())
+            .unwrap_or_else(|| String::from_utf8_lossy(message).into_owned())
+    }
+
+    /// Encode an outgoing message using the encode charset of the buffer.
+    ///
+    /// If no charset is configured, or the conversion fails, the UTF-8 bytes
+    /// of the message are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message that should be encoded.
+    pub fn encode(&self, message: &str) -> Vec<u8> {
+        self.charset_encode()
+            .filter(|c| !c.is_empty())
+            .and_then(|charset| Weechat::iconv_from_internal(&charset, message).ok())
+            .unwrap_or_else(|| message.as_bytes().to_vec())
+    }
+
+    fn set_kind(&self, kind: &BufferKind) {
+        match kind {
+            BufferKind::Server { server } => {
+                self.set_localvar("type", "server");
+                self.set_localvar("server", server);
+                self.set_localvar("channel", server);
+            }
+            BufferKind::Channel {
+                server,
+                channel,
+                nick,
+            } => {
+                self.set_localvar("type", "channel");
+                self.set_localvar("server", server);
+                self.set_localvar("channel", channel);
+                self.set_localvar("nick", nick);
+                self.set("highlight_words_add", "$nick");
+                self.enable_nicklist();
+            }
+            BufferKind::Private {
+                server,
+                remote_nick,
+                nick,
+            } => {
+                self.set_localvar("type", "private");
+                self.set_localvar("server", server);
+                self.set_localvar("channel", remote_nick);
+                self.set_localvar("nick", nick);
+                self.set("highlight_words_add", "$nick");
+                self.disable_nicklist();
+            }
+            BufferKind::Debug => {
+                self.set_localvar("type", "debug");
+                self.disable_log();
+                self.disable_highlights();
+                self.disable_nicklist();
+                self.set_notify(NotifyLevel::None);
+            }
+            BufferKind::List => {
+                self.set_localvar("type", "list");
+                self.disable_log();
+                self.disable_nicklist();
+                self.disable_time_for_each_line();
+                self.set_notify(NotifyLevel::None);
+            }
+        }
+    }
+
+    /// Get all the localvars of the buffer.
+    ///
+    /// Returns an iterator over the names and values of the localvars, sorted
+    /// by name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let buffer = weechat.current_buffer();
+    ///
+    /// for (name, value) in buffer.localvars() {
+    ///     Weechat::print(&format!("{}: {}", name, value));
+    /// }
+    /// ```
+    pub fn localvars(&self) -> impl Iterator<Item = (String, String)> {
+        let weechat = self.weechat();
+
+        let mut localvars = unsafe {
+            let hashtable = weechat.hdata_pointer(
+                self.hdata_pointer(),
+                self.ptr() as *mut c_void,
+                "local_variables",
+            );
+
+            if hashtable.is_null() {
+                Vec::new()
+            } else {
+                weechat.hashtable_to_vec(hashtable as *mut _)
+            }
+        };
+
+        localvars.sort();
+        localvars.into_iter()
+    }
+
+    /// Delete a buffer localvar
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - The name of the localvar that should be deleted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.set_localvar("type", "channel");
+    /// buffer.set_localvar("server", "libera");
+    /// buffer.set_localvar("channel", "#weechat");
+    ///
+    /// buffer.del_localvar("channel");
+    /// assert_eq!(buffer.get_localvar("channel"), None);
+    /// ```
+    pub fn del_localvar(&self, property: &str) {
+        self.set(&format!("localvar_del_{}", property), "")
+    }
+
     /// Get the full name of the buffer.
     pub fn full_name(&self) -> Cow<str> {
         self.get_string("full_name").unwrap()
@@ -1120,11 +2569,53 @@ impl Buffer<'_> {
 
     /// Set the name of the buffer.
     ///
+    /// The number of the buffer and its hotlist entry stay the same, the
+    /// buffer can be found under its new name using
+    /// `Weechat::buffer_search()`.
+    ///
+    /// Returns an error if the name is empty, contains a NUL byte or if
+    /// another buffer of the same plugin already uses the name.
+    ///
     /// # Arguments
     ///
     /// * `name` - The new name that should be set.
-    pub fn set_name(&self, name: &str) {
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::BufferBuilder;
+    /// # use weechat::util::NameError;
+    /// # let weechat = unsafe { Weechat::weechat() };
+    /// # let buffer_handle = BufferBuilder::new("#rust").build().unwrap();
+    /// # let other_handle = BufferBuilder::new("#go").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.set_name("#rust-lang").expect("Can't rename the buffer");
+    ///
+    /// let plugin_name = buffer.plugin_name();
+    /// assert!(weechat.buffer_search(&plugin_name, "#rust-lang").is_some());
+    /// assert!(weechat.buffer_search(&plugin_name, "#rust").is_none());
+    ///
+    /// assert_eq!(buffer.set_name(""), Err(NameError::Empty));
+    /// assert_eq!(
+    ///     buffer.set_name("#go"),
+    ///     Err(NameError::AlreadyExists("#go".to_owned()))
+    /// );
+    /// ```
+    pub fn set_name(&self, name: &str) -> Result<(), NameError> {
+        check_name(NameKind::Buffer, name)?;
+
+        let weechat = self.weechat();
+
+        if let Some(other) = weechat.buffer_search(&self.plugin_name(), name) {
+            if other.ptr() != self.ptr() {
+                return Err(NameError::AlreadyExists(name.to_owned()));
+            }
+        }
+
         self.set("name", name);
+
+        Ok(())
     }
 
     /// Get the short_name of the buffer.
@@ -1132,8 +2623,23 @@ impl Buffer<'_> {
         self.get_string("short_name").unwrap()
     }
 
+    /// Get the short_name of the buffer, or the name of the buffer if the
+    /// buffer has no short name.
+    pub fn short_name_or_name(&self) -> Cow<str> {
+        let short_name = self.short_name();
+
+        if short_name.is_empty() {
+            self.name()
+        } else {
+            short_name
+        }
+    }
+
     /// Set the short_name of the buffer.
     ///
+    /// Setting an empty short name removes the short name, the name of the
+    /// buffer is displayed instead.
+    ///
     /// # Arguments
     ///
     /// * `name` - The new short name that should be set.
@@ -1146,11 +2652,96 @@ impl Buffer<'_> {
         self.get_string("plugin").unwrap()
     }
 
+    /// Get the type of the buffer.
+    pub fn buffer_type(&self) -> BufferType {
+        if self.get_integer("type") == 1 {
+            BufferType::Free
+        } else {
+            BufferType::Formatted
+        }
+    }
+
+    /// Set the type of the buffer.
+    ///
+    /// Changing the type of the buffer removes all the lines of the buffer.
+    /// The type needs to be set to `BufferType::Free` before any lines are
+    /// written using `print_y()`, preferably using
+    /// `BufferBuilder::buffer_type()` when the buffer is created.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_type` - The new type of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::{BufferBuilder, BufferType};
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.set_buffer_type(BufferType::Free);
+    /// assert_eq!(buffer.buffer_type(), BufferType::Free);
+    ///
+    /// buffer.print_y(0, "first row").unwrap();
+    /// ```
+    pub fn set_buffer_type(&self, buffer_type: BufferType) {
+        self.set("type", buffer_type.as_str());
+    }
+
+    /// Set the type of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_type` - The new type of the buffer.
+    #[deprecated(note = "use `set_buffer_type()` instead")]
+    pub fn set_type(&self, buffer_type: BufferType) {
+        self.set_buffer_type(buffer_type)
+    }
+
+    /// Get the notify level of the buffer.
+    ///
+    /// An unknown level is returned as `NotifyLevel::All`, the default of
+    /// Weechat.
+    pub fn notify(&self) -> NotifyLevel {
+        NotifyLevel::from(self.get_integer("notify"))
+    }
+
+    /// Set the notify level of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The new notify level of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::{BufferBuilder, NotifyLevel};
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// let previous = buffer.notify();
+    /// buffer.set_notify(NotifyLevel::None);
+    ///
+    /// // Later on restore the previous level.
+    /// buffer.set_notify(previous);
+    /// ```
+    pub fn set_notify(&self, level: NotifyLevel) {
+        self.set("notify", level.as_str());
+    }
+
     /// Hide time for all lines in the buffer.
     pub fn disable_time_for_each_line(&self) {
         self.set("time_for_each_line", "0");
     }
 
+    /// Show time for all lines in the buffer, this is the default.
+    pub fn enable_time_for_each_line(&self) {
+        self.set("time_for_each_line", "1");
+    }
+
+    /// Is the time displayed for all lines in the buffer.
+    pub fn is_time_for_each_line_enabled(&self) -> bool {
+        self.get_integer("time_for_each_line") == 1
+    }
+
     /// Disable the nicklist for this buffer.
     pub fn disable_nicklist(&self) {
         self.set("nicklist", "0")
@@ -1171,78 +2762,398 @@ impl Buffer<'_> {
         self.set("nicklist", "1")
     }
 
-    /// Get the title of the buffer
-    pub fn title(&self) {
-        self.get_string("title");
+    /// Is the nicklist enabled for this buffer.
+    pub fn is_nicklist_enabled(&self) -> bool {
+        self.get_integer("nicklist") == 1
+    }
+
+    /// Get the title of the buffer.
+    ///
+    /// The title is returned as it was set, color codes that are part of the
+    /// title are not removed, use `Weechat::remove_color()` to get the plain
+    /// text of the title.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.set_title(&format!("{}#weechat", Weechat::color("bold")));
+    ///
+    /// assert_eq!(Weechat::remove_color(&buffer.title()), "#weechat");
+    /// ```
+    pub fn title(&self) -> Cow<str> {
+        self.get_string("title").unwrap_or_default()
     }
 
     /// Set the title of the buffer.
     ///
+    /// The title is passed to Weechat unmodified, so it may contain color
+    /// codes produced by `Weechat::color()`.
+    ///
     /// # Arguments
     ///
     /// * `title` - The new title that will be set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test")
+    /// #    .build()
+    /// #    .unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// let title = format!("{}Connected{}", Weechat::color("green"), Weechat::color("reset"));
+    ///
+    /// buffer.set_title(&title);
+    /// assert_eq!(buffer.title(), title);
+    /// ```
     pub fn set_title(&self, title: &str) {
         self.set("title", title);
     }
 
-    /// Disable logging for this buffer.
-    pub fn disable_log(&self) {
-        self.set("localvar_set_no_log", "1");
+    /// Set the words that will trigger a highlight on this buffer.
+    ///
+    /// Setting an empty list of words clears the highlight words of the
+    /// buffer, the default highlight rules, e.g. the nick of the user, apply
+    /// again. To disable highlights completely use `disable_highlights()`.
+    ///
+    /// Returns an error if one of the words contains a comma, Weechat stores
+    /// the words as a comma separated list and has no way to escape them.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The list of words that should trigger a highlight.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test")
+    /// #    .build()
+    /// #    .unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.set_highlight_words(&["rust", "weechat"]).unwrap();
+    ///
+    /// // Remove all the highlight words again.
+    /// buffer.set_highlight_words(&[]).unwrap();
+    /// ```
+    pub fn set_highlight_words(&self, words: &[&str]) -> Result<(), ()> {
+        self.set("highlight_words", &Buffer::join_highlight_words(words)?);
+        Ok(())
     }
 
-    /// Clear buffer contents
-    pub fn clear(&self) {
-        let weechat = self.weechat();
-
-        let buffer_clear = weechat.get().buffer_clear.unwrap();
-        unsafe { buffer_clear(self.ptr()) }
+    /// Add words to the words that trigger a highlight on this buffer.
+    ///
+    /// Returns an error if one of the words contains a comma, no word is
+    /// added in that case.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The words that should be added.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test")
+    /// #    .build()
+    /// #    .unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// let alternate_nicks = ["alice_", "alice__"];
+    /// buffer.add_highlight_words(&alternate_nicks).unwrap();
+    ///
+    /// // A line containing one of the words is now highlighted.
+    /// buffer.print("bob: alice_, are you there?");
+    /// ```
+    pub fn add_highlight_words(&self, words: &[&str]) -> Result<(), ()> {
+        self.set("highlight_words_add", &Buffer::join_highlight_words(words)?);
+        Ok(())
     }
 
-    /// Close the buffer.
+    /// Remove words from the words that trigger a highlight on this buffer.
     ///
-    /// Note that this will only queue up the buffer to be closed. The close
-    /// callback will first be run, only then will the buffer be closed.
+    /// Returns an error if one of the words contains a comma, no word is
+    /// removed in that case.
     ///
-    /// Multiple calls to this method will result in a nop.
-    pub fn close(&self) {
-        if !self.is_closing() {
-            let weechat = self.weechat();
-
-            let buffer_close = weechat.get().buffer_close.unwrap();
-            unsafe { buffer_close(self.ptr()) };
-            self.mark_as_closing();
-        }
+    /// # Arguments
+    ///
+    /// * `words` - The words that should be removed.
+    pub fn remove_highlight_words(&self, words: &[&str]) -> Result<(), ()> {
+        self.set("highlight_words_del", &Buffer::join_highlight_words(words)?);
+        Ok(())
     }
 
-    /// Get the contents of the input
-    pub fn input(&self) -> Cow<str> {
-        self.get_string("input").unwrap()
+    /// Get the words that trigger a highlight on this buffer.
+    ///
+    /// Returns an empty list if no highlight words are set, highlights that
+    /// were disabled using `disable_highlights()` are returned as `["-"]`.
+    pub fn highlight_words(&self) -> Vec<String> {
+        Buffer::split_list(self.get_string("highlight_words"))
     }
 
-    /// Set the content of the buffer input.
-    pub fn set_input(&self, input: &str) {
-        self.set("input", input)
+    fn join_highlight_words(words: &[&str]) -> Result<String, ()> {
+        if words.iter().any(|w| w.contains(',')) {
+            Err(())
+        } else {
+            Ok(words.join(","))
+        }
     }
 
-    /// Get the position of the cursor in the buffer input.
-    pub fn input_position(&self) -> i32 {
-        self.get_integer("input_pos")
+    fn split_list(list: Option<Cow<str>>) -> Vec<String> {
+        list.map(|l| {
+            l.split(',')
+                .filter(|w| !w.is_empty())
+                .map(|w| w.to_owned())
+                .collect()
+        })
+        .unwrap_or_default()
     }
 
-    /// Set the position of the input buffer.
+    /// Set a regular expression that will trigger a highlight on this buffer.
+    ///
+    /// The regular expression is POSIX extended and case insensitive unless
+    /// it starts with `(?-i)`. Setting an empty string removes the regular
+    /// expression.
     ///
     /// # Arguments
     ///
-    /// * `position` - The new position of the input.
-    pub fn set_input_position(&self, position: i32) {
-        self.set("input_pos", &position.to_string())
+    /// * `regex` - The regular expression that should trigger a highlight.
+    pub fn set_highlight_regex(&self, regex: &str) {
+        self.set("highlight_regex", regex);
     }
 
-    /// Enable multiline support.
+    /// Get the regular expression that triggers a highlight on this buffer.
     ///
-    /// If enabled multiple lines separated by a newline are sent as a single
-    /// string to the input callback. If disabled each line will be sent to the
-    /// input callback separately.
+    /// Returns `None` if no regular expression is set.
+    pub fn highlight_regex(&self) -> Option<Cow<str>> {
+        self.get_string("highlight_regex").filter(|r| !r.is_empty())
+    }
+
+    /// Set the tags that force a highlight on this buffer.
+    ///
+    /// Every line with one of the tags is highlighted, regardless of its
+    /// content. A tag may be a combination of tags joined with `+`, e.g.
+    /// `irc_privmsg+nick_alice`. Setting an empty list of tags removes the
+    /// highlight tags.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - The list of tags that should trigger a highlight.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test")
+    /// #    .build()
+    /// #    .unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// // Highlight every message of alice.
+    /// buffer.set_highlight_tags(&["irc_privmsg+nick_alice"]);
+    /// ```
+    pub fn set_highlight_tags(&self, tags: &[&str]) {
+        self.set("highlight_tags", &tags.join(","));
+    }
+
+    /// Get the tags that force a highlight on this buffer.
+    pub fn highlight_tags(&self) -> Vec<String> {
+        Buffer::split_list(self.get_string("highlight_tags"))
+    }
+
+    /// Disable highlights on this buffer.
+    ///
+    /// This sets the highlight words to the special value `-`, no line of
+    /// the buffer will be highlighted, not even lines that contain the nick
+    /// of the user. Setting the highlight words with
+    /// `set_highlight_words()` enables highlights again, an empty list of
+    /// words restores the default highlight behaviour.
+    pub fn disable_highlights(&self) {
+        self.set("highlight_words", "-");
+    }
+
+    /// Restrict highlights to lines with the given tags.
+    ///
+    /// Lines without one of the tags are never highlighted. A tag may be a
+    /// combination of tags joined with `+`, e.g. `irc_privmsg+nick_alice`,
+    /// in which case all the tags of the combination are required. Setting
+    /// an empty list of tags removes the restriction.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - The list of tags highlights should be restricted to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test")
+    /// #    .build()
+    /// #    .unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// // Only messages can highlight, not joins or topic changes.
+    /// buffer.set_highlight_tags_restrict(&["irc_privmsg", "irc_notice"]);
+    /// ```
+    pub fn set_highlight_tags_restrict(&self, tags: &[&str]) {
+        self.set("highlight_tags_restrict", &tags.join(","));
+    }
+
+    /// Get the tags highlights are restricted to.
+    pub fn highlight_tags_restrict(&self) -> Vec<String> {
+        Buffer::split_list(self.get_string("highlight_tags_restrict"))
+    }
+
+    /// Disable logging for this buffer.
+    pub fn disable_log(&self) {
+        self.set("localvar_set_no_log", "1");
+    }
+
+    /// Is logging disabled for this buffer.
+    pub fn is_log_disabled(&self) -> bool {
+        self.get_localvar("no_log").as_deref() == Some("1")
+    }
+
+    /// Clear buffer contents.
+    ///
+    /// This removes all the lines that were printed to the buffer. The buffer
+    /// doesn't need to be the current buffer, buffers that aren't displayed
+    /// in any window can be cleared as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let buffer = weechat
+    ///     .buffer_search("core", "weechat")
+    ///     .expect("Can't find the core buffer");
+    ///
+    /// buffer.clear();
+    /// assert_eq!(buffer.lines().count(), 0);
+    /// ```
+    pub fn clear(&self) {
+        let weechat = self.weechat();
+
+        let buffer_clear = weechat.get().buffer_clear.unwrap();
+        unsafe { buffer_clear(self.ptr()) }
+    }
+
+    /// Close the buffer.
+    ///
+    /// Note that this will only queue up the buffer to be closed. The close
+    /// callback will first be run, only then will the buffer be closed.
+    ///
+    /// Multiple calls to this method will result in a nop. Calling any other
+    /// method on the buffer after it has been closed will panic.
+    ///
+    /// Closing the buffer that is currently displayed is fine, Weechat
+    /// switches the window to another buffer before the buffer is freed, so
+    /// `Weechat::current_buffer()` keeps returning a valid buffer. Use
+    /// `BufferHandle::close()` to close a buffer through its handle, later
+    /// upgrades of clones of the handle fail instead of returning a closed
+    /// buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let buffer_handle = BufferBuilder::new("command_output")
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    ///
+    /// let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.switch_to();
+    /// buffer.close();
+    ///
+    /// assert!(buffer_handle.upgrade().is_err());
+    /// assert_ne!(weechat.current_buffer().name(), "command_output");
+    /// ```
+    pub fn close(&self) {
+        if !self.is_closing() {
+            let weechat = self.weechat();
+
+            let buffer_close = weechat.get().buffer_close.unwrap();
+            unsafe { buffer_close(self.ptr()) };
+            self.mark_as_closing();
+        }
+    }
+
+    /// Get the contents of the input
+    pub fn input(&self) -> Cow<str> {
+        self.get_string("input").unwrap()
+    }
+
+    /// Set the content of the buffer input.
+    pub fn set_input(&self, input: &str) {
+        self.set("input", input)
+    }
+
+    /// Get the position of the cursor in the buffer input.
+    pub fn input_position(&self) -> i32 {
+        self.get_integer("input_pos")
+    }
+
+    /// Set the position of the input buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The new position of the input.
+    pub fn set_input_position(&self, position: i32) {
+        self.set("input_pos", &position.to_string())
+    }
+
+    /// Replace the content of the buffer input and move the cursor.
+    ///
+    /// The cursor position is counted in characters, not bytes, it needs to
+    /// be between 0 and the number of characters of the new input, inclusive.
+    ///
+    /// Returns an error and leaves the input untouched if the cursor position
+    /// is out of bounds.
+    ///
+    /// **Note**: This isn't atomic, the input and the cursor position are set
+    /// one after the other. Hooks that react to input changes, e.g. of the
+    /// `input_text_changed` signal, see the new input with the cursor still
+    /// at its old position. The position can't be set first since Weechat
+    /// limits it to the length of the input at the time it is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The new content of the input.
+    ///
+    /// * `cursor` - The new position of the cursor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let buffer = weechat.current_buffer();
+    ///
+    /// // Place the cursor after "café".
+    /// buffer.replace_input("café au lait", 4).unwrap();
+    ///
+    /// assert!(buffer.replace_input("", 1).is_err());
+    /// ```
+    pub fn replace_input(&self, input: &str, cursor: usize) -> Result<(), ()> {
+        if cursor > input.chars().count() {
+            return Err(());
+        }
+
+        // Weechat limits the cursor to the length of the current input, the
+        // input needs to be set first.
+        self.set_input(input);
+        self.set_input_position(cursor as i32);
+
+        Ok(())
+    }
+
+    /// Enable multiline support.
+    ///
+    /// If enabled multiple lines separated by a newline are sent as a single
+    /// string to the input callback. If disabled each line will be sent to the
+    /// input callback separately.
     pub fn enable_multiline(&self) {
         self.set("input_multiline", &1.to_string());
     }
@@ -1265,6 +3176,18 @@ impl Buffer<'_> {
         self.get_integer("number")
     }
 
+    /// Move the buffer to the given number.
+    ///
+    /// Buffers that are merged with this buffer are moved as well, numbers
+    /// smaller than 1 are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The new number of the buffer.
+    pub fn set_number(&self, number: u16) {
+        self.set("number", &number.to_string());
+    }
+
     /// Switch to the buffer
     pub fn switch_to(&self) {
         self.set("display", "1");
@@ -1275,24 +3198,114 @@ impl Buffer<'_> {
         self.weechat().core_buffer()
     }
 
-    /// Merge two buffers.
-    pub fn merge(&self, target_buffer: &Buffer) {
+    /// Merge the buffer into another buffer.
+    ///
+    /// Merged buffers share a buffer number and are displayed in the same
+    /// window, e.g. the server buffers of the IRC plugin.
+    ///
+    /// Returns an error if the buffers couldn't be merged, e.g. if the
+    /// buffer should be merged into itself or the buffers are of a different
+    /// type.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_buffer` - The buffer this buffer should be merged into.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// let server = BufferBuilder::new("server.example").build().unwrap();
+    /// let channel = BufferBuilder::new("server.example.#rust").build().unwrap();
+    ///
+    /// let server = server.upgrade().unwrap();
+    /// let channel = channel.upgrade().unwrap();
+    ///
+    /// channel.merge(&server).expect("Can't merge the buffers");
+    /// assert!(channel.is_merged());
+    /// ```
+    pub fn merge(&self, target_buffer: &Buffer) -> Result<(), ()> {
         let weechat = self.weechat();
 
-        if target_buffer != self {
-            let merge = weechat.get().buffer_merge.unwrap();
-            unsafe { merge(self.ptr(), target_buffer.ptr()) };
+        if target_buffer == self {
+            return Err(());
         }
+
+        let merge = weechat.get().buffer_merge.unwrap();
+        unsafe { merge(self.ptr(), target_buffer.ptr()) };
+
+        if self.number() == target_buffer.number() {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Is the buffer merged with other buffers.
+    ///
+    /// This walks the list of all buffers, callers that need to know this
+    /// for every buffer should count the buffers per number instead.
+    pub fn is_merged(&self) -> bool {
+        let number = self.number();
+
+        self.weechat()
+            .buffers()
+            .any(|buffer| &buffer != self && buffer.number() == number)
+    }
+
+    /// Get the other buffers that are merged with this buffer, i.e. the
+    /// buffers that share the number of this buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// for merged in buffer.merged_buffers() {
+    ///     if merged.is_active() {
+    ///         buffer.print(&format!("{} is displayed instead", merged.full_name()));
+    ///     }
+    /// }
+    /// ```
+    pub fn merged_buffers(&self) -> Vec<Buffer> {
+        let number = self.number();
+
+        self.weechat()
+            .buffers()
+            .filter(|buffer| buffer != self && buffer.number() == number)
+            .collect()
+    }
+
+    /// Is the buffer active.
+    ///
+    /// Of merged buffers only the active ones are displayed, e.g. after
+    /// switching between them using `/input switch_active_buffer`. Buffers
+    /// that aren't merged are always active.
+    pub fn is_active(&self) -> bool {
+        self.get_integer("active") != 0
+    }
+
+    /// Is the buffer zoomed.
+    ///
+    /// A zoomed buffer is merged with other buffers but only its own lines
+    /// are displayed, see `/buffer zoom`.
+    pub fn is_zoomed(&self) -> bool {
+        self.get_integer("active") == 2
     }
 
     /// Unmerge the buffer if it's merged with other buffers, the buffer will be
     /// moved to the current buffer number + 1.
+    ///
+    /// Unmerging a buffer that isn't merged does nothing.
     pub fn unmerge(&self) {
         self.unmerge_helper(None);
     }
 
     /// Unmerge the buffer if it's merged with other buffers, the buffer will be
     /// moved to target number.
+    ///
+    /// Unmerging a buffer that isn't merged does nothing.
     pub fn unmerge_to(&self, target_number: u16) {
         self.unmerge_helper(Some(target_number));
     }
@@ -1321,9 +3334,9 @@ impl Buffer<'_> {
     /// # let buffer = buffer_handle.upgrade().unwrap();
     ///
     /// // Switch to the core buffer using a command.
-    /// buffer.run_command("/buffer core");
+    /// buffer.run_command("/buffer core").unwrap();
     /// ```
-    pub fn run_command(&self, command: &str) -> Result<(), ()> {
+    pub fn run_command(&self, command: &str) -> Result<(), CommandError> {
         let command = LossyCString::new(command);
         let weechat = self.weechat();
         let run_command = weechat.get().command.unwrap();
@@ -1331,12 +3344,44 @@ impl Buffer<'_> {
         let ret = unsafe { run_command(weechat.ptr, self.ptr(), command.as_ptr()) };
 
         match ret {
-            WEECHAT_RC_OK => Ok(()),
-            WEECHAT_RC_ERROR => Err(()),
-            _ => unreachable!(),
+            WEECHAT_RC_OK | WEECHAT_RC_OK_EAT => Ok(()),
+            ret => Err(CommandError::WeechatError(ret)),
         }
     }
 
+    /// Send text to the buffer as if the user typed it.
+    ///
+    /// Unlike `run_command()` the text is never run as a command, lines
+    /// starting with a `/` or one of the characters in the
+    /// `weechat.look.command_chars` option are escaped so they are sent as
+    /// text. The text may contain multiple lines, each line is sent
+    /// separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text that should be sent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// // Sends the text "/quit is the command to close Weechat" to the
+    /// // buffer, Weechat keeps running.
+    /// buffer.send_text("/quit is the command to close Weechat").unwrap();
+    /// ```
+    pub fn send_text(&self, text: &str) -> Result<(), CommandError> {
+        let weechat = self.weechat();
+
+        let command_chars = match weechat.config_get("weechat.look.command_chars") {
+            Some(ConfigOption::String(option)) => option.value().into_owned(),
+            _ => String::new(),
+        };
+
+        self.run_command(&escape_input(text, &command_chars))
+    }
+
     fn hdata_pointer(&self) -> *mut t_hdata {
         let weechat = self.weechat();
 
@@ -1351,6 +3396,26 @@ impl Buffer<'_> {
         unsafe { weechat.hdata_pointer(hdata, self.ptr() as *mut c_void, "own_lines") }
     }
 
+    /// Get the command history of the buffer, newest entry first.
+    ///
+    /// The history contains the input that was sent to the buffer, its size
+    /// is limited by the `weechat.history.max_commands` option.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximal number of entries that should be returned.
+    pub fn command_history(&self, limit: usize) -> Vec<String> {
+        let weechat = self.weechat();
+
+        unsafe {
+            let first =
+                weechat.hdata_pointer(self.hdata_pointer(), self.ptr() as *mut c_void, "history");
+            let hdata = weechat.hdata_get("history");
+
+            weechat.history_entries(hdata, first, limit)
+        }
+    }
+
     /// Get the number of lines that the buffer has printed out.
     pub fn num_lines(&self) -> i32 {
         let weechat = self.weechat();
@@ -1368,6 +3433,11 @@ impl Buffer<'_> {
     /// traversed forwards (from the first line of the buffer, to the last) as
     /// well as backwards (from the last line of the buffer to the first).
     ///
+    /// Only the lines of this buffer are returned, if the buffer is merged
+    /// with other buffers the lines of the other buffers, which are displayed
+    /// interleaved with the lines of this buffer, aren't part of the
+    /// iterator.
+    ///
     /// # Example
     /// ```no_run
     /// # use weechat::Weechat;
@@ -1382,6 +3452,15 @@ impl Buffer<'_> {
     /// for line in lines {
     ///     Weechat::print(&format!("{:?}", line.tags()));
     /// }
+    ///
+    /// // Get the text of the last 10 lines, oldest line first.
+    /// let mut last_lines: Vec<String> = buffer
+    ///     .lines()
+    ///     .rev()
+    ///     .take(10)
+    ///     .map(|line| line.message_without_colors())
+    ///     .collect();
+    /// last_lines.reverse();
     /// ```
     pub fn lines(&self) -> BufferLines {
         let weechat = self.weechat();
@@ -1418,14 +3497,24 @@ impl Buffer<'_> {
         if ptr.is_null() {
             None
         } else {
-            Some(Window {
-                weechat: weechat.ptr,
-                ptr,
-                phantom: PhantomData,
-            })
+            Some(Window { weechat, ptr })
         }
     }
 
+    /// Get all the windows that are currently displaying this buffer.
+    ///
+    /// The windows are returned in the order of their numbers, the list is
+    /// empty if no window is displaying this buffer.
+    pub fn windows(&self) -> Vec<Window> {
+        let weechat = self.weechat();
+
+        weechat
+            .windows()
+            .into_iter()
+            .filter(|window| window.buffer().ptr() == self.ptr())
+            .collect()
+    }
+
     /// Hide the buffer from the buflist.
     pub fn hide(&self) {
         self.set("hidden", "1");
@@ -1435,4 +3524,337 @@ impl Buffer<'_> {
     pub fn unhide(&self) {
         self.set("hidden", "0");
     }
+
+    /// Hide or unhide the buffer from the buflist.
+    ///
+    /// This is the same as calling `hide()` or `unhide()`, a hidden buffer
+    /// stays open but isn't shown in the buflist and is skipped when
+    /// switching buffers, like with the `/buffer hide` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden` - True if the buffer should be hidden, false otherwise.
+    pub fn set_hidden(&self, hidden: bool) {
+        self.set("hidden", if hidden { "1" } else { "0" });
+    }
+
+    /// Is the buffer hidden from the buflist.
+    pub fn is_hidden(&self) -> bool {
+        self.get_integer("hidden") == 1
+    }
+
+    /// Bind a key that is only active while this buffer is displayed.
+    ///
+    /// Buffer key bindings take precedence over the global key bindings,
+    /// the key is passed to Weechat as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key that should be bound, in the format of the `/key`
+    ///     command, e.g. `meta-j` or `ctrl-x`.
+    ///
+    /// * `command` - The command that should be run when the key is pressed,
+    ///     e.g. `/mylist select next`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("list").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// buffer.bind_key("meta2-A", "/mylist up");
+    /// buffer.bind_key("meta2-B", "/mylist down");
+    /// buffer.bind_key("ctrl-M", "/mylist select");
+    /// ```
+    pub fn bind_key(&self, key: &str, command: &str) {
+        self.set(&format!("key_bind_{}", key), command);
+    }
+
+    /// Remove a key binding of this buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key that should be unbound, the special value `*`
+    ///     removes all the key bindings of the buffer.
+    pub fn unbind_key(&self, key: &str) {
+        self.set(&format!("key_unbind_{}", key), "");
+    }
+
+    /// Add the buffer to the hotlist.
+    ///
+    /// If the buffer is already in the hotlist with a higher priority the
+    /// priority won't be lowered. Like for printed lines, Weechat doesn't add
+    /// the buffer if it's currently displayed in a window.
+    ///
+    /// This can be used to signal activity in a buffer without printing a
+    /// line, e.g. for presence events.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority with which the buffer should be added to
+    ///     the hotlist.
+    pub fn add_to_hotlist(&self, priority: HotlistPriority) {
+        self.set("hotlist", priority.as_str());
+    }
+
+    /// Remove the buffer from the hotlist, e.g. to mark the buffer as read.
+    ///
+    /// Use `Weechat::hotlist_clear_all()` to remove all buffers from the
+    /// hotlist.
+    pub fn clear_hotlist(&self) {
+        self.set("hotlist", "-1");
+    }
+
+    /// Set the priority of the buffer in the hotlist.
+    ///
+    /// Unlike `add_to_hotlist()` this lowers the priority as well, the buffer
+    /// is removed from the hotlist and added again with the given priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The new priority of the buffer, `None` removes the
+    ///     buffer from the hotlist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::{BufferBuilder, HotlistPriority};
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// // The highlight was read elsewhere, keep the buffer marked as unread.
+    /// if buffer.hotlist_priority() == Some(HotlistPriority::Highlight) {
+    ///     buffer.set_hotlist(Some(HotlistPriority::Message));
+    /// }
+    /// ```
+    pub fn set_hotlist(&self, priority: Option<HotlistPriority>) {
+        self.clear_hotlist();
+
+        if let Some(priority) = priority {
+            self.add_to_hotlist(priority);
+        }
+    }
+
+    /// Get the priority of the buffer in the hotlist.
+    ///
+    /// Returns `None` if the buffer isn't in the hotlist.
+    pub fn hotlist_priority(&self) -> Option<HotlistPriority> {
+        let weechat = self.weechat();
+
+        unsafe {
+            let hotlist =
+                weechat.hdata_pointer(self.hdata_pointer(), self.ptr() as *mut c_void, "hotlist");
+
+            if hotlist.is_null() {
+                return None;
+            }
+
+            let hdata = weechat.hdata_get("hotlist");
+            HotlistPriority::from_raw(weechat.hdata_integer(hdata, hotlist, "priority"))
+        }
+    }
+
+    /// Set the unread marker after the last line of the buffer.
+    pub fn unread_marker(&self) {
+        self.set("unread", "");
+    }
+
+    /// Mark the buffer as read.
+    ///
+    /// This sets the unread marker after the last line of the buffer and
+    /// removes the buffer from the hotlist. This works for any buffer, the
+    /// buffer doesn't need to be displayed in a window.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// // The messages were read on another client.
+    /// if buffer.unread_lines_count() > 0 {
+    ///     buffer.mark_read();
+    /// }
+    /// ```
+    pub fn mark_read(&self) {
+        self.unread_marker();
+        self.clear_hotlist();
+    }
+
+    /// Get the number of lines that were printed after the unread marker.
+    ///
+    /// Returns 0 if the buffer has no unread marker. If the marker was set
+    /// while the buffer was empty it sits before the first line, every line
+    /// of the buffer is counted in that case. For merged buffers the lines of
+    /// all the merged buffers are counted.
+    pub fn unread_lines_count(&self) -> usize {
+        let weechat = self.weechat();
+
+        unsafe {
+            let lines =
+                weechat.hdata_pointer(self.hdata_pointer(), self.ptr() as *mut c_void, "lines");
+
+            if lines.is_null() {
+                return 0;
+            }
+
+            let lines_hdata = weechat.hdata_get("lines");
+            let line_hdata = weechat.hdata_get("line");
+
+            let last_read_line = weechat.hdata_pointer(lines_hdata, lines, "last_read_line");
+
+            // Without a read line the marker is either missing, then no line
+            // is unread, or it was set on an empty buffer and every line is
+            // unread.
+            let mut line = if last_read_line.is_null() {
+                if weechat.hdata_integer(lines_hdata, lines, "first_line_not_read") == 0 {
+                    return 0;
+                }
+
+                weechat.hdata_pointer(lines_hdata, lines, "first_line")
+            } else {
+                weechat.hdata_pointer(line_hdata, last_read_line, "next_line")
+            };
+
+            let mut count = 0;
+
+            while !line.is_null() {
+                count += 1;
+                line = weechat.hdata_pointer(line_hdata, line, "next_line");
+            }
+
+            count
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(policy: &mut MultilinePolicy, input: &str) -> Vec<String> {
+        split_input(policy, &mut None, input, |_, _| true, |_, _| ())
+    }
+
+    #[test]
+    fn single_lines_are_passed_unchanged() {
+        let mut policy = MultilinePolicy::SplitLines;
+
+        assert_eq!(split(&mut policy, "hello"), vec!["hello"]);
+        assert_eq!(split(&mut policy, ""), vec![""]);
+        assert_eq!(split(&mut policy, "hello\n"), vec!["hello"]);
+    }
+
+    #[test]
+    fn crlf_is_normalized() {
+        assert_eq!(
+            split(&mut MultilinePolicy::SendAsOne, "a\r\nb\r\n"),
+            vec!["a\nb"]
+        );
+        assert_eq!(
+            split(&mut MultilinePolicy::SplitLines, "a\r\nb\r\n"),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn blank_lines() {
+        assert_eq!(
+            split(&mut MultilinePolicy::SendAsOne, "a\n\n\nb\n\n"),
+            vec!["a\n\n\nb"]
+        );
+        assert_eq!(
+            split(&mut MultilinePolicy::SplitLines, "a\n\r\n\nb\n\n"),
+            vec!["a", "b"]
+        );
+        assert!(split(&mut MultilinePolicy::SendAsOne, "\n\n").is_empty());
+        assert!(split(&mut MultilinePolicy::SplitLines, "\r\n").is_empty());
+    }
+
+    #[test]
+    fn confirm_is_asked_for_multiple_lines_only() {
+        let mut policy = MultilinePolicy::confirm(|_: &Weechat, _: &Buffer, _: &str| true);
+        let mut asked = Vec::new();
+
+        let mut confirm = |input: &str, answer: bool| {
+            split_input(
+                &mut policy,
+                &mut None,
+                input,
+                |_, input| {
+                    asked.push(input.to_owned());
+                    answer
+                },
+                |_, _| (),
+            )
+        };
+
+        assert_eq!(confirm("a\r\nb\n", true), vec!["a\nb"]);
+        assert!(confirm("a\nb", false).is_empty());
+        assert_eq!(confirm("a\n", false), vec!["a"]);
+        assert_eq!(asked, vec!["a\nb", "a\nb"]);
+    }
+
+    #[test]
+    fn long_messages_are_rejected() {
+        let mut guard = Some(InputLengthGuard {
+            max_length: 3,
+            callback: Box::new(|_: &Weechat, _: &Buffer, _: &str| {}),
+        });
+        let mut rejected = Vec::new();
+
+        let messages = split_input(
+            &mut MultilinePolicy::SplitLines,
+            &mut guard,
+            "ab\nabcd\n\nabc",
+            |_, _| true,
+            |_, message| rejected.push(message.to_owned()),
+        );
+
+        assert_eq!(messages, vec!["ab", "abc"]);
+        assert_eq!(rejected, vec!["abcd"]);
+    }
+
+    #[test]
+    fn leading_slash_is_escaped() {
+        assert_eq!(escape_input("/quit", ""), "//quit");
+        assert_eq!(escape_input("/quit now", "."), "//quit now");
+        assert_eq!(escape_input("hello /quit", ""), "hello /quit");
+    }
+
+    #[test]
+    fn double_slash_is_escaped() {
+        assert_eq!(escape_input("//", ""), "///");
+        assert_eq!(escape_input("//quit", ""), "///quit");
+    }
+
+    #[test]
+    fn multiple_lines_are_escaped() {
+        assert_eq!(
+            escape_input("hello\n/quit\n\nworld\n/", ""),
+            "hello\n//quit\n\nworld\n//"
+        );
+    }
+
+    #[test]
+    fn command_chars_are_escaped() {
+        assert_eq!(escape_input(".quit", ".!"), "..quit");
+        assert_eq!(escape_input("!quit\n/quit", ".!"), "!!quit\n//quit");
+        assert_eq!(escape_input("§quit", "§"), "§§quit");
+        assert_eq!(escape_input(".quit", ""), ".quit");
+    }
+
+    #[test]
+    fn highlight_words_are_joined() {
+        assert_eq!(
+            Buffer::join_highlight_words(&["alice", "bob"]),
+            Ok("alice,bob".to_owned())
+        );
+        assert_eq!(Buffer::join_highlight_words(&[]), Ok(String::new()));
+    }
+
+    #[test]
+    fn highlight_words_with_commas_are_rejected() {
+        assert_eq!(Buffer::join_highlight_words(&["alice,bob"]), Err(()));
+        assert_eq!(Buffer::join_highlight_words(&["alice", ","]), Err(()));
+    }
 }
@@ -1,7 +1,10 @@
-use std::{borrow::Cow, ffi::CStr, marker::PhantomData};
+use std::{borrow::Cow, ffi::CStr, marker::PhantomData, ptr};
 
-use crate::{buffer::Buffer, LossyCString, Weechat};
-use weechat_sys::{t_gui_buffer, t_gui_nick, t_weechat_plugin};
+use crate::{
+    buffer::{Buffer, NickGroup},
+    LossyCString, Weechat,
+};
+use weechat_sys::{t_gui_buffer, t_gui_nick, t_gui_nick_group, t_weechat_plugin};
 
 /// Settings to create a new nick.
 pub struct NickSettings<'a> {
@@ -108,6 +111,22 @@ impl<'a> Nick<'a> {
         }
     }
 
+    fn set(&self, property: &str, value: &str) {
+        let weechat = self.get_weechat();
+        let nick_set = weechat.get().nicklist_nick_set.unwrap();
+        let c_property = LossyCString::new(property);
+        let c_value = LossyCString::new(value);
+
+        unsafe {
+            nick_set(
+                self.buf_ptr,
+                self.ptr,
+                c_property.as_ptr(),
+                c_value.as_ptr(),
+            )
+        }
+    }
+
     /// Get the name property of the nick.
     pub fn name(&self) -> Cow<str> {
         self.get_string("name").unwrap()
@@ -127,4 +146,151 @@ impl<'a> Nick<'a> {
     pub fn prefix_color(&self) -> Cow<str> {
         self.get_string("prefix_color").unwrap()
     }
+
+    /// Is the nick visible in the nicklist.
+    pub fn visible(&self) -> bool {
+        let weechat = self.get_weechat();
+        let get_integer = weechat.get().nicklist_nick_get_integer.unwrap();
+        let c_property = LossyCString::new("visible");
+
+        unsafe { get_integer(self.buf_ptr, self.ptr, c_property.as_ptr()) != 0 }
+    }
+
+    /// Get the group the nick belongs to.
+    pub fn group(&self) -> NickGroup<'a> {
+        let weechat = self.get_weechat();
+        let get_pointer = weechat.get().nicklist_nick_get_pointer.unwrap();
+        let c_property = LossyCString::new("group");
+
+        let group = unsafe { get_pointer(self.buf_ptr, self.ptr, c_property.as_ptr()) };
+
+        NickGroup {
+            ptr: group as *mut t_gui_nick_group,
+            buf_ptr: self.buf_ptr,
+            weechat_ptr: self.weechat_ptr,
+            buffer: PhantomData,
+        }
+    }
+
+    /// Set the color of the nick.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new color of the nick.
+    pub fn set_color(&self, color: &str) {
+        self.set("color", color)
+    }
+
+    /// Set the prefix of the nick.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The new prefix displayed before the nick in the nicklist.
+    pub fn set_prefix(&self, prefix: &str) {
+        self.set("prefix", prefix)
+    }
+
+    /// Set the color of the nick prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix_color` - The new color of the prefix.
+    pub fn set_prefix_color(&self, prefix_color: &str) {
+        self.set("prefix_color", prefix_color)
+    }
+
+    /// Show or hide the nick in the nicklist.
+    ///
+    /// # Arguments
+    ///
+    /// * `visible` - Should the nick be visible in the nicklist.
+    pub fn set_visible(&self, visible: bool) {
+        self.set("visible", if visible { "1" } else { "0" })
+    }
+
+    /// Remove the nick from the nicklist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// if let Some(nick) = buffer.search_nick("alice") {
+    ///     nick.remove();
+    /// }
+    /// ```
+    pub fn remove(self) {
+        let weechat = self.get_weechat();
+        let nicklist_remove_nick = weechat.get().nicklist_remove_nick.unwrap();
+
+        unsafe { nicklist_remove_nick(self.buf_ptr, self.ptr) };
+    }
+}
+
+/// An iterator over the nicks of a nicklist.
+///
+/// The iterator is created by `Buffer::nicks()` or `NickGroup::nicks()`. It
+/// walks the nicklist lazily, nicks and groups must not be removed from the
+/// nicklist while iterating over it.
+pub struct Nicks<'a> {
+    pub(crate) buf_ptr: *mut t_gui_buffer,
+    pub(crate) weechat_ptr: *mut t_weechat_plugin,
+    pub(crate) group: Option<*mut t_gui_nick_group>,
+    pub(crate) current_group: *mut t_gui_nick_group,
+    pub(crate) current_nick: *mut t_gui_nick,
+    pub(crate) done: bool,
+    pub(crate) buffer: PhantomData<&'a Buffer<'a>>,
+}
+
+impl<'a> Nicks<'a> {
+    pub(crate) fn new(
+        buf_ptr: *mut t_gui_buffer,
+        weechat_ptr: *mut t_weechat_plugin,
+        group: Option<*mut t_gui_nick_group>,
+    ) -> Self {
+        Nicks {
+            buf_ptr,
+            weechat_ptr,
+            group,
+            current_group: ptr::null_mut(),
+            current_nick: ptr::null_mut(),
+            done: false,
+            buffer: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for Nicks<'a> {
+    type Item = Nick<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let weechat = Weechat::from_ptr(self.weechat_ptr);
+        let get_next_item = weechat.get().nicklist_get_next_item.unwrap();
+
+        while !self.done {
+            unsafe {
+                get_next_item(
+                    self.buf_ptr,
+                    &mut self.current_group,
+                    &mut self.current_nick,
+                )
+            };
+
+            if self.current_group.is_null() && self.current_nick.is_null() {
+                self.done = true;
+            } else if !self.current_nick.is_null()
+                && (self.group.is_none() || self.group == Some(self.current_group))
+            {
+                return Some(Nick {
+                    ptr: self.current_nick,
+                    buf_ptr: self.buf_ptr,
+                    weechat_ptr: self.weechat_ptr,
+                    buffer: PhantomData,
+                });
+            }
+        }
+
+        None
+    }
 }
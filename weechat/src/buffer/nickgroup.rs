@@ -1,13 +1,16 @@
-use std::{borrow::Cow, ffi::CStr, marker::PhantomData};
+use std::{borrow::Cow, ffi::CStr, marker::PhantomData, os::raw::c_void};
 
 use weechat_sys::{t_gui_buffer, t_gui_nick_group, t_weechat_plugin};
 
 use crate::{
-    buffer::{Buffer, Nick, NickSettings},
+    buffer::{Buffer, Nick, NickSettings, Nicks},
     LossyCString, Weechat,
 };
 
 /// Weechat nicklist Group type.
+///
+/// The group is owned by Weechat, dropping the object doesn't remove the group
+/// from the nicklist, use `remove()` for that.
 pub struct NickGroup<'a> {
     pub(crate) ptr: *mut t_gui_nick_group,
     pub(crate) buf_ptr: *mut t_gui_buffer,
@@ -46,6 +49,22 @@ impl<'a> NickGroup<'a> {
         unsafe { get_integer(self.buf_ptr, self.ptr, c_property.as_ptr()) }
     }
 
+    fn set(&self, property: &str, value: &str) {
+        let weechat = self.get_weechat();
+        let group_set = weechat.get().nicklist_group_set.unwrap();
+        let c_property = LossyCString::new(property);
+        let c_value = LossyCString::new(value);
+
+        unsafe {
+            group_set(
+                self.buf_ptr,
+                self.ptr,
+                c_property.as_ptr(),
+                c_value.as_ptr(),
+            )
+        }
+    }
+
     /// Get the name of the group.
     pub fn name(&self) -> Cow<str> {
         self.get_string("name").unwrap()
@@ -61,6 +80,24 @@ impl<'a> NickGroup<'a> {
         self.get_integer("visible") != 0
     }
 
+    /// Set the color of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new color of the group.
+    pub fn set_color(&self, color: &str) {
+        self.set("color", color)
+    }
+
+    /// Show or hide the group in the nicklist.
+    ///
+    /// # Arguments
+    ///
+    /// * `visible` - Should the group be visible in the nicklist.
+    pub fn set_visible(&self, visible: bool) {
+        self.set("visible", if visible { "1" } else { "0" })
+    }
+
     /// Get the group nesting level.
     ///
     /// Returns 0 if this is the root group, 1 if it's a child of the root
@@ -69,6 +106,22 @@ impl<'a> NickGroup<'a> {
         self.get_integer("level") as u32
     }
 
+    /// Remove the group, its subgroups and all their nicks from the nicklist.
+    ///
+    /// Removing the root group removes all groups and nicks from the
+    /// nicklist, the root group itself is kept.
+    pub fn remove(self) {
+        let weechat = self.get_weechat();
+
+        if self.level() == 0 {
+            let nicklist_remove_all = weechat.get().nicklist_remove_all.unwrap();
+            unsafe { nicklist_remove_all(self.buf_ptr) };
+        } else {
+            let nicklist_remove_group = weechat.get().nicklist_remove_group.unwrap();
+            unsafe { nicklist_remove_group(self.buf_ptr, self.ptr) };
+        }
+    }
+
     /// Create and add a new nick to the buffer nicklist under this group.
     ///
     /// # Arguments
@@ -77,7 +130,8 @@ impl<'a> NickGroup<'a> {
     ///     added.
     ///
     /// Returns the newly created nick if one is created successfully, an empty
-    /// error otherwise.
+    /// error otherwise. Adding a nick that is already in the nicklist of the
+    /// buffer fails, use `Nick` setters to update an existing nick.
     pub fn add_nick(&self, nick_settings: NickSettings) -> Result<Nick, ()> {
         let weechat = self.get_weechat();
         let nick_ptr = Buffer::add_nick_helper(&weechat, self.buf_ptr, nick_settings, Some(self));
@@ -94,8 +148,51 @@ impl<'a> NickGroup<'a> {
         })
     }
 
+    /// Get an iterator over the nicks of this group.
+    ///
+    /// Only nicks that are direct members of this group are returned, nicks
+    /// of subgroups are skipped. Nicks must not be removed from the nicklist
+    /// while iterating.
+    pub fn nicks(&self) -> Nicks<'a> {
+        Nicks::new(self.buf_ptr, self.weechat_ptr, Some(self.ptr))
+    }
+
+    /// Get an iterator over the direct subgroups of this group.
+    ///
+    /// Subgroups of the subgroups are not returned, call `children()` on the
+    /// returned groups to walk the whole tree of groups. Groups must not be
+    /// removed from the nicklist while iterating.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test_buffer").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// for group in buffer.nicklist_root().children() {
+    ///     buffer.print(&format!("{}: {} nicks", group.name(), group.nicks().count()));
+    /// }
+    /// ```
+    pub fn children(&self) -> NickGroups<'a> {
+        let weechat = self.get_weechat();
+
+        let first_child = unsafe {
+            let hdata = weechat.hdata_get("nick_group");
+            weechat.hdata_pointer(hdata, self.ptr as *mut c_void, "children")
+        };
+
+        NickGroups {
+            buf_ptr: self.buf_ptr,
+            weechat_ptr: self.weechat_ptr,
+            current: first_child as *mut t_gui_nick_group,
+            buffer: PhantomData,
+        }
+    }
+
     /// Search for a nick in this nick group.
     ///
+    /// Nicks of the subgroups of this group are found as well.
+    ///
     /// # Arguments
     ///
     /// * `nick` - The name of the nick that should be found.
@@ -103,7 +200,7 @@ impl<'a> NickGroup<'a> {
     /// Returns a `Nick` if one is found, None otherwise.
     pub fn search_nick(&self, nick: &str) -> Option<Nick> {
         let weechat = self.get_weechat();
-        let nick = Buffer::search_nick_helper(&weechat, self.buf_ptr, nick, None);
+        let nick = Buffer::search_nick_helper(&weechat, self.buf_ptr, nick, Some(self));
 
         if nick.is_null() {
             None
@@ -117,3 +214,40 @@ impl<'a> NickGroup<'a> {
         }
     }
 }
+
+/// An iterator over the subgroups of a nick group.
+///
+/// The iterator is created by `NickGroup::children()`, it walks the linked
+/// list of groups lazily.
+pub struct NickGroups<'a> {
+    buf_ptr: *mut t_gui_buffer,
+    weechat_ptr: *mut t_weechat_plugin,
+    current: *mut t_gui_nick_group,
+    buffer: PhantomData<&'a Buffer<'a>>,
+}
+
+impl<'a> Iterator for NickGroups<'a> {
+    type Item = NickGroup<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current.is_null() {
+            return None;
+        }
+
+        let weechat = Weechat::from_ptr(self.weechat_ptr);
+        let group = self.current;
+
+        self.current = unsafe {
+            let hdata = weechat.hdata_get("nick_group");
+            weechat.hdata_pointer(hdata, group as *mut c_void, "next_group")
+                as *mut t_gui_nick_group
+        };
+
+        Some(NickGroup {
+            ptr: group,
+            buf_ptr: self.buf_ptr,
+            weechat_ptr: self.weechat_ptr,
+            buffer: PhantomData,
+        })
+    }
+}
@@ -1,6 +1,6 @@
-use std::{marker::PhantomData, ptr};
+use std::{ffi::c_void, ptr};
 
-use weechat_sys::{t_gui_window, t_weechat_plugin};
+use weechat_sys::{t_gui_buffer, t_gui_window};
 
 use super::Buffer;
 use crate::{LossyCString, Weechat};
@@ -10,20 +10,98 @@ use crate::{LossyCString, Weechat};
 /// A window is a screen area which displays a buffer. It is possible to split
 /// your screen into many windows.
 pub struct Window<'a> {
-    pub(crate) weechat: *mut t_weechat_plugin,
+    pub(crate) weechat: &'a Weechat,
     pub(crate) ptr: *mut t_gui_window,
-    pub(crate) phantom: PhantomData<&'a Buffer<'a>>,
+}
+
+impl std::fmt::Debug for Window<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Window")
+            .field("number", &self.number())
+            .finish()
+    }
+}
+
+impl Weechat {
+    /// Get the window that is currently focused.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let window = weechat.current_window();
+    /// let buffer = window.buffer();
+    ///
+    /// buffer.print(&format!(
+    ///     "This window is {}x{} chars big",
+    ///     window.width(),
+    ///     window.height()
+    /// ));
+    /// ```
+    pub fn current_window(&self) -> Window<'_> {
+        let get_pointer = self.get().window_get_pointer.unwrap();
+        let property = LossyCString::new("current");
+
+        let ptr = unsafe { get_pointer(ptr::null_mut(), property.as_ptr()) };
+
+        Window {
+            weechat: self,
+            ptr: ptr as *mut t_gui_window,
+        }
+    }
+
+    /// Get all the windows that are currently open.
+    ///
+    /// The windows are returned in the order of their numbers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// for window in weechat.windows() {
+    ///     Weechat::print(&format!(
+    ///         "Window {} displays {}",
+    ///         window.number(),
+    ///         window.buffer().full_name()
+    ///     ));
+    /// }
+    /// ```
+    pub fn windows(&self) -> Vec<Window<'_>> {
+        let mut windows = Vec::new();
+
+        unsafe {
+            let hdata = self.hdata_get("window");
+            let mut window = self.hdata_get_list(hdata, "gui_windows");
+
+            while !window.is_null() {
+                windows.push(Window {
+                    weechat: self,
+                    ptr: window as *mut t_gui_window,
+                });
+                window = self.hdata_move(hdata, window, 1);
+            }
+        }
+
+        windows
+    }
 }
 
 impl<'a> Window<'a> {
     fn get_integer(&self, property: &str) -> i32 {
-        let weechat = Weechat::from_ptr(self.weechat);
-        let get_integer = weechat.get().window_get_integer.unwrap();
+        let get_integer = self.weechat.get().window_get_integer.unwrap();
         let property = LossyCString::new(property);
 
         unsafe { get_integer(self.ptr, property.as_ptr()) }
     }
 
+    fn get_pointer(&self, property: &str) -> *mut c_void {
+        let get_pointer = self.weechat.get().window_get_pointer.unwrap();
+        let property = LossyCString::new(property);
+
+        unsafe { get_pointer(self.ptr, property.as_ptr()) }
+    }
+
     fn get_bool(&self, property: &str) -> bool {
         self.get_integer(property) == 1
     }
@@ -33,6 +111,12 @@ impl<'a> Window<'a> {
         self.get_integer("number")
     }
 
+    /// The buffer that is displayed in the window.
+    pub fn buffer(&self) -> Buffer<'a> {
+        let buffer = self.get_pointer("buffer") as *mut t_gui_buffer;
+        self.weechat.buffer_from_ptr(buffer)
+    }
+
     /// The X coordinate position of the window in the terminal (the first
     /// column is 0).
     pub fn x(&self) -> i32 {
@@ -100,6 +184,26 @@ impl<'a> Window<'a> {
     /// Returns true if the last line of the buffer is shown in the window, or
     /// to put it differently if the window is scrolled completely down.
     pub fn is_last_line_displayed(&self) -> bool {
+        !self.is_scrolled()
+    }
+
+    /// Returns true if the window is scrolled up, i.e. the last line of the
+    /// buffer isn't shown in the window.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::buffer::{BufferBuilder, HotlistPriority};
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// // Only skip the notification if the user can see the new message.
+    /// let seen = buffer.windows().iter().any(|w| !w.is_scrolled());
+    ///
+    /// if !seen {
+    ///     buffer.add_to_hotlist(HotlistPriority::Highlight);
+    /// }
+    /// ```
+    pub fn is_scrolled(&self) -> bool {
         self.get_bool("scrolling")
     }
 
@@ -110,8 +214,7 @@ impl<'a> Window<'a> {
     }
 
     fn set_title_helper(&self, title: Option<&str>) {
-        let weechat = Weechat::from_ptr(self.weechat);
-        let set_title = weechat.get().window_set_title.unwrap();
+        let set_title = self.weechat.get().window_set_title.unwrap();
 
         if let Some(title) = title {
             let title = LossyCString::new(title);
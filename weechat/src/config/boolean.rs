@@ -96,6 +96,14 @@ impl<'a> BooleanOption<'a> {
         let ret = unsafe { config_boolean(self.get_ptr()) };
         ret != 0
     }
+
+    /// Get the default value of the option.
+    pub fn default_value(&self) -> bool {
+        let weechat = self.get_weechat();
+        let config_boolean_default = weechat.get().config_boolean_default.unwrap();
+        let ret = unsafe { config_boolean_default(self.get_ptr()) };
+        ret != 0
+    }
 }
 
 impl<'a> FromPtrs for BooleanOption<'a> {
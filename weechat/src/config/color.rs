@@ -97,6 +97,24 @@ impl<'a> ColorOption<'a> {
             CStr::from_ptr(string).to_string_lossy()
         }
     }
+
+    /// Get the default value of the option.
+    ///
+    /// Returns an empty string if the default value is null, use
+    /// `default_is_null()` to tell the two apart.
+    pub fn default_value(&self) -> Cow<str> {
+        let weechat = self.get_weechat();
+        let config_color_default = weechat.get().config_color_default.unwrap();
+        unsafe {
+            let string = config_color_default(self.get_ptr());
+
+            if string.is_null() {
+                Cow::from("")
+            } else {
+                CStr::from_ptr(string).to_string_lossy()
+            }
+        }
+    }
 }
 
 impl<'a> FromPtrs for ColorOption<'a> {
@@ -26,8 +26,14 @@ use crate::{
     LossyCString, Weechat,
 };
 
+#[cfg(feature = "fuzzy")]
+use crate::{infolist::InfolistVariable, util::fuzzy_score};
+#[cfg(feature = "fuzzy")]
+use std::cmp::Reverse;
+
 /// Weechat configuration file
 pub struct Config {
+    name: String,
     inner: Conf,
     _config_data: *mut ConfigPointers,
     sections: HashMap<String, Rc<RefCell<ConfigSection>>>,
@@ -183,7 +189,10 @@ impl Drop for Config {
         unsafe {
             // Now drop the config.
             Box::from_raw(self._config_data);
-            config_free(self.inner.ptr)
+
+            if !Weechat::is_unloading() {
+                config_free(self.inner.ptr)
+            }
         };
     }
 }
@@ -297,6 +306,7 @@ impl Config {
         };
 
         Ok(Config {
+            name: name.to_owned(),
             inner: Conf {
                 ptr: config_ptr,
                 weechat_ptr: weechat.ptr,
@@ -607,6 +617,176 @@ impl Config {
             })
         }
     }
+
+    /// Get the name of the configuration file.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Search the options of the configuration file using fuzzy matching.
+    ///
+    /// The pattern is matched against the `section.option` name of every
+    /// option of this configuration file. If the pattern is prefixed with the
+    /// name of an existing configuration file, e.g. `weechat.` or `irc.`, the
+    /// options of that configuration file are searched instead. Any other
+    /// prefix, e.g. the section in `look.color`, is part of the pattern.
+    ///
+    /// Returns a list of the score, section name, option name and the option
+    /// itself for every matching option. The list is sorted so that the best
+    /// match comes first, options with the same score are sorted by their
+    /// name.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern the options should be matched against.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if it is being called in a section read/write callback
+    /// of this configuration file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::config::Config;
+    /// # let config = Config::new("my_plugin").unwrap();
+    /// for (_, section, name, option) in config.search_options_fuzzy("weechat.lk.bfr") {
+    ///     println!("{}.{}: {:?}", section, name, option.value());
+    /// }
+    /// ```
+    #[cfg(feature = "fuzzy")]
+    #[cfg_attr(feature = "docs", doc(cfg(fuzzy)))]
+    pub fn search_options_fuzzy(&self, pattern: &str) -> Vec<(i64, String, String, ConfigOption)> {
+        let mut options = match pattern.split_once('.') {
+            Some((file, pattern)) if file == self.name => self.search_own_options_fuzzy(pattern),
+            Some((file, pattern)) if Config::config_file_exists(file) => {
+                Config::search_foreign_options_fuzzy(file, pattern)
+            }
+            _ => self.search_own_options_fuzzy(pattern),
+        };
+
+        // The options are collected from hash maps, sort options with the
+        // same score by name so the order is stable.
+        options.sort_by(
+            |(score, section, name, _), (other_score, other_section, other_name, _)| {
+                Reverse(score)
+                    .cmp(&Reverse(other_score))
+                    .then_with(|| (section, name).cmp(&(other_section, other_name)))
+            },
+        );
+
+        options
+    }
+
+    #[cfg(feature = "fuzzy")]
+    fn config_file_exists(name: &str) -> bool {
+        let weechat = unsafe { Weechat::weechat() };
+
+        unsafe {
+            let hdata = weechat.hdata_get("config_file");
+            let mut config_file = weechat.hdata_get_list(hdata, "config_files");
+
+            while !config_file.is_null() {
+                if weechat.hdata_string(hdata, config_file, "name") == name {
+                    return true;
+                }
+
+                config_file = weechat.hdata_move(hdata, config_file, 1);
+            }
+        }
+
+        false
+    }
+
+    #[cfg(feature = "fuzzy")]
+    fn search_own_options_fuzzy(&self, pattern: &str) -> Vec<(i64, String, String, ConfigOption)> {
+        let weechat = Weechat::from_ptr(self.inner.weechat_ptr);
+        let config_search_option = weechat.get().config_search_option.unwrap();
+
+        let mut options = Vec::new();
+
+        for section in self.sections.values() {
+            let section = section.borrow();
+
+            for option_name in section.option_pointers.keys() {
+                let full_name = format!("{}.{}", section.name(), option_name);
+
+                let score = if let Some(score) = fuzzy_score(pattern, &full_name) {
+                    score
+                } else {
+                    continue;
+                };
+
+                let name = LossyCString::new(option_name.as_str());
+                let ptr =
+                    unsafe { config_search_option(self.inner.ptr, section.ptr, name.as_ptr()) };
+
+                if ptr.is_null() {
+                    continue;
+                }
+
+                let option_type = weechat.config_option_get_string(ptr, "type").unwrap();
+
+                options.push((
+                    score,
+                    section.name().to_owned(),
+                    option_name.to_owned(),
+                    Config::option_from_type_and_ptr(self.inner.weechat_ptr, ptr, &option_type),
+                ));
+            }
+        }
+
+        options
+    }
+
+    #[cfg(feature = "fuzzy")]
+    fn search_foreign_options_fuzzy<'a>(
+        config_name: &str,
+        pattern: &str,
+    ) -> Vec<(i64, String, String, ConfigOption<'a>)> {
+        let weechat = unsafe { Weechat::weechat() };
+
+        let infolist = if let Ok(infolist) =
+            weechat.get_infolist("option", Some(&format!("{}.*", config_name)))
+        {
+            infolist
+        } else {
+            return Vec::new();
+        };
+
+        let mut options = Vec::new();
+
+        for item in infolist {
+            let full_name = if let Some(InfolistVariable::String(name)) = item.get("full_name") {
+                name.to_string()
+            } else {
+                continue;
+            };
+
+            let (section_name, option_name) = match full_name
+                .strip_prefix(config_name)
+                .and_then(|n| n.strip_prefix('.'))
+                .and_then(|n| n.split_once('.'))
+            {
+                Some((section, option)) => (section.to_owned(), option.to_owned()),
+                None => continue,
+            };
+
+            let score = if let Some(score) =
+                fuzzy_score(pattern, &format!("{}.{}", section_name, option_name))
+            {
+                score
+            } else {
+                continue;
+            };
+
+            if let Some(option) = weechat.config_get(&full_name) {
+                options.push((score, section_name, option_name, option));
+            }
+        }
+
+        options
+    }
 }
 
 impl Conf {
@@ -1,5 +1,10 @@
 use crate::{config::OptionChanged, LossyCString, Weechat};
-use std::{borrow::Cow, convert::TryFrom, ffi::CStr};
+use libc::{c_char, c_int};
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    ffi::{c_void, CStr},
+};
 use weechat_sys::{t_config_option, t_weechat_plugin};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -37,6 +42,19 @@ impl OptionType {
     }
 }
 
+/// The value of a config option, decoded into the type of the option.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OptionValue {
+    /// The value of a boolean option.
+    Boolean(bool),
+    /// The value of an integer option.
+    Integer(i32),
+    /// The value of a string option.
+    String(String),
+    /// The value of a color option, the name of the color.
+    Color(String),
+}
+
 impl Default for OptionType {
     fn default() -> Self {
         OptionType::String
@@ -52,7 +70,39 @@ pub trait HidenConfigOptionT {
     /// Returns the raw pointer to the config option.
     fn get_ptr(&self) -> *mut t_config_option;
     fn get_weechat(&self) -> Weechat;
+}
 
+/// Base configuration option methods.
+///
+/// These methods are implemented for every option and don't depend on the
+/// option type.
+pub trait BaseConfigOption: HidenConfigOptionT {
+    /// Get a string property of the option.
+    ///
+    /// This is a low level method, the typed methods like `name()` or
+    /// `description()` should be preferred if one exists for the property.
+    ///
+    /// Returns `None` if the property doesn't exist or isn't set.
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - The name of the property, one of `config_name`,
+    ///     `section_name`, `name`, `parent_name`, `type` or `description`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::config::BaseConfigOption;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let option = weechat
+    ///     .config_get("irc.server.libera.nicks")
+    ///     .expect("Can't find the option");
+    ///
+    /// if let Some(parent) = option.get_string("parent_name") {
+    ///     Weechat::print(&format!("The option inherits from {}", parent));
+    /// }
+    /// ```
     fn get_string(&self, property: &str) -> Option<Cow<str>> {
         let weechat = self.get_weechat();
         let get_string = weechat.get().config_option_get_string.unwrap();
@@ -67,13 +117,74 @@ pub trait HidenConfigOptionT {
             }
         }
     }
-}
 
-/// Base configuration option methods.
-///
-/// These methods are implemented for every option and don't depend on the
-/// option type.
-pub trait BaseConfigOption: HidenConfigOptionT {
+    /// Get an integer property of the option.
+    ///
+    /// Returns `None` if the property doesn't exist, isn't set or isn't an
+    /// integer.
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - The name of the property, one of `min` or `max`, or
+    ///     `value` and `default_value` for boolean and integer options.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::config::BaseConfigOption;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let option = weechat
+    ///     .config_get("weechat.look.hotlist_names_count")
+    ///     .expect("Can't find the option");
+    ///
+    /// let min = option.get_integer("min");
+    /// let max = option.get_integer("max");
+    /// ```
+    fn get_integer(&self, property: &str) -> Option<i32> {
+        let is_integer = match property {
+            "min" | "max" => true,
+            "value" | "default_value" => matches!(
+                self.option_type(),
+                OptionType::Boolean | OptionType::Integer
+            ),
+            _ => false,
+        };
+
+        if !is_integer {
+            return None;
+        }
+
+        let pointer = self.get_pointer(property)?;
+
+        Some(unsafe { *(pointer as *const c_int) })
+    }
+
+    /// Get a raw pointer property of the option.
+    ///
+    /// This is an escape hatch for properties that aren't exposed otherwise,
+    /// the caller is responsible to cast the pointer to the correct type.
+    ///
+    /// Returns `None` if the property doesn't exist or isn't set.
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - The name of the property, e.g. `config_file`,
+    ///     `section`, `string_values`, `min`, `max`, `default_value` or
+    ///     `value`. See the Weechat plugin API reference for the full list.
+    fn get_pointer(&self, property: &str) -> Option<*mut c_void> {
+        let weechat = self.get_weechat();
+        let get_pointer = weechat.get().config_option_get_pointer.unwrap();
+        let property = LossyCString::new(property);
+
+        let pointer = unsafe { get_pointer(self.get_ptr(), property.as_ptr()) };
+
+        if pointer.is_null() {
+            None
+        } else {
+            Some(pointer)
+        }
+    }
+
     /// Get the name of the option.
     fn name(&self) -> Cow<str> {
         self.get_string("name")
@@ -143,6 +254,77 @@ pub trait BaseConfigOption: HidenConfigOptionT {
 
         ret != 0
     }
+
+    /// Is the default value of the option undefined/null.
+    fn default_is_null(&self) -> bool {
+        let weechat = self.get_weechat();
+        let default_is_null = weechat.get().config_option_default_is_null.unwrap();
+
+        let ret = unsafe { default_is_null(self.get_ptr()) };
+
+        ret != 0
+    }
+
+    /// Does the option currently have its default value.
+    ///
+    /// This can be used to find options that were changed by the user.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::config::BaseConfigOption;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let option = weechat
+    ///     .config_get("weechat.look.buffer_time_format")
+    ///     .expect("Can't find the option");
+    ///
+    /// if !option.is_default() {
+    ///     option.reset(true);
+    /// }
+    ///
+    /// assert!(option.is_default());
+    /// ```
+    fn is_default(&self) -> bool {
+        if self.is_null() || self.default_is_null() {
+            return self.is_null() && self.default_is_null();
+        }
+
+        let weechat = self.get_weechat();
+        let ptr = self.get_ptr();
+
+        unsafe {
+            match self.option_type() {
+                OptionType::Boolean => {
+                    let value = weechat.get().config_boolean.unwrap();
+                    let default = weechat.get().config_boolean_default.unwrap();
+                    value(ptr) == default(ptr)
+                }
+                OptionType::Integer => {
+                    let value = weechat.get().config_integer.unwrap();
+                    let default = weechat.get().config_integer_default.unwrap();
+                    value(ptr) == default(ptr)
+                }
+                OptionType::String => {
+                    let value = weechat.get().config_string.unwrap();
+                    let default = weechat.get().config_string_default.unwrap();
+                    c_str_eq(value(ptr), default(ptr))
+                }
+                OptionType::Color => {
+                    let value = weechat.get().config_color.unwrap();
+                    let default = weechat.get().config_color_default.unwrap();
+                    c_str_eq(value(ptr), default(ptr))
+                }
+            }
+        }
+    }
+}
+
+unsafe fn c_str_eq(first: *const c_char, second: *const c_char) -> bool {
+    if first.is_null() || second.is_null() {
+        first.is_null() && second.is_null()
+    } else {
+        CStr::from_ptr(first) == CStr::from_ptr(second)
+    }
 }
 
 /// Marker trait for config options.
@@ -149,6 +149,13 @@ impl<'a> IntegerOption<'a> {
         let config_integer = weechat.get().config_integer.unwrap();
         unsafe { config_integer(self.get_ptr()) }
     }
+
+    /// Get the default value of the option.
+    pub fn default_value(&self) -> i32 {
+        let weechat = self.get_weechat();
+        let config_integer_default = weechat.get().config_integer_default.unwrap();
+        unsafe { config_integer_default(self.get_ptr()) }
+    }
 }
 
 impl<'a> FromPtrs for IntegerOption<'a> {
@@ -42,7 +42,7 @@ pub use crate::config::{
 };
 
 pub use crate::config::{
-    config_options::{BaseConfigOption, ConfigOptions, OptionType},
+    config_options::{BaseConfigOption, ConfigOptions, OptionType, OptionValue},
     section::{
         ConfigOption, ConfigSection, ConfigSectionSettings, SectionHandle, SectionHandleMut,
         SectionReadCallback, SectionWriteCallback, SectionWriteDefaultCallback,
@@ -14,7 +14,7 @@ use weechat_sys::{t_config_file, t_config_option, t_config_section, t_weechat_pl
 
 use crate::{
     config::{
-        config_options::{CheckCB, OptionPointers, OptionType},
+        config_options::{CheckCB, OptionPointers, OptionType, OptionValue},
         BaseConfigOption, BooleanOption, BooleanOptionSettings, ColorOption, ColorOptionSettings,
         Conf, Config, ConfigOptions, IntegerOption, IntegerOptionSettings, OptionChanged,
         StringOption, StringOptionSettings,
@@ -52,6 +52,27 @@ impl<'a> ConfigOption<'a> {
             ConfigOption::String(ref o) => o,
         }
     }
+
+    /// Get the value of the option decoded into the type of the option.
+    pub fn value(&self) -> OptionValue {
+        match self {
+            ConfigOption::Boolean(o) => OptionValue::Boolean(o.value()),
+            ConfigOption::Integer(o) => OptionValue::Integer(o.value()),
+            ConfigOption::String(o) => OptionValue::String(o.value().into_owned()),
+            ConfigOption::Color(o) => OptionValue::Color(o.value().into_owned()),
+        }
+    }
+
+    /// Get the default value of the option decoded into the type of the
+    /// option.
+    pub fn default_value(&self) -> OptionValue {
+        match self {
+            ConfigOption::Boolean(o) => OptionValue::Boolean(o.default_value()),
+            ConfigOption::Integer(o) => OptionValue::Integer(o.default_value()),
+            ConfigOption::String(o) => OptionValue::String(o.default_value().into_owned()),
+            ConfigOption::Color(o) => OptionValue::Color(o.default_value().into_owned()),
+        }
+    }
 }
 
 impl<'a> Deref for ConfigOption<'a> {
@@ -384,8 +405,11 @@ impl Drop for ConfigSection {
 
         unsafe {
             Box::from_raw(self.section_data as *mut ConfigSectionPointers);
-            options_free(self.ptr);
-            section_free(self.ptr);
+
+            if !Weechat::is_unloading() {
+                options_free(self.ptr);
+                section_free(self.ptr);
+            }
         };
     }
 }
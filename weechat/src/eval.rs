@@ -0,0 +1,145 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{CStr, CString},
+    ptr,
+};
+
+use crate::{LossyCString, Weechat};
+
+/// A Weechat expression that will be evaluated many times.
+///
+/// Weechat doesn't expose a pre-parsed form of an expression, every
+/// evaluation parses the expression again. This type avoids the work that can
+/// be avoided on the Rust side:
+///
+/// * The expression is converted into a C string only once, when it's
+///   created.
+/// * Expressions that don't contain any `${...}` variables always evaluate to
+///   the same string, the result of those is cached after the first
+///   evaluation and Weechat isn't called again.
+///
+/// Expressions that contain variables are evaluated by Weechat every time,
+/// since the values of the variables may change between evaluations, e.g. a
+/// user format string for a bar item that is evaluated on every redraw.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::collections::HashMap;
+/// # use weechat::EvalExpression;
+/// let format = EvalExpression::new("${color:green}${nick}${color:reset}: ${message}");
+///
+/// let mut variables = HashMap::new();
+/// variables.insert("nick", "alice");
+/// variables.insert("message", "Hello");
+///
+/// let line = format
+///     .evaluate_with_variables(&variables)
+///     .expect("Can't evaluate the format");
+/// ```
+pub struct EvalExpression {
+    expression: CString,
+    is_static: bool,
+    cached_result: RefCell<Option<String>>,
+}
+
+impl std::fmt::Debug for EvalExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvalExpression")
+            .field("expression", &self.expression)
+            .field("is_static", &self.is_static)
+            .finish()
+    }
+}
+
+impl EvalExpression {
+    /// Create a new expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The Weechat expression, see the Weechat
+    ///     [documentation] for the syntax.
+    ///
+    /// [documentation]: https://weechat.org/files/doc/stable/weechat_plugin_api.en.html#_string_eval_expression
+    pub fn new(expression: &str) -> Self {
+        EvalExpression {
+            expression: LossyCString::new(expression),
+            is_static: !expression.contains("${"),
+            cached_result: RefCell::new(None),
+        }
+    }
+
+    /// Does the expression evaluate to the same string every time.
+    ///
+    /// This is the case if the expression doesn't contain any variables.
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+
+    /// Evaluate the expression.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn evaluate(&self) -> Result<String, ()> {
+        self.evaluate_with_variables(&HashMap::new())
+    }
+
+    /// Evaluate the expression with additional variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `variables` - Variables that can be used in the expression as
+    ///     `${name}`, they take precedence over the variables Weechat
+    ///     provides.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn evaluate_with_variables(&self, variables: &HashMap<&str, &str>) -> Result<String, ()> {
+        if let Some(result) = self.cached_result.borrow().as_ref() {
+            return Ok(result.clone());
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let string_eval_expression = weechat.get().string_eval_expression.unwrap();
+        let hashtable_free = weechat.get().hashtable_free.unwrap();
+
+        let extra_vars = if variables.is_empty() {
+            ptr::null_mut()
+        } else {
+            weechat.hashmap_to_weechat(variables.clone())
+        };
+
+        let result = unsafe {
+            let result = string_eval_expression(
+                self.expression.as_ptr(),
+                ptr::null_mut(),
+                extra_vars,
+                ptr::null_mut(),
+            );
+
+            if !extra_vars.is_null() {
+                hashtable_free(extra_vars);
+            }
+
+            if result.is_null() {
+                return Err(());
+            }
+
+            let string = CStr::from_ptr(result).to_string_lossy().into_owned();
+            libc::free(result as *mut libc::c_void);
+
+            string
+        };
+
+        if self.is_static {
+            *self.cached_result.borrow_mut() = Some(result.clone());
+        }
+
+        Ok(result)
+    }
+}
@@ -1,9 +1,286 @@
-use std::{collections::HashMap, ffi::c_void};
+use libc::c_char;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    ffi::{c_void, CStr},
+    fmt,
+    iter::FromIterator,
+    marker::PhantomData,
+    vec,
+};
 
-use weechat_sys::{t_hashtable, WEECHAT_HASHTABLE_STRING};
+use weechat_sys::{t_hashtable, t_weechat_plugin, WEECHAT_HASHTABLE_STRING};
 
 use crate::{LossyCString, Weechat};
 
+/// A Weechat hashtable.
+///
+/// Hashtables are used by parts of the plugin API to pass structured data
+/// around, e.g. hsignals. A hashtable is either created by the plugin, in
+/// which case it's freed when the object is dropped, or borrowed from Weechat
+/// for the duration of a callback.
+///
+/// Only hashtables with string keys and values can be read from and written
+/// to directly, iterating over a hashtable converts the keys and values of
+/// any type to strings.
+///
+/// # Example
+///
+/// ```no_run
+/// # use weechat::Hashtable;
+/// let mut hashtable = Hashtable::new();
+/// hashtable.set("nick", "alice").unwrap();
+///
+/// assert_eq!(hashtable.get("nick").as_deref(), Some("alice"));
+/// assert_eq!(hashtable.len(), 1);
+///
+/// for (key, value) in hashtable.iter() {
+///     println!("{}: {}", key, value);
+/// }
+/// ```
+pub struct Hashtable<'a> {
+    ptr: *mut t_hashtable,
+    weechat_ptr: *mut t_weechat_plugin,
+    owned: bool,
+    phantom: PhantomData<&'a Weechat>,
+}
+
+impl Hashtable<'static> {
+    /// Create a new empty hashtable with string keys and values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn new() -> Self {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let hashtable_new = weechat.get().hashtable_new.unwrap();
+        let table_type: *const i8 = WEECHAT_HASHTABLE_STRING as *const _ as *const i8;
+
+        let ptr = unsafe { hashtable_new(8, table_type, table_type, None, None) };
+
+        if ptr.is_null() {
+            panic!("Can't allocate a new hashtable");
+        }
+
+        Hashtable {
+            ptr,
+            weechat_ptr: weechat.ptr,
+            owned: true,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl Default for Hashtable<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Hashtable<'a> {
+    pub(crate) fn from_ptr(weechat: &'a Weechat, ptr: *mut t_hashtable) -> Self {
+        Hashtable {
+            ptr,
+            weechat_ptr: weechat.ptr,
+            owned: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Take ownership of a hashtable that Weechat handed over to us, the
+    /// hashtable is freed when the object is dropped.
+    pub(crate) fn from_raw(weechat: &Weechat, ptr: *mut t_hashtable) -> Hashtable<'static> {
+        Hashtable {
+            ptr,
+            weechat_ptr: weechat.ptr,
+            owned: true,
+            phantom: PhantomData,
+        }
+    }
+
+    pub(crate) fn ptr(&self) -> *mut t_hashtable {
+        self.ptr
+    }
+
+    /// Hand the hashtable over to Weechat, which is responsible for freeing
+    /// it from now on.
+    pub(crate) fn into_raw(self) -> *mut t_hashtable {
+        let ptr = self.ptr;
+        std::mem::forget(self);
+        ptr
+    }
+
+    fn weechat(&self) -> Weechat {
+        Weechat::from_ptr(self.weechat_ptr)
+    }
+
+    fn has_type(&self, property: &str) -> bool {
+        let weechat = self.weechat();
+        let hashtable_get_string = weechat.get().hashtable_get_string.unwrap();
+        let property = LossyCString::new(property);
+
+        unsafe {
+            let table_type = hashtable_get_string(self.ptr, property.as_ptr());
+
+            !table_type.is_null() && CStr::from_ptr(table_type).to_bytes() == b"string"
+        }
+    }
+
+    fn has_string_keys(&self) -> bool {
+        self.has_type("type_keys")
+    }
+
+    /// Get the value for the given key.
+    ///
+    /// Returns `None` if the key doesn't exist or the hashtable doesn't have
+    /// string keys and values.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the value that should be fetched.
+    pub fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        if !self.has_string_keys() || !self.has_type("type_values") {
+            return None;
+        }
+
+        let weechat = self.weechat();
+        let hashtable_get = weechat.get().hashtable_get.unwrap();
+        let key = LossyCString::new(key);
+
+        unsafe {
+            let value = hashtable_get(self.ptr, key.as_ptr() as *const c_void);
+
+            if value.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(value as *const c_char).to_string_lossy())
+            }
+        }
+    }
+
+    /// Does the hashtable contain the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key that should be checked.
+    pub fn contains_key(&self, key: &str) -> bool {
+        if !self.has_string_keys() {
+            return false;
+        }
+
+        let weechat = self.weechat();
+        let hashtable_has_key = weechat.get().hashtable_has_key.unwrap();
+        let key = LossyCString::new(key);
+
+        unsafe { hashtable_has_key(self.ptr, key.as_ptr() as *const c_void) != 0 }
+    }
+
+    /// Set the value for the given key, an existing value is replaced.
+    ///
+    /// Returns an error if the hashtable doesn't have string keys and values.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the value.
+    ///
+    /// * `value` - The value that should be set.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ()> {
+        if !self.has_string_keys() || !self.has_type("type_values") {
+            return Err(());
+        }
+
+        let weechat = self.weechat();
+        let hashtable_set = weechat.get().hashtable_set.unwrap();
+        let key = LossyCString::new(key);
+        let value = LossyCString::new(value);
+
+        let item = unsafe {
+            hashtable_set(
+                self.ptr,
+                key.as_ptr() as *const c_void,
+                value.as_ptr() as *const c_void,
+            )
+        };
+
+        if item.is_null() {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Remove the given key from the hashtable.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key that should be removed.
+    pub fn remove(&mut self, key: &str) {
+        if !self.has_string_keys() {
+            return;
+        }
+
+        let weechat = self.weechat();
+        let hashtable_remove = weechat.get().hashtable_remove.unwrap();
+        let key = LossyCString::new(key);
+
+        unsafe { hashtable_remove(self.ptr, key.as_ptr() as *const c_void) }
+    }
+
+    /// Get the number of items in the hashtable.
+    pub fn len(&self) -> usize {
+        let weechat = self.weechat();
+        let hashtable_get_integer = weechat.get().hashtable_get_integer.unwrap();
+        let property = LossyCString::new("items_count");
+
+        unsafe { hashtable_get_integer(self.ptr, property.as_ptr()) as usize }
+    }
+
+    /// Is the hashtable empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the keys and values of the hashtable.
+    ///
+    /// Keys and values that aren't strings are converted to strings.
+    pub fn iter(&self) -> vec::IntoIter<(String, String)> {
+        unsafe { self.weechat().hashtable_to_vec(self.ptr) }.into_iter()
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> FromIterator<(K, V)> for Hashtable<'static> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut hashtable = Hashtable::new();
+
+        for (key, value) in iter {
+            // A new hashtable has string keys and values.
+            let _ = hashtable.set(key.as_ref(), value.as_ref());
+        }
+
+        hashtable
+    }
+}
+
+impl fmt::Debug for Hashtable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Drop for Hashtable<'_> {
+    fn drop(&mut self) {
+        if !self.owned || Weechat::is_unloading() {
+            return;
+        }
+
+        let weechat = self.weechat();
+        let hashtable_free = weechat.get().hashtable_free.unwrap();
+        unsafe { hashtable_free(self.ptr) };
+    }
+}
+
 impl Weechat {
     pub(crate) fn hashmap_to_weechat(&self, hashmap: HashMap<&str, &str>) -> *mut t_hashtable {
         let hashtable_new = self.get().hashtable_new.unwrap();
@@ -27,4 +304,38 @@ impl Weechat {
 
         hashtable
     }
+
+    pub(crate) unsafe fn hashtable_to_vec(
+        &self,
+        hashtable: *mut t_hashtable,
+    ) -> Vec<(String, String)> {
+        unsafe extern "C" fn c_map_cb(
+            data: *mut c_void,
+            _hashtable: *mut t_hashtable,
+            key: *const c_char,
+            value: *const c_char,
+        ) {
+            let entries: &mut Vec<(String, String)> = &mut *(data as *mut Vec<(String, String)>);
+
+            let key = CStr::from_ptr(key).to_string_lossy().to_string();
+            let value = if value.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(value).to_string_lossy().to_string()
+            };
+
+            entries.push((key, value));
+        }
+
+        let hashtable_map_string = self.get().hashtable_map_string.unwrap();
+        let mut entries = Vec::new();
+
+        hashtable_map_string(
+            hashtable,
+            Some(c_map_cb),
+            &mut entries as *mut _ as *mut c_void,
+        );
+
+        entries
+    }
 }
@@ -2,10 +2,330 @@ use std::{
     borrow::Cow,
     collections::HashMap,
     ffi::{c_void, CStr},
+    ptr,
+    rc::Rc,
+    time::SystemTime,
 };
-use weechat_sys::t_hdata;
+use weechat_sys::{
+    t_hdata, WEECHAT_HDATA_INTEGER, WEECHAT_HDATA_POINTER, WEECHAT_HDATA_SHARED_STRING,
+    WEECHAT_HDATA_STRING, WEECHAT_HDATA_TIME,
+};
+
+use crate::{time::FromWeechatTime, LossyCString, Weechat};
+
+/// A read-only view of a Weechat hdata.
+///
+/// Hdata describe the structures Weechat uses internally, e.g. buffers,
+/// lines or windows, and allow plugins to read the fields of those
+/// structures by name. The list of hdata and their variables can be found
+/// in the Weechat plugin API reference.
+///
+/// Every read checks that the hdata declares a variable with the given name
+/// and type and that the pointer belongs to this hdata, `None` is returned
+/// otherwise.
+///
+/// Reads are unsafe since Weechat may free the structure a pointer points to
+/// at any time and most hdata, e.g. `line`, don't have a list that the
+/// pointer could be checked against. Pointers should only be read in the
+/// callback that fetched them, before anything that might free the structure
+/// ran.
+///
+/// Making the getters `unsafe` is a deliberate choice, it means that hdata
+/// can't be read without an `unsafe` block. A safe API would have to check
+/// every pointer against a list before reading it, which isn't possible for
+/// most hdata, and would otherwise allow use after free bugs in safe code.
+///
+/// # Example
+///
+/// ```no_run
+/// # let weechat = unsafe { weechat::Weechat::weechat() };
+/// let hdata = weechat.hdata("buffer").expect("Can't find the buffer hdata");
+///
+/// // The first buffer in the list is the core buffer.
+/// let core = hdata.get_list("gui_buffers").unwrap();
+///
+/// // Nothing can close the core buffer while the lines are read.
+/// unsafe {
+///     assert_eq!(hdata.integer(&core, "number"), Some(1));
+///     assert_eq!(hdata.string(&core, "full_name").as_deref(), Some("core.weechat"));
+///
+///     // Walk the lines of the core buffer.
+///     let lines_hdata = weechat.hdata("lines").unwrap();
+///     let line_hdata = weechat.hdata("line").unwrap();
+///     let line_data_hdata = weechat.hdata("line_data").unwrap();
+///
+///     let lines = hdata.pointer(&core, "own_lines").unwrap();
+///     let mut line = lines_hdata.pointer(&lines, "first_line");
+///
+///     while let Some(current) = line {
+///         let data = line_hdata.pointer(&current, "data").unwrap();
+///         println!("{:?}", line_data_hdata.string(&data, "message"));
+///
+///         line = line_hdata.move_pointer(&current, 1);
+///     }
+/// }
+/// ```
+pub struct HData<'a> {
+    ptr: *mut t_hdata,
+    name: Rc<str>,
+    weechat: &'a Weechat,
+}
+
+/// A pointer to a structure that is described by an hdata.
+///
+/// The pointer remembers the name of its hdata, it can only be read using
+/// that hdata. Weechat may free the structure at any time, e.g. when a buffer
+/// is closed, pointers shouldn't be kept around after the callback that
+/// fetched them returned. Use `HData::check_pointer()` to check if the
+/// structure still exists if the hdata has a list that contains it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HDataPointer {
+    ptr: *mut c_void,
+    hdata: Rc<str>,
+}
+
+impl HDataPointer {
+    /// Get the name of the hdata that describes the structure this pointer
+    /// points to.
+    pub fn hdata_name(&self) -> &str {
+        &self.hdata
+    }
+}
+
+impl<'a> HData<'a> {
+    /// Get the name of the hdata.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn wrap(&self, ptr: *mut c_void) -> Option<HDataPointer> {
+        if ptr.is_null() {
+            None
+        } else {
+            Some(HDataPointer {
+                ptr,
+                hdata: self.name.clone(),
+            })
+        }
+    }
+
+    /// Check that the pointer belongs to this hdata and that it has a
+    /// variable with the given name of one of the given types.
+    fn check_variable(&self, pointer: &HDataPointer, name: &str, types: &[i32]) -> bool {
+        if pointer.hdata != self.name {
+            return false;
+        }
+
+        let hdata_get_var_type = self.weechat.get().hdata_get_var_type.unwrap();
+        let name = LossyCString::new(name);
+
+        let var_type = unsafe { hdata_get_var_type(self.ptr, name.as_ptr()) };
+
+        types.contains(&var_type)
+    }
+
+    /// Get a list of the hdata, e.g. `gui_buffers` for the `buffer` hdata.
+    ///
+    /// Returns `None` if the list doesn't exist or is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the list.
+    pub fn get_list(&self, name: &str) -> Option<HDataPointer> {
+        let ptr = unsafe { self.weechat.hdata_get_list(self.ptr, name) };
+        self.wrap(ptr)
+    }
+
+    /// Move a pointer to another element of its list.
+    ///
+    /// Returns `None` if the pointer doesn't belong to this hdata or if
+    /// there is no element at the given position. A count of 0 returns the
+    /// same pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - The pointer that should be moved.
+    ///
+    /// * `count` - The number of elements the pointer should be moved, a
+    ///     negative number moves the pointer backwards.
+    ///
+    /// # Safety
+    ///
+    /// The structure the pointer points to and the structures it is moved
+    /// over need to be alive.
+    pub unsafe fn move_pointer(&self, pointer: &HDataPointer, count: i32) -> Option<HDataPointer> {
+        if pointer.hdata != self.name {
+            return None;
+        }
+
+        if count == 0 {
+            return Some(pointer.clone());
+        }
+
+        let ptr = self.weechat.hdata_move(self.ptr, pointer.ptr, count);
+        self.wrap(ptr)
+    }
+
+    /// Check if the structure the pointer points to is still part of one of
+    /// the lists of this hdata.
+    ///
+    /// Only lists that Weechat marks for pointer checks are searched, e.g.
+    /// `gui_buffers` of the `buffer` hdata. Returns `false` for hdata without
+    /// such a list, e.g. `line`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - The pointer that should be checked.
+    pub fn check_pointer(&self, pointer: &HDataPointer) -> bool {
+        if pointer.hdata != self.name {
+            return false;
+        }
+
+        let hdata_check_pointer = self.weechat.get().hdata_check_pointer.unwrap();
+
+        unsafe { hdata_check_pointer(self.ptr, ptr::null_mut(), pointer.ptr) == 1 }
+    }
 
-use crate::{LossyCString, Weechat};
+    /// Read an integer variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - The pointer to the structure that should be read.
+    ///
+    /// * `name` - The name of the variable.
+    ///
+    /// # Safety
+    ///
+    /// The structure the pointer points to needs to be alive.
+    pub unsafe fn integer(&self, pointer: &HDataPointer, name: &str) -> Option<i32> {
+        if !self.check_variable(pointer, name, &[WEECHAT_HDATA_INTEGER]) {
+            return None;
+        }
+
+        Some(self.weechat.hdata_integer(self.ptr, pointer.ptr, name))
+    }
+
+    /// Read a string variable.
+    ///
+    /// Returns `None` if the string isn't set.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - The pointer to the structure that should be read.
+    ///
+    /// * `name` - The name of the variable.
+    ///
+    /// # Safety
+    ///
+    /// The structure the pointer points to needs to be alive.
+    pub unsafe fn string(&self, pointer: &HDataPointer, name: &str) -> Option<Cow<str>> {
+        if !self.check_variable(
+            pointer,
+            name,
+            &[WEECHAT_HDATA_STRING, WEECHAT_HDATA_SHARED_STRING],
+        ) {
+            return None;
+        }
+
+        let hdata_string = self.weechat.get().hdata_string.unwrap();
+        let name = LossyCString::new(name);
+
+        let string = hdata_string(self.ptr, pointer.ptr, name.as_ptr());
+
+        if string.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(string).to_string_lossy())
+        }
+    }
+
+    /// Read a time variable.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - The pointer to the structure that should be read.
+    ///
+    /// * `name` - The name of the variable.
+    ///
+    /// # Safety
+    ///
+    /// The structure the pointer points to needs to be alive.
+    pub unsafe fn time(&self, pointer: &HDataPointer, name: &str) -> Option<SystemTime> {
+        if !self.check_variable(pointer, name, &[WEECHAT_HDATA_TIME]) {
+            return None;
+        }
+
+        let time = self.weechat.hdata_time(self.ptr, pointer.ptr, name);
+
+        Some(SystemTime::from_weechat_time(time))
+    }
+
+    /// Read a pointer variable.
+    ///
+    /// The returned pointer belongs to the hdata that the variable refers
+    /// to, e.g. the `own_lines` variable of the `buffer` hdata points to a
+    /// structure of the `lines` hdata. Returns `None` if the pointer is
+    /// null or if the hdata doesn't declare which hdata the pointer belongs
+    /// to.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer` - The pointer to the structure that should be read.
+    ///
+    /// * `name` - The name of the variable.
+    ///
+    /// # Safety
+    ///
+    /// The structure the pointer points to needs to be alive.
+    pub unsafe fn pointer(&self, pointer: &HDataPointer, name: &str) -> Option<HDataPointer> {
+        if !self.check_variable(pointer, name, &[WEECHAT_HDATA_POINTER]) {
+            return None;
+        }
+
+        let hdata_get_var_hdata = self.weechat.get().hdata_get_var_hdata.unwrap();
+        let c_name = LossyCString::new(name);
+
+        let hdata_name = hdata_get_var_hdata(self.ptr, c_name.as_ptr());
+
+        if hdata_name.is_null() {
+            return None;
+        }
+
+        let ptr = self.weechat.hdata_pointer(self.ptr, pointer.ptr, name);
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(HDataPointer {
+                ptr,
+                hdata: Rc::from(CStr::from_ptr(hdata_name).to_string_lossy().as_ref()),
+            })
+        }
+    }
+}
+
+impl Weechat {
+    /// Get the hdata with the given name.
+    ///
+    /// Returns `None` if no hdata with the given name exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the hdata, e.g. `buffer` or `line`.
+    pub fn hdata(&self, name: &str) -> Option<HData<'_>> {
+        let ptr = unsafe { self.hdata_get(name) };
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(HData {
+                ptr,
+                name: Rc::from(name),
+                weechat: self,
+            })
+        }
+    }
+}
 
 impl Weechat {
     pub(crate) unsafe fn hdata_get(&self, name: &str) -> *mut t_hdata {
@@ -16,6 +336,13 @@ impl Weechat {
         hdata_get(self.ptr, name.as_ptr())
     }
 
+    pub(crate) unsafe fn hdata_get_list(&self, hdata: *mut t_hdata, name: &str) -> *mut c_void {
+        let hdata_get_list = self.get().hdata_get_list.unwrap();
+        let name = LossyCString::new(name);
+
+        hdata_get_list(hdata, name.as_ptr())
+    }
+
     pub(crate) unsafe fn hdata_pointer(
         &self,
         hdata: *mut t_hdata,
@@ -1,10 +1,17 @@
-//! Bar items are used to display status information in Weechat.
+//! Bars and bar items are used to display status information in Weechat.
 use core::ptr;
 use libc::c_char;
 use std::os::raw::c_void;
-use weechat_sys::{t_gui_bar_item, t_gui_buffer, t_gui_window, t_hashtable, t_weechat_plugin};
+use weechat_sys::{
+    t_gui_bar, t_gui_bar_item, t_gui_buffer, t_gui_window, t_hashtable, t_weechat_plugin,
+};
 
-use crate::{buffer::Buffer, LossyCString, Weechat};
+use crate::{
+    buffer::Buffer,
+    hooks::{defer_drop, in_callback, run_callback, HookData},
+    util::{NameError, NameKind},
+    LossyCString, Weechat,
+};
 
 /// Trait for the bar item callback
 ///
@@ -37,14 +44,22 @@ struct BarItemCbData {
 
 /// A handle to a bar item. The bar item is automatically removed when the object is
 /// dropped.
+///
+/// The bar item may be dropped inside of its own callback, it's removed once
+/// the callback returned.
 pub struct BarItem {
     name: String,
     ptr: *mut t_gui_bar_item,
     weechat: *mut t_weechat_plugin,
-    _data: Box<BarItemCbData>,
+    _data: HookData<BarItemCbData>,
 }
 
-impl Drop for BarItem {
+struct BarItemRemover {
+    ptr: *mut t_gui_bar_item,
+    weechat: *mut t_weechat_plugin,
+}
+
+impl Drop for BarItemRemover {
     fn drop(&mut self) {
         let weechat = Weechat::from_ptr(self.weechat);
         let bar_item_remove = weechat.get().bar_item_remove.unwrap();
@@ -52,12 +67,31 @@ impl Drop for BarItem {
     }
 }
 
+impl Drop for BarItem {
+    fn drop(&mut self) {
+        if Weechat::is_unloading() {
+            return;
+        }
+
+        let remover = BarItemRemover {
+            ptr: self.ptr,
+            weechat: self.weechat,
+        };
+
+        if in_callback() {
+            defer_drop(Box::new(remover));
+        }
+    }
+}
+
 impl BarItem {
     /// Create a new bar item that can be added by a user.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the new bar item.
+    /// * `name` - The name of the new bar item. Needs to be valid according to
+    ///     `Weechat::validate_name()` and unique across the plugin, otherwise
+    ///     the bar item creation will fail.
     ///
     /// * `callback` - The callback that should be called after the bar items
     /// is marked to be updated.
@@ -67,14 +101,18 @@ impl BarItem {
     /// Panics if the method is not called from the main Weechat thread.
     ///
     /// # Example
+    ///
     /// ```no_run
     /// # use weechat::Weechat;
     /// # use weechat::buffer::Buffer;
     /// # use weechat::hooks::BarItem;
-    /// let item = BarItem::new("buffer_plugin", |weechat:&Weechat,
-    /// buffer: &Buffer| {
-    ///     "rust/sample".to_owned()
-    /// });
+    /// let item = BarItem::new("buffer_plugin", |_: &Weechat, buffer: &Buffer| {
+    ///     format!("{}rust/{}", Weechat::color("green"), buffer.short_name())
+    /// })
+    /// .expect("Can't create the bar item");
+    ///
+    /// // Rebuild the item, e.g. after the state that it displays changed.
+    /// item.update();
     /// ```
     ///
     // TODO: Provide window object, the callback should accept a Window object
@@ -83,7 +121,7 @@ impl BarItem {
     // TODO: If we're going to allow bar items to be searched for like we do for
     // buffers, we need to do something about the multiple ownership that may
     // come from this.
-    pub fn new(name: &str, callback: impl BarItemCallback) -> Result<BarItem, ()> {
+    pub fn new(name: &str, callback: impl BarItemCallback) -> Result<BarItem, NameError> {
         unsafe extern "C" fn c_item_cb(
             pointer: *const c_void,
             _data: *mut c_void,
@@ -98,7 +136,7 @@ impl BarItem {
 
             let cb_trait = &mut data.callback;
 
-            let ret = cb_trait.callback(&weechat, &buffer);
+            let ret = run_callback(|| cb_trait.callback(&weechat, &buffer));
 
             // Weechat wants a malloc'ed string
             libc::strdup(LossyCString::new(ret).as_ptr())
@@ -106,6 +144,8 @@ impl BarItem {
         Weechat::check_thread();
         let weechat = unsafe { Weechat::weechat() };
 
+        Weechat::validate_name(NameKind::BarItem, name)?;
+
         let data = Box::new(BarItemCbData {
             callback: Box::new(callback),
             weechat_ptr: weechat.ptr,
@@ -126,10 +166,12 @@ impl BarItem {
             )
         };
 
-        let cb_data = unsafe { Box::from_raw(data_ref) };
+        let cb_data = HookData::new(unsafe { Box::from_raw(data_ref) });
 
+        // Weechat only refuses valid names if the plugin already has a bar item
+        // with the same name.
         if bar_item_ptr.is_null() {
-            return Err(());
+            return Err(NameError::AlreadyExists(name.to_owned()));
         }
 
         Ok(BarItem {
@@ -140,8 +182,414 @@ impl BarItem {
         })
     }
 
+    /// Get the name of the bar item.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Update the content of the bar item, by calling its build callback.
     pub fn update(&self) {
         Weechat::bar_item_update(&self.name);
     }
 }
+
+/// The position of a bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[allow(missing_docs)]
+pub enum BarPosition {
+    Top,
+    #[default]
+    Bottom,
+    Left,
+    Right,
+}
+
+impl BarPosition {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BarPosition::Top => "top",
+            BarPosition::Bottom => "bottom",
+            BarPosition::Left => "left",
+            BarPosition::Right => "right",
+        }
+    }
+}
+
+/// The type of a bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarType {
+    /// The bar is displayed once, outside of the windows.
+    Root,
+    /// The bar is displayed in every window, this is the default.
+    #[default]
+    Window,
+}
+
+impl BarType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BarType::Root => "root",
+            BarType::Window => "window",
+        }
+    }
+}
+
+/// Settings for a new bar.
+///
+/// The bar is shown at the bottom of every window, its size adapts to its
+/// content and it uses the default colors unless configured otherwise.
+#[derive(Debug, Clone)]
+pub struct BarSettings {
+    name: String,
+    hidden: bool,
+    priority: i32,
+    bar_type: BarType,
+    condition: String,
+    position: BarPosition,
+    size: u32,
+    size_max: u32,
+    color_fg: String,
+    color_delim: String,
+    color_bg: String,
+    separator: bool,
+    items: Vec<String>,
+}
+
+impl BarSettings {
+    /// Create new bar settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the bar, it needs to be unique.
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        BarSettings {
+            name: name.into(),
+            hidden: false,
+            priority: 0,
+            bar_type: BarType::default(),
+            condition: String::new(),
+            position: BarPosition::default(),
+            size: 0,
+            size_max: 0,
+            color_fg: "default".to_owned(),
+            color_delim: "default".to_owned(),
+            color_bg: "default".to_owned(),
+            separator: false,
+            items: Vec::new(),
+        }
+    }
+
+    /// Set if the bar should be hidden after it was created.
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden` - True if the bar should be hidden.
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Set the priority of the bar, bars with a higher priority are
+    /// displayed closer to the edge of the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority of the bar.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the type of the bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `bar_type` - The type of the bar.
+    pub fn bar_type(mut self, bar_type: BarType) -> Self {
+        self.bar_type = bar_type;
+        self
+    }
+
+    /// Set the condition for displaying the bar in a window.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - `active`, `inactive`, `nicklist` or an expression
+    ///     that is evaluated for every window.
+    pub fn condition<C: Into<String>>(mut self, condition: C) -> Self {
+        self.condition = condition.into();
+        self
+    }
+
+    /// Set the position of the bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the bar.
+    pub fn position(mut self, position: BarPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Set the size of the bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of lines for bars at the top or bottom, the
+    ///     number of columns for bars at the left or right, 0 adapts the
+    ///     size to the content of the bar.
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the maximal size of a bar whose size adapts to its content.
+    ///
+    /// # Arguments
+    ///
+    /// * `size_max` - The maximal size, 0 means no limit.
+    pub fn size_max(mut self, size_max: u32) -> Self {
+        self.size_max = size_max;
+        self
+    }
+
+    /// Set the colors of the bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `foreground` - The color of the text.
+    ///
+    /// * `delimiter` - The color of the delimiters.
+    ///
+    /// * `background` - The background color of the bar.
+    pub fn colors(mut self, foreground: &str, delimiter: &str, background: &str) -> Self {
+        self.color_fg = foreground.to_owned();
+        self.color_delim = delimiter.to_owned();
+        self.color_bg = background.to_owned();
+        self
+    }
+
+    /// Set if a separator line should be drawn between the bar and the
+    /// windows.
+    ///
+    /// # Arguments
+    ///
+    /// * `separator` - True if the separator should be drawn.
+    pub fn separator(mut self, separator: bool) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Add an item to the bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The name of the bar item, e.g. `buffer_name` or the name
+    ///     of a `BarItem`.
+    pub fn add_item<T: Into<String>>(mut self, item: T) -> Self {
+        self.items.push(item.into());
+        self
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// A handle to a bar, the bar is removed when the object is dropped.
+///
+/// Bars are stored in the Weechat configuration, dropping the handle removes
+/// the bar so it doesn't outlive the plugin.
+///
+/// The user may delete the bar, e.g. using `/bar del`, while the handle is
+/// alive. The handle looks the bar up by its name before every operation,
+/// operations on a deleted bar return an error and dropping the handle
+/// doesn't do anything.
+pub struct Bar {
+    name: String,
+    ptr: *mut t_gui_bar,
+    weechat: *mut t_weechat_plugin,
+}
+
+impl Drop for Bar {
+    fn drop(&mut self) {
+        if let Some(ptr) = self.search() {
+            let weechat = Weechat::from_ptr(self.weechat);
+            let bar_remove = weechat.get().bar_remove.unwrap();
+            unsafe { bar_remove(ptr) };
+        }
+    }
+}
+
+impl Bar {
+    /// Create a new bar.
+    ///
+    /// Returns an error if a bar with the same name already exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The settings of the new bar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::Buffer;
+    /// # use weechat::hooks::{Bar, BarItem, BarPosition, BarSettings, BarType};
+    /// let item = BarItem::new("rooms", |_: &Weechat, _: &Buffer| "#rust".to_owned())
+    ///     .expect("Can't create the bar item");
+    ///
+    /// let bar = Bar::new(
+    ///     BarSettings::new("rooms")
+    ///         .bar_type(BarType::Root)
+    ///         .position(BarPosition::Left)
+    ///         .size(20)
+    ///         .add_item("rooms"),
+    /// )
+    /// .expect("Can't create the bar");
+    ///
+    /// bar.hide().unwrap();
+    /// ```
+    pub fn new(settings: BarSettings) -> Result<Bar, ()> {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let bar_search = weechat.get().bar_search.unwrap();
+        let bar_new = weechat.get().bar_new.unwrap();
+
+        let name = LossyCString::new(&settings.name);
+
+        if !unsafe { bar_search(name.as_ptr()) }.is_null() {
+            return Err(());
+        }
+
+        let hidden = LossyCString::new(on_off(settings.hidden));
+        let priority = LossyCString::new(settings.priority.to_string());
+        let bar_type = LossyCString::new(settings.bar_type.as_str());
+        let condition = LossyCString::new(&settings.condition);
+        let position = LossyCString::new(settings.position.as_str());
+        let filling = LossyCString::new("horizontal");
+        let filling_left_right = LossyCString::new("vertical");
+        let size = LossyCString::new(settings.size.to_string());
+        let size_max = LossyCString::new(settings.size_max.to_string());
+        let color_fg = LossyCString::new(&settings.color_fg);
+        let color_delim = LossyCString::new(&settings.color_delim);
+        let color_bg = LossyCString::new(&settings.color_bg);
+        let color_bg_inactive = LossyCString::new(&settings.color_bg);
+        let separator = LossyCString::new(on_off(settings.separator));
+        let items = LossyCString::new(settings.items.join(","));
+
+        let ptr = unsafe {
+            bar_new(
+                name.as_ptr(),
+                hidden.as_ptr(),
+                priority.as_ptr(),
+                bar_type.as_ptr(),
+                condition.as_ptr(),
+                position.as_ptr(),
+                filling.as_ptr(),
+                filling_left_right.as_ptr(),
+                size.as_ptr(),
+                size_max.as_ptr(),
+                color_fg.as_ptr(),
+                color_delim.as_ptr(),
+                color_bg.as_ptr(),
+                color_bg_inactive.as_ptr(),
+                separator.as_ptr(),
+                items.as_ptr(),
+            )
+        };
+
+        if ptr.is_null() {
+            Err(())
+        } else {
+            Ok(Bar {
+                name: settings.name,
+                ptr,
+                weechat: weechat.ptr,
+            })
+        }
+    }
+
+    /// Get the pointer of the bar if the bar still exists.
+    ///
+    /// The pointer is only returned if the bar with our name is the one we
+    /// created, the user might have deleted our bar and created a new one
+    /// with the same name.
+    fn search(&self) -> Option<*mut t_gui_bar> {
+        let weechat = Weechat::from_ptr(self.weechat);
+        let bar_search = weechat.get().bar_search.unwrap();
+
+        let name = LossyCString::new(&self.name);
+        let ptr = unsafe { bar_search(name.as_ptr()) };
+
+        if ptr == self.ptr {
+            Some(ptr)
+        } else {
+            None
+        }
+    }
+
+    fn set(&self, property: &str, value: &str) -> Result<(), ()> {
+        let ptr = self.search().ok_or(())?;
+
+        let weechat = Weechat::from_ptr(self.weechat);
+        let bar_set = weechat.get().bar_set.unwrap();
+
+        let property = LossyCString::new(property);
+        let value = LossyCString::new(value);
+
+        if unsafe { bar_set(ptr, property.as_ptr(), value.as_ptr()) } == 1 {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Get the name of the bar.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Replace the items of the bar.
+    ///
+    /// Returns an error if the bar was deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The names of the items that should be displayed.
+    pub fn set_item_list(&self, items: &[&str]) -> Result<(), ()> {
+        self.set("items", &items.join(","))
+    }
+
+    /// Show the bar.
+    ///
+    /// Returns an error if the bar was deleted.
+    pub fn show(&self) -> Result<(), ()> {
+        self.set("hidden", "off")
+    }
+
+    /// Hide the bar.
+    ///
+    /// Returns an error if the bar was deleted.
+    pub fn hide(&self) -> Result<(), ()> {
+        self.set("hidden", "on")
+    }
+
+    /// Redraw the bar, e.g. after its items changed.
+    pub fn update(&self) {
+        let weechat = Weechat::from_ptr(self.weechat);
+        let bar_update = weechat.get().bar_update.unwrap();
+
+        let name = LossyCString::new(&self.name);
+        unsafe { bar_update(name.as_ptr()) };
+    }
+}
@@ -5,13 +5,15 @@ use weechat_sys::{t_gui_buffer, t_weechat_plugin, WEECHAT_RC_OK};
 
 use crate::{buffer::Buffer, Args, LossyCString, ReturnCode, Weechat};
 
-use super::Hook;
+use super::{run_callback, Hook, HookData};
 
 /// Hook for a weechat command, the command is removed when the object is
 /// dropped.
+///
+/// The command may be dropped inside of its own callback.
 pub struct Command {
     _hook: Hook,
-    _hook_data: Box<CommandHookData>,
+    _hook_data: HookData<CommandHookData>,
 }
 
 /// Trait for the command callback
@@ -112,7 +114,8 @@ impl CommandSettings {
     ///
     /// # Arguments
     ///
-    /// * `completion` - The completion that should be added to the command.
+    /// * `completion` - The completion that should be added to the command,
+    ///     either a template string or a `CompletionTemplate`.
     pub fn add_completion<T: Into<String>>(mut self, completion: T) -> Self {
         self.completion.push(completion.into());
         self
@@ -125,9 +128,32 @@ struct CommandHookData {
 }
 
 /// Hook for a weechat command, the hook is removed when the object is dropped.
+///
+/// Dropping the hook inside of its own callback is supported, the hook is
+/// removed once the callback returned.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::{borrow::Cow, cell::RefCell, rc::Rc};
+/// # use weechat::{Weechat, ReturnCode};
+/// # use weechat::buffer::Buffer;
+/// # use weechat::hooks::CommandRun;
+/// let hook: Rc<RefCell<Option<CommandRun>>> = Rc::new(RefCell::new(None));
+/// let hook_clone = hook.clone();
+///
+/// // Intercept the next /quit command only.
+/// *hook.borrow_mut() = Some(
+///     CommandRun::new("/quit", move |_: &Weechat, _: &Buffer, _: Cow<str>| {
+///         hook_clone.borrow_mut().take();
+///         ReturnCode::OkEat
+///     })
+///     .unwrap(),
+/// );
+/// ```
 pub struct CommandRun {
     _hook: Hook,
-    _hook_data: Box<CommandRunHookData>,
+    _hook_data: HookData<CommandRunHookData>,
 }
 
 /// Trait for the command-run callback
@@ -202,7 +228,7 @@ impl CommandRun {
             let buffer = weechat.buffer_from_ptr(buffer);
             let command = CStr::from_ptr(command).to_string_lossy();
 
-            cb.callback(&weechat, &buffer, command) as isize as i32
+            run_callback(|| cb.callback(&weechat, &buffer, command)) as isize as i32
         }
 
         Weechat::check_thread();
@@ -227,7 +253,7 @@ impl CommandRun {
                 ptr::null_mut(),
             )
         };
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
 
         if hook_ptr.is_null() {
             Err(())
@@ -308,7 +334,7 @@ impl Command {
             let cb = &mut hook_data.callback;
             let args = Args::new(argc, argv);
 
-            cb.callback(&weechat, &buffer, args);
+            run_callback(|| cb.callback(&weechat, &buffer, args));
 
             WEECHAT_RC_OK
         }
@@ -343,7 +369,7 @@ impl Command {
                 ptr::null_mut(),
             )
         };
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
 
         let hook = Hook {
             ptr: hook_ptr,
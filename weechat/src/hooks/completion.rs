@@ -5,7 +5,12 @@ use weechat_sys::{
     t_gui_buffer, t_gui_completion, t_weechat_plugin, WEECHAT_RC_ERROR, WEECHAT_RC_OK,
 };
 
-use crate::{buffer::Buffer, hooks::Hook, LossyCString, Weechat};
+use crate::{
+    buffer::Buffer,
+    hooks::{run_callback, Hook, HookData},
+    util::NameKind,
+    LossyCString, Weechat,
+};
 
 /// A handle to a completion item.
 pub struct Completion {
@@ -148,9 +153,11 @@ impl Completion {
 }
 
 /// Hook for a completion item, the hook is removed when the object is dropped.
+///
+/// The hook may be dropped inside of its own callback.
 pub struct CompletionHook {
     _hook: Hook,
-    _hook_data: Box<CompletionHookData>,
+    _hook_data: HookData<CompletionHookData>,
 }
 
 struct CompletionHookData {
@@ -165,7 +172,9 @@ impl CompletionHook {
     /// # Arguments
     ///
     /// * `name` - The name of the new completion. After this is created the
-    ///     can be used as `%(name)` when creating commands.
+    ///     can be used as `%(name)` when creating commands. The creation fails
+    ///     if the name isn't valid according to `Weechat::validate_name()`,
+    ///     e.g. if it contains a space.
     ///
     /// * `description` - The description of the new completion.
     ///
@@ -201,6 +210,17 @@ impl CompletionHook {
     ///     servers,
     /// ).unwrap();
     ///
+    /// // A closure can be used for simple completions as well.
+    /// let colors = CompletionHook::new(
+    ///     "my_colors",
+    ///     "Completion for a couple of colors",
+    ///     |_: &Weechat, _: &Buffer, _: Cow<str>, completion: &Completion| {
+    ///         for color in &["red", "green", "blue"] {
+    ///             completion.add(color);
+    ///         }
+    ///         Ok(())
+    ///     },
+    /// ).unwrap();
     /// ```
     pub fn new(
         completion_item: &str,
@@ -221,12 +241,8 @@ impl CompletionHook {
 
             let completion_item = CStr::from_ptr(completion_item).to_string_lossy();
 
-            let ret = cb.callback(
-                &weechat,
-                &buffer,
-                completion_item,
-                &Completion::from_raw(hook_data.weechat_ptr, completion),
-            );
+            let completion = Completion::from_raw(hook_data.weechat_ptr, completion);
+            let ret = run_callback(|| cb.callback(&weechat, &buffer, completion_item, &completion));
 
             if let Ok(()) = ret {
                 WEECHAT_RC_OK
@@ -238,6 +254,10 @@ impl CompletionHook {
         Weechat::check_thread();
         let weechat = unsafe { Weechat::weechat() };
 
+        if Weechat::validate_name(NameKind::Completion, completion_item).is_err() {
+            return Err(());
+        }
+
         let data = Box::new(CompletionHookData {
             callback: Box::new(callback),
             weechat_ptr: weechat.ptr,
@@ -260,7 +280,7 @@ impl CompletionHook {
             )
         };
 
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
 
         if hook_ptr.is_null() {
             return Err(());
@@ -277,3 +297,289 @@ impl CompletionHook {
         })
     }
 }
+
+/// A single alternative of an argument in a completion template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionItem {
+    /// An empty alternative, this makes the argument optional. Only allowed
+    /// as the first alternative of an argument.
+    Empty,
+    /// A literal word, e.g. `add`.
+    Literal(String),
+    /// A named completion item, e.g. `irc_servers` for `%(irc_servers)`.
+    Completion(String),
+    /// Stop the completion, no completion is done for this and the
+    /// following arguments (`%-`).
+    StopHere,
+    /// Repeat the completion of the previous argument for all the
+    /// following arguments (`%*`).
+    RepeatLast,
+}
+
+impl CompletionItem {
+    fn is_valid(&self) -> bool {
+        let is_valid_word = |word: &str, forbidden: &[char]| {
+            !word.is_empty()
+                && !word
+                    .chars()
+                    .any(|c| c.is_whitespace() || c == '|' || forbidden.contains(&c))
+        };
+
+        match self {
+            CompletionItem::Literal(word) => is_valid_word(word, &[]) && !word.starts_with('%'),
+            CompletionItem::Completion(name) => is_valid_word(name, &['(', ')']),
+            CompletionItem::Empty | CompletionItem::StopHere | CompletionItem::RepeatLast => true,
+        }
+    }
+
+    fn parse(item: &str) -> Result<Self, ()> {
+        let item = match item {
+            "" => CompletionItem::Empty,
+            "%-" => CompletionItem::StopHere,
+            "%*" => CompletionItem::RepeatLast,
+            _ => {
+                if let Some(name) = item.strip_prefix("%(") {
+                    CompletionItem::Completion(name.strip_suffix(')').ok_or(())?.to_owned())
+                } else {
+                    CompletionItem::Literal(item.to_owned())
+                }
+            }
+        };
+
+        if item.is_valid() {
+            Ok(item)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl std::fmt::Display for CompletionItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompletionItem::Empty => Ok(()),
+            CompletionItem::Literal(word) => f.write_str(word),
+            CompletionItem::Completion(name) => write!(f, "%({})", name),
+            CompletionItem::StopHere => f.write_str("%-"),
+            CompletionItem::RepeatLast => f.write_str("%*"),
+        }
+    }
+}
+
+/// A completion template for a command.
+///
+/// A template describes the completion of the command arguments, every
+/// argument has a list of alternatives. The template serializes to the
+/// string format Weechat expects, arguments are separated by spaces and
+/// alternatives by `|`. It can be passed to
+/// `CommandSettings::add_completion()`.
+///
+/// # Example
+///
+/// ```
+/// # use weechat::hooks::{CompletionItem, CompletionTemplate};
+/// let template = CompletionTemplate::new()
+///     .literals(&["list", "add", "del"])
+///     .completion("irc_servers")
+///     .stop();
+///
+/// assert_eq!(template.to_string(), "list|add|del %(irc_servers) %-");
+///
+/// let template = CompletionTemplate::new().argument(vec![
+///     CompletionItem::Completion("filename".to_owned()),
+///     CompletionItem::RepeatLast,
+/// ]);
+///
+/// assert_eq!(template.to_string(), "%(filename)|%*");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionTemplate {
+    arguments: Vec<Vec<CompletionItem>>,
+}
+
+impl CompletionTemplate {
+    /// Create a new empty completion template.
+    pub fn new() -> Self {
+        CompletionTemplate::default()
+    }
+
+    /// Add an argument with the given alternatives to the template.
+    ///
+    /// # Arguments
+    ///
+    /// * `alternatives` - The alternatives of the argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alternatives are empty, only consist of an empty
+    /// alternative, contain an empty alternative that isn't the first one,
+    /// or contain a literal or a completion name that is empty or contains
+    /// whitespace or a `|`. Literals may not start with a `%` and completion
+    /// names may not contain parentheses.
+    pub fn argument(mut self, alternatives: Vec<CompletionItem>) -> Self {
+        assert!(
+            CompletionTemplate::is_valid_argument(&alternatives),
+            "Invalid completion template argument {:?}",
+            alternatives
+        );
+
+        self.arguments.push(alternatives);
+        self
+    }
+
+    /// Add an argument that completes one of the given literal words.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The words that should be completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `argument()`.
+    pub fn literals(self, words: &[&str]) -> Self {
+        self.argument(
+            words
+                .iter()
+                .map(|word| CompletionItem::Literal((*word).to_owned()))
+                .collect(),
+        )
+    }
+
+    /// Add an argument that uses the named completion item.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the completion item, e.g. `buffers_names`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `argument()`.
+    pub fn completion(self, name: &str) -> Self {
+        self.argument(vec![CompletionItem::Completion(name.to_owned())])
+    }
+
+    /// Add an optional argument with the given alternatives.
+    ///
+    /// # Arguments
+    ///
+    /// * `alternatives` - The alternatives of the argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `argument()`.
+    pub fn optional(self, alternatives: Vec<CompletionItem>) -> Self {
+        let mut items = vec![CompletionItem::Empty];
+        items.extend(alternatives);
+
+        self.argument(items)
+    }
+
+    /// Stop the completion, no completion is done for the following
+    /// arguments.
+    pub fn stop(self) -> Self {
+        self.argument(vec![CompletionItem::StopHere])
+    }
+
+    /// Repeat the completion of the previous argument for all the following
+    /// arguments.
+    pub fn repeat(self) -> Self {
+        self.argument(vec![CompletionItem::RepeatLast])
+    }
+
+    /// Get the arguments of the template.
+    pub fn arguments(&self) -> &[Vec<CompletionItem>] {
+        &self.arguments
+    }
+
+    /// Parse a completion template string.
+    ///
+    /// Returns an error if the string isn't a valid single template, e.g. if
+    /// it contains multiple templates separated by `||`.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The template string in the format Weechat uses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use weechat::hooks::CompletionTemplate;
+    /// let templates = [
+    ///     "list|add|del %(irc_servers) %-",
+    ///     "%(filename)|%*",
+    ///     "server |add|delete|list|listfull",
+    ///     "-all %(irc_channel)|%(nicks) %-",
+    ///     "%(python_script)|%(perl_script)|%*",
+    ///     "%(buffers_plugins_names)|%(buffers_names) %(buffer_properties_set)",
+    /// ];
+    ///
+    /// for template in templates.iter() {
+    ///     let parsed = CompletionTemplate::parse(template).unwrap();
+    ///     assert_eq!(&parsed.to_string(), template);
+    /// }
+    ///
+    /// assert!(CompletionTemplate::parse("add||del").is_err());
+    /// assert!(CompletionTemplate::parse("%(irc_servers").is_err());
+    /// assert!(CompletionTemplate::parse("add  del").is_err());
+    /// ```
+    pub fn parse(template: &str) -> Result<Self, ()> {
+        let arguments = template
+            .split(' ')
+            .map(|argument| {
+                argument
+                    .split('|')
+                    .map(CompletionItem::parse)
+                    .collect::<Result<Vec<_>, ()>>()
+            })
+            .collect::<Result<Vec<_>, ()>>()?;
+
+        if arguments
+            .iter()
+            .all(|argument| CompletionTemplate::is_valid_argument(argument))
+        {
+            Ok(CompletionTemplate { arguments })
+        } else {
+            Err(())
+        }
+    }
+
+    fn is_valid_argument(alternatives: &[CompletionItem]) -> bool {
+        let empty_count = alternatives
+            .iter()
+            .filter(|item| **item == CompletionItem::Empty)
+            .count();
+
+        let empty_is_first = empty_count == 0
+            || (empty_count == 1 && alternatives.first() == Some(&CompletionItem::Empty));
+
+        alternatives.len() > empty_count
+            && empty_is_first
+            && alternatives.iter().all(CompletionItem::is_valid)
+    }
+}
+
+impl std::fmt::Display for CompletionTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, argument) in self.arguments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            for (j, item) in argument.iter().enumerate() {
+                if j > 0 {
+                    f.write_str("|")?;
+                }
+
+                write!(f, "{}", item)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<CompletionTemplate> for String {
+    fn from(template: CompletionTemplate) -> Self {
+        template.to_string()
+    }
+}
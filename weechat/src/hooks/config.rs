@@ -0,0 +1,217 @@
+use libc::{c_char, c_int};
+use std::{ffi::CStr, os::raw::c_void, ptr};
+
+use weechat_sys::{t_weechat_plugin, WEECHAT_RC_OK};
+
+use super::{run_callback, Hook, HookData};
+use crate::{config::OptionValue, LossyCString, Weechat};
+
+/// Hook for config option changes, the hook is removed when the object is
+/// dropped, this may happen inside of its own callback.
+pub struct ConfigHook {
+    _hook: Hook,
+    _hook_data: HookData<ConfigHookData>,
+}
+
+struct ConfigHookData {
+    callback: Box<dyn ConfigCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+/// Trait for the config hook callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait ConfigCallback {
+    /// Callback that will be called when a config option changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `option_name` - The full name of the option that changed (format:
+    /// "file.section.option").
+    ///
+    /// * `value` - The new value of the option as a string, `None` if the
+    /// option was removed.
+    fn callback(&mut self, weechat: &Weechat, option_name: &str, value: Option<&str>);
+}
+
+impl<T: FnMut(&Weechat, &str, Option<&str>) + 'static> ConfigCallback for T {
+    fn callback(&mut self, weechat: &Weechat, option_name: &str, value: Option<&str>) {
+        self(weechat, option_name, value)
+    }
+}
+
+/// Trait for the typed config hook callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait OptionValueCallback {
+    /// Callback that will be called when a config option changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `option_name` - The full name of the option that changed (format:
+    /// "file.section.option").
+    ///
+    /// * `value` - The new value of the option, decoded into the type of the
+    /// option.
+    fn callback(&mut self, weechat: &Weechat, option_name: &str, value: OptionValue);
+}
+
+impl<T: FnMut(&Weechat, &str, OptionValue) + 'static> OptionValueCallback for T {
+    fn callback(&mut self, weechat: &Weechat, option_name: &str, value: OptionValue) {
+        self(weechat, option_name, value)
+    }
+}
+
+impl ConfigHook {
+    /// Hook config option changes.
+    ///
+    /// Any option can be watched, including options that are owned by
+    /// Weechat or by other plugins. The callback receives the new value as a
+    /// string, use `new_typed()` to receive the value decoded into the type of
+    /// the option.
+    ///
+    /// # Arguments
+    ///
+    /// * `option_mask` - The full name of the option that should be watched
+    /// (format: "file.section.option"), the wildcard `*` is allowed.
+    ///
+    /// * `callback` - A function or a struct that implements ConfigCallback,
+    /// the callback method of the trait will be called when a matching
+    /// option changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::hooks::ConfigHook;
+    /// let hook = ConfigHook::new(
+    ///     "weechat.look.*",
+    ///     |_: &Weechat, option_name: &str, value: Option<&str>| {
+    ///         Weechat::print(&format!("{} changed to {:?}", option_name, value));
+    ///     },
+    /// )
+    /// .expect("Can't hook the options");
+    /// ```
+    pub fn new(option_mask: &str, callback: impl ConfigCallback + 'static) -> Result<Self, ()> {
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            option_name: *const c_char,
+            value: *const c_char,
+        ) -> c_int {
+            let hook_data: &mut ConfigHookData = { &mut *(pointer as *mut ConfigHookData) };
+            let cb = &mut hook_data.callback;
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+            let option_name = CStr::from_ptr(option_name).to_string_lossy();
+            let value = if value.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(value).to_string_lossy())
+            };
+
+            run_callback(|| cb.callback(&weechat, &option_name, value.as_deref()));
+
+            WEECHAT_RC_OK
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = Box::new(ConfigHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_config = weechat.get().hook_config.unwrap();
+
+        let option_mask = LossyCString::new(option_mask);
+
+        let hook_ptr = unsafe {
+            hook_config(
+                weechat.ptr,
+                option_mask.as_ptr(),
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+        let hook = Hook {
+            ptr: hook_ptr,
+            weechat_ptr: weechat.ptr,
+        };
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(ConfigHook {
+                _hook: hook,
+                _hook_data: hook_data,
+            })
+        }
+    }
+
+    /// Hook config option changes and receive the new value decoded into the
+    /// type of the option.
+    ///
+    /// The option is looked up using `Weechat::config_get()` every time it
+    /// changes, so a boolean option will be delivered as a `bool`, an integer
+    /// option as an `i32` and so on. Changes of options that can't be found
+    /// anymore are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `option_mask` - The full name of the option that should be watched
+    /// (format: "file.section.option"), the wildcard `*` is allowed.
+    ///
+    /// * `callback` - A function or a struct that implements
+    /// OptionValueCallback, the callback method of the trait will be called
+    /// when a matching option changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::config::OptionValue;
+    /// # use weechat::hooks::ConfigHook;
+    /// let hook = ConfigHook::new_typed(
+    ///     "irc.look.buffer_switch_autojoin",
+    ///     |_: &Weechat, _: &str, value: OptionValue| {
+    ///         if let OptionValue::Boolean(autojoin) = value {
+    ///             Weechat::print(&format!("Switching on autojoin: {}", autojoin));
+    ///         }
+    ///     },
+    /// )
+    /// .expect("Can't hook the option");
+    /// ```
+    pub fn new_typed(
+        option_mask: &str,
+        mut callback: impl OptionValueCallback + 'static,
+    ) -> Result<Self, ()> {
+        ConfigHook::new(
+            option_mask,
+            move |weechat: &Weechat, option_name: &str, _: Option<&str>| {
+                if let Some(option) = weechat.config_get(option_name) {
+                    callback.callback(weechat, option_name, option.value());
+                }
+            },
+        )
+    }
+}
@@ -1,15 +1,19 @@
 use libc::c_int;
 use std::{
-    os::{raw::c_void, unix::io::AsRawFd},
+    os::{
+        raw::c_void,
+        unix::io::{AsRawFd, RawFd},
+    },
     ptr,
 };
 
 use weechat_sys::{t_weechat_plugin, WEECHAT_RC_OK};
 
-use super::Hook;
+use super::{run_callback, Hook, HookData};
 use crate::Weechat;
 
 /// Setting for the FdHook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FdHookMode {
     /// Catch read events.
     Read,
@@ -37,9 +41,12 @@ impl FdHookMode {
 }
 
 /// Hook for a file descriptor, the hook is removed when the object is dropped.
-pub struct FdHook<F> {
+///
+/// The hook may be dropped inside of its own callback, e.g. once the other
+/// end of a socket closed the connection.
+pub struct FdHook<F: 'static> {
     _hook: Hook,
-    _hook_data: Box<FdHookData<F>>,
+    _hook_data: HookData<FdHookData<F>>,
 }
 
 /// Callback trait for file descriptor based hooks.
@@ -58,13 +65,25 @@ pub trait FdHookCallback {
     fn callback(&mut self, weechat: &Weechat, fd_object: &mut Self::FdObject);
 }
 
+/// Callback for hooks on a raw file descriptor.
+///
+/// The file descriptor isn't owned by the hook, it needs to stay open as
+/// long as the hook exists.
+impl<T: FnMut(&Weechat, &mut RawFd) + 'static> FdHookCallback for T {
+    type FdObject = RawFd;
+
+    fn callback(&mut self, weechat: &Weechat, fd: &mut RawFd) {
+        self(weechat, fd)
+    }
+}
+
 struct FdHookData<F> {
     callback: Box<dyn FdHookCallback<FdObject = F>>,
     weechat_ptr: *mut t_weechat_plugin,
     fd_object: F,
 }
 
-impl<F> FdHook<F> {
+impl<F: 'static> FdHook<F> {
     /// Hook an object that can be turned into a raw file descriptor.
     /// Returns the hook object.
     ///
@@ -113,6 +132,30 @@ impl<F> FdHook<F> {
     ///     .expect("Can't create executor FD hook");
     ///
     /// ```
+    ///
+    /// A raw file descriptor can be hooked using a closure:
+    ///
+    /// ```no_run
+    /// # use std::io::{Read, Write};
+    /// # use std::os::unix::{io::{AsRawFd, RawFd}, net::UnixStream};
+    /// # use weechat::{Weechat, hooks::{FdHook, FdHookMode}};
+    /// let (mut writer, mut reader) = UnixStream::pair().unwrap();
+    ///
+    /// let hook = FdHook::new(
+    ///     reader.as_raw_fd(),
+    ///     FdHookMode::Read,
+    ///     move |_: &Weechat, _: &mut RawFd| {
+    ///         let mut buffer = [0; 64];
+    ///
+    ///         if let Ok(n) = reader.read(&mut buffer) {
+    ///             Weechat::print(&format!("Read {} bytes", n));
+    ///         }
+    ///     },
+    /// )
+    /// .expect("Can't hook the socket");
+    ///
+    /// writer.write_all(b"ping").unwrap();
+    /// ```
     pub fn new(
         fd_object: F,
         mode: FdHookMode,
@@ -131,7 +174,7 @@ impl<F> FdHook<F> {
             let mut fd_object = &mut hook_data.fd_object;
             let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
 
-            cb.callback(&weechat, &mut fd_object);
+            run_callback(|| cb.callback(&weechat, &mut fd_object));
 
             WEECHAT_RC_OK
         }
@@ -164,7 +207,7 @@ impl<F> FdHook<F> {
             )
         };
 
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
         let hook = Hook {
             ptr: hook_ptr,
             weechat_ptr: weechat.ptr,
@@ -180,3 +223,45 @@ impl<F> FdHook<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn closures_are_called_with_the_hooked_fd() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let (read_fd, write_fd) = (fds[0], fds[1]);
+
+        let written = unsafe { libc::write(write_fd, b"hello".as_ptr() as *const libc::c_void, 5) };
+        assert_eq!(written, 5);
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let r = received.clone();
+
+        // This is the same data the C callback of the hook receives.
+        let mut data = FdHookData {
+            callback: Box::new(move |_: &Weechat, fd: &mut RawFd| {
+                let mut buffer = [0u8; 16];
+                let read = unsafe {
+                    libc::read(*fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len())
+                };
+                r.borrow_mut().extend_from_slice(&buffer[..read as usize]);
+            }),
+            weechat_ptr: ptr::NonNull::dangling().as_ptr(),
+            fd_object: read_fd,
+        };
+        let weechat = Weechat::from_ptr(data.weechat_ptr);
+
+        run_callback(|| data.callback.callback(&weechat, &mut data.fd_object));
+
+        assert_eq!(received.borrow().as_slice(), b"hello");
+
+        unsafe {
+            libc::close(read_fd);
+            libc::close(write_fd);
+        }
+    }
+}
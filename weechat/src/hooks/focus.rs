@@ -0,0 +1,138 @@
+use std::{os::raw::c_void, ptr};
+
+use weechat_sys::{t_hashtable, t_weechat_plugin};
+
+use super::{run_callback, Hook, HookData};
+use crate::{Hashtable, LossyCString, Weechat};
+
+/// Hook for the focus of the mouse or the cursor, the hook is removed when
+/// the object is dropped.
+///
+/// The hook may be dropped inside of its own callback.
+pub struct FocusHook {
+    _hook: Hook,
+    _hook_data: HookData<FocusHookData>,
+}
+
+struct FocusHookData {
+    callback: Box<dyn FocusCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+/// Trait for the focus callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait FocusCallback {
+    /// Callback that will be called when a mouse or cursor action happens on
+    /// the hooked area.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `info` - The information about the focused area, e.g. `_buffer`,
+    ///     `_chat_line_message` or `nick`, see the Weechat [documentation] for
+    ///     the full list. Keys that the callback adds are passed to the
+    ///     other focus hooks and to the command of the key binding.
+    ///
+    /// [documentation]: https://weechat.org/files/doc/stable/weechat_plugin_api.en.html#_hook_focus
+    fn callback(&mut self, weechat: &Weechat, info: &mut Hashtable);
+}
+
+impl<T: FnMut(&Weechat, &mut Hashtable) + 'static> FocusCallback for T {
+    fn callback(&mut self, weechat: &Weechat, info: &mut Hashtable) {
+        self(weechat, info)
+    }
+}
+
+impl FocusHook {
+    /// Hook the focus of an area.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - The area that should be hooked, `chat` for the chat area
+    ///     or the name of a bar item.
+    ///
+    /// * `callback` - A function or a struct that implements FocusCallback,
+    ///     the callback method of the trait will be called when the focus
+    ///     information of the area is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::{Hashtable, Weechat};
+    /// # use weechat::hooks::FocusHook;
+    /// // Add the id of the clicked message, it can be used as
+    /// // ${message_id} in the command of a mouse key binding.
+    /// let hook = FocusHook::new("chat", |_: &Weechat, info: &mut Hashtable| {
+    ///     let tags = info.get("_chat_line_tags").unwrap_or_default().into_owned();
+    ///
+    ///     if let Some(id) = tags.split(',').find_map(|t| t.strip_prefix("id_")) {
+    ///         let _ = info.set("message_id", id);
+    ///     }
+    /// })
+    /// .expect("Can't hook the focus");
+    /// ```
+    pub fn new(area: &str, callback: impl FocusCallback + 'static) -> Result<Self, ()> {
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            info: *mut t_hashtable,
+        ) -> *mut t_hashtable {
+            let hook_data: &mut FocusHookData = { &mut *(pointer as *mut FocusHookData) };
+            let cb = &mut hook_data.callback;
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+            let mut hashtable = Hashtable::from_ptr(&weechat, info);
+
+            run_callback(|| cb.callback(&weechat, &mut hashtable));
+
+            // The keys were added to the info hashtable itself, returning it
+            // tells Weechat to keep it as it is.
+            info
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = Box::new(FocusHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_focus = weechat.get().hook_focus.unwrap();
+
+        let area = LossyCString::new(area);
+
+        let hook_ptr = unsafe {
+            hook_focus(
+                weechat.ptr,
+                area.as_ptr(),
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+        let hook = Hook {
+            ptr: hook_ptr,
+            weechat_ptr: weechat.ptr,
+        };
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(FocusHook {
+                _hook: hook,
+                _hook_data: hook_data,
+            })
+        }
+    }
+}
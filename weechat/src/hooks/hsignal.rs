@@ -0,0 +1,190 @@
+use libc::{c_char, c_int};
+use std::{ffi::CStr, os::raw::c_void, ptr};
+
+use weechat_sys::{t_hashtable, t_weechat_plugin};
+
+use super::{run_callback, Hook, HookData};
+use crate::{Hashtable, LossyCString, ReturnCode, Weechat};
+
+/// Hook for a hsignal, the hook is removed when the object is dropped.
+///
+/// Hsignals are signals that carry a hashtable instead of a single value,
+/// e.g. the `irc_redirection_*` signals of the IRC plugin.
+pub struct HSignalHook {
+    _hook: Hook,
+    _hook_data: HookData<HSignalHookData>,
+}
+
+struct HSignalHookData {
+    callback: Box<dyn HSignalCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+/// Trait for the hsignal callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait HSignalCallback {
+    /// Callback that will be called when a hsignal is fired.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `signal_name` - The name of the signal that fired the callback.
+    ///
+    /// * `hashtable` - The hashtable that was sent with the signal, an empty
+    ///     hashtable if the signal was sent without one.
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        signal_name: &str,
+        hashtable: &Hashtable,
+    ) -> ReturnCode;
+}
+
+impl<T: FnMut(&Weechat, &str, &Hashtable) -> ReturnCode + 'static> HSignalCallback for T {
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        signal_name: &str,
+        hashtable: &Hashtable,
+    ) -> ReturnCode {
+        self(weechat, signal_name, hashtable)
+    }
+}
+
+impl HSignalHook {
+    /// Hook a hsignal.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_name` - The signal to hook (wildcard `*` is allowed).
+    ///
+    /// * `callback` - A function or a struct that implements HSignalCallback,
+    /// the callback method of the trait will be called when the signal is
+    /// fired.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::{cell::Cell, rc::Rc};
+    /// # use weechat::{Hashtable, Weechat, ReturnCode};
+    /// # use weechat::hooks::HSignalHook;
+    /// let called = Rc::new(Cell::new(false));
+    /// let called_clone = called.clone();
+    ///
+    /// let hook = HSignalHook::new(
+    ///     "my_plugin_event",
+    ///     move |_: &Weechat, _: &str, hashtable: &Hashtable| {
+    ///         assert_eq!(hashtable.get("server").as_deref(), Some("libera"));
+    ///         assert_eq!(hashtable.get("channel").as_deref(), Some("#rust"));
+    ///         called_clone.set(true);
+    ///
+    ///         ReturnCode::Ok
+    ///     },
+    /// )
+    /// .expect("Can't hook the hsignal");
+    ///
+    /// let hashtable: Hashtable = vec![("server", "libera"), ("channel", "#rust")]
+    ///     .into_iter()
+    ///     .collect();
+    ///
+    /// Weechat::hook_hsignal_send("my_plugin_event", &hashtable);
+    /// assert!(called.get());
+    /// ```
+    pub fn new(signal_name: &str, callback: impl HSignalCallback + 'static) -> Result<Self, ()> {
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            signal_name: *const c_char,
+            hashtable: *mut t_hashtable,
+        ) -> c_int {
+            let hook_data: &mut HSignalHookData = { &mut *(pointer as *mut HSignalHookData) };
+            let cb = &mut hook_data.callback;
+
+            let signal_name = CStr::from_ptr(signal_name).to_str().unwrap_or_default();
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+
+            if hashtable.is_null() {
+                let hashtable = Hashtable::new();
+                run_callback(|| cb.callback(&weechat, signal_name, &hashtable)) as i32
+            } else {
+                let hashtable = Hashtable::from_ptr(&weechat, hashtable);
+                run_callback(|| cb.callback(&weechat, signal_name, &hashtable)) as i32
+            }
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = Box::new(HSignalHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_hsignal = weechat.get().hook_hsignal.unwrap();
+
+        let signal_name = LossyCString::new(signal_name);
+
+        let hook_ptr = unsafe {
+            hook_hsignal(
+                weechat.ptr,
+                signal_name.as_ptr(),
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+        let hook = Hook {
+            ptr: hook_ptr,
+            weechat_ptr: weechat.ptr,
+        };
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(HSignalHook {
+                _hook: hook,
+                _hook_data: hook_data,
+            })
+        }
+    }
+}
+
+impl Weechat {
+    /// Send a hsignal.
+    ///
+    /// This will send out a signal carrying a hashtable, callbacks that are
+    /// registered with a `HSignalHook` to listen to that signal will get
+    /// called.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_name` - The name of the signal that should be sent out.
+    ///
+    /// * `hashtable` - The hashtable that should be provided to the signal
+    ///     callbacks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn hook_hsignal_send(signal_name: &str, hashtable: &Hashtable) -> ReturnCode {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let signal_name = LossyCString::new(signal_name);
+        let hsignal_send = weechat.get().hook_hsignal_send.unwrap();
+
+        let ret = unsafe { hsignal_send(signal_name.as_ptr(), hashtable.ptr()) };
+
+        ReturnCode::from_raw(ret)
+    }
+}
@@ -0,0 +1,166 @@
+use libc::c_char;
+use std::{borrow::Cow, ffi::CStr, os::raw::c_void, ptr};
+
+use weechat_sys::t_weechat_plugin;
+
+use super::{run_callback, Hook, HookData};
+use crate::{LossyCString, Weechat};
+
+/// Hook for an info item, the hook is removed when the object is dropped.
+///
+/// The info can be queried by other plugins and scripts using `info_get()`
+/// or inside of evaluated expressions using `${info:name,arguments}`.
+///
+/// The hook may be dropped inside of its own callback.
+pub struct InfoHook {
+    _hook: Hook,
+    _hook_data: HookData<InfoHookData>,
+}
+
+struct InfoHookData {
+    callback: Box<dyn InfoCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+/// Trait for the info callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait InfoCallback {
+    /// Callback that will be called when the info is queried.
+    ///
+    /// Returns the value of the info or `None` if there is no value for the
+    /// given arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `arguments` - The arguments that were passed while querying the
+    ///     info, an empty string if there were none.
+    fn callback(&mut self, weechat: &Weechat, arguments: Cow<str>) -> Option<String>;
+}
+
+impl<T: FnMut(&Weechat, Cow<str>) -> Option<String> + 'static> InfoCallback for T {
+    fn callback(&mut self, weechat: &Weechat, arguments: Cow<str>) -> Option<String> {
+        self(weechat, arguments)
+    }
+}
+
+impl InfoHook {
+    /// Create a new info item.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the info, by convention it starts with the
+    ///     name of the plugin.
+    ///
+    /// * `description` - The description of the info, displayed with
+    ///     `/help eval` and in the list of infos.
+    ///
+    /// * `callback` - A function or a struct that implements InfoCallback,
+    ///     the callback method of the trait will be called when the info is
+    ///     queried.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::borrow::Cow;
+    /// # use weechat::Weechat;
+    /// # use weechat::hooks::InfoHook;
+    /// let hook = InfoHook::new(
+    ///     "matrix_unread_count",
+    ///     "Number of unread messages in a room",
+    ///     |_: &Weechat, room: Cow<str>| {
+    ///         if room.is_empty() {
+    ///             None
+    ///         } else {
+    ///             Some("3".to_owned())
+    ///         }
+    ///     },
+    /// )
+    /// .expect("Can't hook the info");
+    ///
+    /// assert_eq!(
+    ///     Weechat::info_get("matrix_unread_count", "#rust"),
+    ///     Some("3".to_owned())
+    /// );
+    /// assert_eq!(Weechat::info_get("matrix_unread_count", ""), None);
+    /// ```
+    pub fn new(
+        name: &str,
+        description: &str,
+        callback: impl InfoCallback + 'static,
+    ) -> Result<Self, ()> {
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            _info_name: *const c_char,
+            arguments: *const c_char,
+        ) -> *mut c_char {
+            let hook_data: &mut InfoHookData = { &mut *(pointer as *mut InfoHookData) };
+            let cb = &mut hook_data.callback;
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+            let arguments = if arguments.is_null() {
+                Cow::from("")
+            } else {
+                CStr::from_ptr(arguments).to_string_lossy()
+            };
+
+            let value = run_callback(|| cb.callback(&weechat, arguments));
+
+            // Weechat frees the returned string.
+            match value {
+                Some(value) => libc::strdup(LossyCString::new(value).as_ptr()),
+                None => ptr::null_mut(),
+            }
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = Box::new(InfoHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_info = weechat.get().hook_info.unwrap();
+
+        let name = LossyCString::new(name);
+        let description = LossyCString::new(description);
+        let args_description = LossyCString::new("");
+
+        let hook_ptr = unsafe {
+            hook_info(
+                weechat.ptr,
+                name.as_ptr(),
+                description.as_ptr(),
+                args_description.as_ptr(),
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+        let hook = Hook {
+            ptr: hook_ptr,
+            weechat_ptr: weechat.ptr,
+        };
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(InfoHook {
+                _hook: hook,
+                _hook_data: hook_data,
+            })
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::{os::raw::c_void, ptr};
+
+use weechat_sys::{t_hashtable, t_weechat_plugin};
+
+use super::{run_callback, Hook, HookData};
+use crate::{buffer::BufferType, Hashtable, LossyCString, Weechat};
+
+/// Hook for lines that are added to a buffer, the hook is removed when the
+/// object is dropped.
+///
+/// Unlike a print hook, a line hook is called before the line is displayed
+/// and can change or hide the line.
+///
+/// The hook may be dropped inside of its own callback.
+pub struct LineHook {
+    _hook: Hook,
+    _hook_data: HookData<LineHookData>,
+}
+
+struct LineHookData {
+    callback: Box<dyn LineCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+/// Trait for the line callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait LineCallback {
+    /// Callback that will be called when a line is added to a buffer.
+    ///
+    /// Returns a hashtable with the fields of the line that should be
+    /// changed, `None` if the line should stay as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `line` - The fields of the line, e.g. `buffer_name`, `date`,
+    ///     `tags`, `displayed`, `notify_level`, `highlight`, `prefix` and
+    ///     `message`. The same keys can be used in the returned hashtable,
+    ///     see the Weechat [documentation] for the full list.
+    ///
+    /// [documentation]: https://weechat.org/files/doc/stable/weechat_plugin_api.en.html#_hook_line
+    fn callback(&mut self, weechat: &Weechat, line: &Hashtable) -> Option<Hashtable<'static>>;
+}
+
+impl<T: FnMut(&Weechat, &Hashtable) -> Option<Hashtable<'static>> + 'static> LineCallback for T {
+    fn callback(&mut self, weechat: &Weechat, line: &Hashtable) -> Option<Hashtable<'static>> {
+        self(weechat, line)
+    }
+}
+
+impl LineHook {
+    /// Hook lines that are added to buffers.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_type` - Only lines of buffers with this type are hooked,
+    ///     `None` to hook lines of buffers of any type.
+    ///
+    /// * `buffer_name` - A comma separated list of buffer masks, e.g.
+    ///     `irc.libera.*,!irc.libera.#weechat`, `None` to hook lines of all
+    ///     buffers.
+    ///
+    /// * `tags` - Only lines with one of these tags are hooked, tags can be
+    ///     combined with a `+` to require all of them. An empty list hooks
+    ///     all lines.
+    ///
+    /// * `callback` - A function or a struct that implements LineCallback,
+    ///     the callback method of the trait will be called when a matching
+    ///     line is added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::{Hashtable, Weechat};
+    /// # use weechat::buffer::BufferType;
+    /// # use weechat::hooks::LineHook;
+    /// // Show bots with a different prefix and never highlight on them.
+    /// let hook = LineHook::new(
+    ///     Some(BufferType::Formatted),
+    ///     Some("irc.*"),
+    ///     &["nick_bot"],
+    ///     |_: &Weechat, line: &Hashtable| {
+    ///         let mut update = Hashtable::new();
+    ///         update.set("prefix", &format!("[bot] {}", line.get("prefix")?)).ok()?;
+    ///         update.set("highlight", "0").ok()?;
+    ///         Some(update)
+    ///     },
+    /// )
+    /// .expect("Can't hook lines");
+    /// ```
+    pub fn new(
+        buffer_type: Option<BufferType>,
+        buffer_name: Option<&str>,
+        tags: &[&str],
+        callback: impl LineCallback + 'static,
+    ) -> Result<Self, ()> {
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            line: *mut t_hashtable,
+        ) -> *mut t_hashtable {
+            let hook_data: &mut LineHookData = { &mut *(pointer as *mut LineHookData) };
+            let cb = &mut hook_data.callback;
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+            let line = Hashtable::from_ptr(&weechat, line);
+
+            // Weechat frees the returned hashtable.
+            run_callback(|| cb.callback(&weechat, &line))
+                .map(|update| update.into_raw())
+                .unwrap_or(ptr::null_mut())
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = Box::new(LineHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_line = weechat.get().hook_line.unwrap();
+
+        let buffer_type = LossyCString::new(buffer_type.map(|t| t.as_str()).unwrap_or("*"));
+        let buffer_name = buffer_name.map(LossyCString::new);
+        let tags = if tags.is_empty() {
+            None
+        } else {
+            Some(LossyCString::new(tags.join(",")))
+        };
+
+        let hook_ptr = unsafe {
+            hook_line(
+                weechat.ptr,
+                buffer_type.as_ptr(),
+                buffer_name
+                    .as_ref()
+                    .map(|n| n.as_ptr())
+                    .unwrap_or(ptr::null()),
+                tags.as_ref().map(|t| t.as_ptr()).unwrap_or(ptr::null()),
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+        let hook = Hook {
+            ptr: hook_ptr,
+            weechat_ptr: weechat.ptr,
+        };
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(LineHook {
+                _hook: hook,
+                _hook_data: hook_data,
+            })
+        }
+    }
+}
@@ -2,32 +2,148 @@
 //!
 //! Weechat hooks are used for many different things, to create commands, to
 //! listen to events on a file descriptor, add completions to Weechat, etc.
+//!
+//! Every hook may be dropped from inside of a hook callback, including its
+//! own. Unhooking and freeing the callback is deferred until the callback
+//! returned in that case.
 
 mod signal;
 
 mod bar;
 mod commands;
 mod completion;
+mod config;
 mod fd;
+mod focus;
+mod hsignal;
+mod info;
+mod line;
 #[cfg(feature = "unsound")]
 mod modifier;
+mod print;
+mod process;
 mod timer;
 
-pub use bar::{BarItem, BarItemCallback};
+pub use bar::{Bar, BarItem, BarItemCallback, BarPosition, BarSettings, BarType};
 pub use commands::{Command, CommandCallback, CommandRun, CommandRunCallback, CommandSettings};
-pub use completion::{Completion, CompletionCallback, CompletionHook, CompletionPosition};
+pub use completion::{
+    Completion, CompletionCallback, CompletionHook, CompletionItem, CompletionPosition,
+    CompletionTemplate,
+};
+pub use config::{ConfigCallback, ConfigHook, OptionValueCallback};
 
 pub use fd::{FdHook, FdHookCallback, FdHookMode};
+pub use focus::{FocusCallback, FocusHook};
+pub use hsignal::{HSignalCallback, HSignalHook};
+pub use info::{InfoCallback, InfoHook};
+pub use line::{LineCallback, LineHook};
 #[cfg(feature = "unsound")]
-pub use modifier::{ModifierCallback, ModifierData, ModifierHook};
-pub use signal::{SignalCallback, SignalData, SignalHook};
+pub use modifier::{
+    ModifierCallback, ModifierData, ModifierHook, NickColorCallback, NickColorModifier,
+};
+pub use print::{PrintCallback, PrintHook, PrintedLine};
+pub use process::{ProcessCallback, ProcessHook, ProcessStatus};
+pub use signal::{
+    AwayStatusCallback, AwayStatusHook, BufferProperty, BufferPropertyCallback, BufferPropertyHook,
+    OwnedSignalData, SignalCallback, SignalData, SignalHook,
+};
+
+pub(crate) use signal::free_deferred_signals;
+#[cfg(feature = "async")]
+pub use signal::SignalStream;
 pub use timer::{RemainingCalls, TimerCallback, TimerHook};
 
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    ops::{Deref, DerefMut},
+};
+
 use crate::Weechat;
 use weechat_sys::{t_hook, t_weechat_plugin};
 
+thread_local! {
+    static CALLBACK_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static DEFERRED_DROPS: RefCell<Vec<Box<dyn Any>>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) fn in_callback() -> bool {
+    CALLBACK_DEPTH.with(|depth| depth.get() > 0)
+}
+
+pub(crate) fn defer_drop(value: Box<dyn Any>) {
+    DEFERRED_DROPS.with(|drops| drops.borrow_mut().push(value));
+}
+
+/// Run a hook callback.
+///
+/// Hooks that are dropped while the callback runs, be it the hook that is
+/// being called or another one, are unhooked right away but their data is
+/// only freed once the outermost callback returned. This makes it possible
+/// for a callback to drop its own hook.
+pub(crate) fn run_callback<R>(f: impl FnOnce() -> R) -> R {
+    CALLBACK_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let ret = f();
+    let depth = CALLBACK_DEPTH.with(|depth| {
+        depth.set(depth.get() - 1);
+        depth.get()
+    });
+
+    if depth == 0 {
+        // The drops might drop other hooks, take the list out first so the
+        // list isn't borrowed while that happens.
+        let drops = DEFERRED_DROPS.with(|drops| drops.replace(Vec::new()));
+        for value in drops {
+            drop(value);
+        }
+    }
+
+    ret
+}
+
+/// The data of a hook that is passed to the hook callback.
+///
+/// If the data is dropped while a hook callback runs, freeing the data is
+/// deferred until the callback returned.
+pub(crate) struct HookData<T: 'static>(Option<Box<T>>);
+
+impl<T: 'static> HookData<T> {
+    pub(crate) fn new(data: Box<T>) -> Self {
+        HookData(Some(data))
+    }
+}
+
+impl<T: 'static> Deref for HookData<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl<T: 'static> DerefMut for HookData<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.as_mut().unwrap()
+    }
+}
+
+impl<T: 'static> Drop for HookData<T> {
+    fn drop(&mut self) {
+        if let Some(data) = self.0.take() {
+            if in_callback() {
+                defer_drop(data);
+            }
+        }
+    }
+}
+
 /// Weechat Hook type. The hook is unhooked automatically when the object is
 /// dropped.
+///
+/// Weechat supports unhooking while a hook callback runs, even the hook of
+/// the running callback, so the hook is always unhooked right away and its
+/// callback won't be called anymore. The `HookData` of the hook takes care
+/// of keeping the callback alive until the running callback returned.
 pub(crate) struct Hook {
     pub(crate) ptr: *mut t_hook,
     pub(crate) weechat_ptr: *mut t_weechat_plugin,
@@ -35,8 +151,49 @@ pub(crate) struct Hook {
 
 impl Drop for Hook {
     fn drop(&mut self) {
+        if Weechat::is_unloading() {
+            return;
+        }
+
         let weechat = Weechat::from_ptr(self.weechat_ptr);
         let unhook = weechat.get().unhook.unwrap();
         unsafe { unhook(self.ptr) };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    struct DropFlag(Rc<Cell<bool>>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[test]
+    fn hook_data_is_freed_right_away_outside_of_callbacks() {
+        let dropped = Rc::new(Cell::new(false));
+
+        drop(HookData::new(Box::new(DropFlag(dropped.clone()))));
+
+        assert!(dropped.get());
+    }
+
+    #[test]
+    fn hook_data_is_freed_after_the_outermost_callback() {
+        let dropped = Rc::new(Cell::new(false));
+        let data = HookData::new(Box::new(DropFlag(dropped.clone())));
+
+        run_callback(|| {
+            run_callback(|| drop(data));
+            assert!(!dropped.get());
+        });
+
+        assert!(dropped.get());
+        assert!(!in_callback());
+    }
+}
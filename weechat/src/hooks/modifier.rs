@@ -1,16 +1,18 @@
 use libc::c_char;
-use std::{borrow::Cow, ffi::CStr, os::raw::c_void, ptr};
+use std::{borrow::Cow, cell::RefCell, ffi::CStr, os::raw::c_void, ptr, rc::Rc};
 
 use weechat_sys::{t_gui_buffer, t_weechat_plugin};
 
-use super::Hook;
+use super::{run_callback, Hook, HookData};
 use crate::{buffer::Buffer, LossyCString, Weechat};
 
 /// Hook for a modifier, the hook is removed when the object is dropped.
+///
+/// The hook may be dropped inside of its own callback.
 #[cfg_attr(feature = "docs", doc(cfg(unsound)))]
 pub struct ModifierHook {
     _hook: Hook,
-    _hook_data: Box<ModifierHookData>,
+    _hook_data: HookData<ModifierHookData>,
 }
 
 struct ModifierHookData {
@@ -184,7 +186,8 @@ impl ModifierHook {
 
             let data = ModifierData::from_name(&weechat, modifier_name, modifier_data);
 
-            let modified_string = cb.callback(&weechat, modifier_name, data, string);
+            let modified_string =
+                run_callback(|| cb.callback(&weechat, modifier_name, data, string));
 
             if let Some(modified_string) = modified_string {
                 let string_length = modified_string.len();
@@ -220,7 +223,7 @@ impl ModifierHook {
             )
         };
 
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
         let hook = Hook {
             ptr: hook_ptr,
             weechat_ptr: weechat.ptr,
@@ -236,3 +239,102 @@ impl ModifierHook {
         }
     }
 }
+
+/// Trait for the nick color callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait NickColorCallback {
+    /// Callback that will be called when Weechat computes the color of a nick.
+    ///
+    /// Should return the name of the color that should be used for the nick,
+    /// or `None` if the color that Weechat computed should be used.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `nick` - The nick for which the color is being computed.
+    fn callback(&mut self, weechat: &Weechat, nick: &str) -> Option<String>;
+}
+
+impl<T: FnMut(&Weechat, &str) -> Option<String> + 'static> NickColorCallback for T {
+    fn callback(&mut self, weechat: &Weechat, nick: &str) -> Option<String> {
+        self(weechat, nick)
+    }
+}
+
+#[derive(Clone)]
+struct NickColorModifierData {
+    callback: Rc<RefCell<Box<dyn NickColorCallback>>>,
+}
+
+impl ModifierCallback for NickColorModifierData {
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        _modifier_name: &str,
+        _data: Option<ModifierData>,
+        string: Cow<str>,
+    ) -> Option<String> {
+        self.callback.borrow_mut().callback(weechat, &string)
+    }
+}
+
+/// Hook for the nick color modifiers, the hooks are removed when the object is
+/// dropped.
+///
+/// This hooks the `nick_color_name` and the `weechat_nick_color_name`
+/// modifiers, the string that is passed to those modifiers is the nick whose
+/// color is being computed. This allows plugins to override the color Weechat
+/// chooses for a nick.
+#[cfg_attr(feature = "docs", doc(cfg(unsound)))]
+pub struct NickColorModifier {
+    _nick_color_name: ModifierHook,
+    _weechat_nick_color_name: ModifierHook,
+}
+
+impl NickColorModifier {
+    /// Hook the nick color modifiers.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function or a struct that implements
+    /// NickColorCallback, the callback method of the trait will be called when
+    /// the color of a nick is computed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::hooks::NickColorModifier;
+    /// let modifier = NickColorModifier::new(|_: &Weechat, nick: &str| {
+    ///     if nick == "poljar" {
+    ///         Some("lightmagenta".to_owned())
+    ///     } else {
+    ///         None
+    ///     }
+    /// })
+    /// .expect("Can't hook the nick color modifiers");
+    /// ```
+    #[cfg_attr(feature = "docs", doc(cfg(unsound)))]
+    pub fn new(callback: impl NickColorCallback + 'static) -> Result<Self, ()> {
+        let callback: Box<dyn NickColorCallback> = Box::new(callback);
+
+        let data = NickColorModifierData {
+            callback: Rc::new(RefCell::new(callback)),
+        };
+
+        let nick_color_name = ModifierHook::new("nick_color_name", data.clone())?;
+        let weechat_nick_color_name = ModifierHook::new("weechat_nick_color_name", data)?;
+
+        Ok(Self {
+            _nick_color_name: nick_color_name,
+            _weechat_nick_color_name: weechat_nick_color_name,
+        })
+    }
+}
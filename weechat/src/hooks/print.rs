@@ -0,0 +1,218 @@
+use libc::{c_char, c_int, time_t};
+use std::{borrow::Cow, ffi::CStr, os::raw::c_void, ptr, slice, time::SystemTime};
+
+use weechat_sys::{t_gui_buffer, t_weechat_plugin, WEECHAT_RC_OK};
+
+use super::{run_callback, Hook, HookData};
+use crate::{buffer::Buffer, time::FromWeechatTime, LossyCString, Weechat};
+
+/// Hook for printed lines, the hook is removed when the object is dropped.
+///
+/// The hook may be dropped inside of its own callback.
+pub struct PrintHook {
+    _hook: Hook,
+    _hook_data: HookData<PrintHookData>,
+}
+
+struct PrintHookData {
+    callback: Box<dyn PrintCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+/// A line that was printed to a buffer.
+#[derive(Debug, Clone)]
+pub struct PrintedLine<'a> {
+    /// The date of the line.
+    pub date: SystemTime,
+    /// The tags of the line.
+    pub tags: Vec<Cow<'a, str>>,
+    /// Is the line displayed, false if the line is filtered.
+    pub displayed: bool,
+    /// Is the line a highlight.
+    pub highlight: bool,
+    /// The prefix of the line.
+    pub prefix: Cow<'a, str>,
+    /// The message of the line.
+    pub message: Cow<'a, str>,
+}
+
+/// Trait for the print callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait PrintCallback {
+    /// Callback that will be called after a line was printed.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `buffer` - The buffer the line was printed to.
+    ///
+    /// * `line` - The line that was printed.
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, line: PrintedLine);
+}
+
+impl<T: FnMut(&Weechat, &Buffer, PrintedLine) + 'static> PrintCallback for T {
+    fn callback(&mut self, weechat: &Weechat, buffer: &Buffer, line: PrintedLine) {
+        self(weechat, buffer, line)
+    }
+}
+
+impl PrintHook {
+    /// Hook printed lines.
+    ///
+    /// The callback is called after the line was added to the buffer, Weechat
+    /// ignores the return value of print callbacks so a print hook can't
+    /// suppress a line. Use a modifier or a line hook to change or hide
+    /// lines before they are displayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer whose lines should be hooked, `None` to hook
+    ///     the lines of all buffers.
+    ///
+    /// * `tags` - Only lines with one of these tags are hooked, tags can be
+    ///     combined with a `+` to require all of them, e.g.
+    ///     `irc_privmsg+notify_message`. An empty list hooks all lines.
+    ///
+    /// * `message` - Only lines whose message contains this string are
+    ///     hooked, the match is case insensitive.
+    ///
+    /// * `strip_colors` - Should colors be removed from the prefix and the
+    ///     message before the callback is called.
+    ///
+    /// * `callback` - A function or a struct that implements PrintCallback,
+    ///     the callback method of the trait will be called when a matching
+    ///     line is printed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::Buffer;
+    /// # use weechat::hooks::{PrintHook, PrintedLine};
+    /// let hook = PrintHook::new(
+    ///     None,
+    ///     &["notify_private", "notify_highlight"],
+    ///     None,
+    ///     true,
+    ///     |_: &Weechat, buffer: &Buffer, line: PrintedLine| {
+    ///         Weechat::print(&format!(
+    ///             "New message in {}: {}",
+    ///             buffer.short_name_or_name(),
+    ///             line.message
+    ///         ));
+    ///     },
+    /// )
+    /// .expect("Can't hook printed lines");
+    /// ```
+    pub fn new(
+        buffer: Option<&Buffer>,
+        tags: &[&str],
+        message: Option<&str>,
+        strip_colors: bool,
+        callback: impl PrintCallback + 'static,
+    ) -> Result<Self, ()> {
+        #[allow(clippy::too_many_arguments)]
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            buffer: *mut t_gui_buffer,
+            date: time_t,
+            tags_count: c_int,
+            tags: *mut *const c_char,
+            displayed: c_int,
+            highlight: c_int,
+            prefix: *const c_char,
+            message: *const c_char,
+        ) -> c_int {
+            let hook_data: &mut PrintHookData = { &mut *(pointer as *mut PrintHookData) };
+            let cb = &mut hook_data.callback;
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+            let buffer = weechat.buffer_from_ptr(buffer);
+
+            let to_string = |string: *const c_char| {
+                if string.is_null() {
+                    Cow::from("")
+                } else {
+                    CStr::from_ptr(string).to_string_lossy()
+                }
+            };
+
+            let tags = if tags.is_null() || tags_count <= 0 {
+                Vec::new()
+            } else {
+                slice::from_raw_parts(tags, tags_count as usize)
+                    .iter()
+                    .map(|&tag| to_string(tag))
+                    .collect()
+            };
+
+            let line = PrintedLine {
+                date: SystemTime::from_weechat_time(date),
+                tags,
+                displayed: displayed != 0,
+                highlight: highlight != 0,
+                prefix: to_string(prefix),
+                message: to_string(message),
+            };
+
+            run_callback(|| cb.callback(&weechat, &buffer, line));
+
+            WEECHAT_RC_OK
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = Box::new(PrintHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_print = weechat.get().hook_print.unwrap();
+
+        let buffer = buffer.map(|b| b.ptr()).unwrap_or(ptr::null_mut());
+        let tags = if tags.is_empty() {
+            None
+        } else {
+            Some(LossyCString::new(tags.join(",")))
+        };
+        let message = message.map(LossyCString::new);
+
+        let hook_ptr = unsafe {
+            hook_print(
+                weechat.ptr,
+                buffer,
+                tags.as_ref().map(|t| t.as_ptr()).unwrap_or(ptr::null()),
+                message.as_ref().map(|m| m.as_ptr()).unwrap_or(ptr::null()),
+                strip_colors as c_int,
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+        let hook = Hook {
+            ptr: hook_ptr,
+            weechat_ptr: weechat.ptr,
+        };
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(PrintHook {
+                _hook: hook,
+                _hook_data: hook_data,
+            })
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use libc::{c_char, c_int};
+use std::{borrow::Cow, convert::TryFrom, ffi::CStr, os::raw::c_void, ptr, time::Duration};
+
+use weechat_sys::{t_weechat_plugin, WEECHAT_RC_OK};
+
+use super::{run_callback, Hook, HookData};
+use crate::{LossyCString, Weechat};
+
+/// Hook for a child process, the process is killed if the hook is dropped
+/// before the process finished.
+///
+/// The hook may be dropped inside of its own callback.
+pub struct ProcessHook {
+    hook: Hook,
+    hook_data: HookData<ProcessHookData>,
+}
+
+/// The status of a hooked process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// The process is still running, more output will follow.
+    Running,
+    /// The process finished with the given return code.
+    Finished(i32),
+    /// The process couldn't be started or was killed because it reached the
+    /// timeout.
+    Error,
+}
+
+impl From<i32> for ProcessStatus {
+    fn from(return_code: i32) -> Self {
+        match return_code {
+            // WEECHAT_HOOK_PROCESS_RUNNING
+            -1 => ProcessStatus::Running,
+            code if code >= 0 => ProcessStatus::Finished(code),
+            // WEECHAT_HOOK_PROCESS_ERROR
+            _ => ProcessStatus::Error,
+        }
+    }
+}
+
+/// Trait for the process callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait ProcessCallback {
+    /// Callback that will be called when the process produced output or
+    /// finished.
+    ///
+    /// Weechat buffers the output of the process, the callback is called
+    /// every time the buffer is full and a last time when the process
+    /// finished. The output of a single call is only a part of the whole
+    /// output, it needs to be accumulated if the complete output is needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `command` - The command that was run.
+    ///
+    /// * `status` - The status of the process.
+    ///
+    /// * `stdout` - The standard output of the process since the last call.
+    ///
+    /// * `stderr` - The error output of the process since the last call.
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        command: Cow<str>,
+        status: ProcessStatus,
+        stdout: Cow<str>,
+        stderr: Cow<str>,
+    );
+}
+
+impl<T: FnMut(&Weechat, Cow<str>, ProcessStatus, Cow<str>, Cow<str>) + 'static> ProcessCallback
+    for T
+{
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        command: Cow<str>,
+        status: ProcessStatus,
+        stdout: Cow<str>,
+        stderr: Cow<str>,
+    ) {
+        self(weechat, command, status, stdout, stderr)
+    }
+}
+
+struct ProcessHookData {
+    callback: Box<dyn ProcessCallback>,
+    weechat_ptr: *mut t_weechat_plugin,
+    finished: bool,
+}
+
+impl Drop for ProcessHook {
+    fn drop(&mut self) {
+        // Weechat removes the hook by itself once the process finished, make
+        // sure it's not unhooked a second time.
+        if self.hook_data.finished {
+            self.hook.ptr = ptr::null_mut();
+        }
+    }
+}
+
+impl ProcessHook {
+    /// Run a command in a child process.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command that should be run. Besides shell commands
+    ///     Weechat supports a couple of special forms, e.g. `url:https://...`
+    ///     downloads the URL and the content is returned as the output of
+    ///     the process, see the Weechat [documentation] for all of them.
+    ///
+    /// * `timeout` - The maximal time the process may run, the process is
+    ///     killed after the timeout. A timeout of zero means no timeout.
+    ///     Weechat takes the timeout in milliseconds as a 32 bit integer, an
+    ///     error is returned if the timeout is longer than `i32::MAX`
+    ///     milliseconds, roughly 24 days.
+    ///
+    /// * `callback` - A function or a struct that implements ProcessCallback,
+    ///     the callback method of the trait will be called when the process
+    ///     produced output or finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::borrow::Cow;
+    /// # use std::time::Duration;
+    /// # use weechat::Weechat;
+    /// # use weechat::hooks::{ProcessHook, ProcessStatus};
+    /// let mut output = String::new();
+    ///
+    /// let hook = ProcessHook::new(
+    ///     "echo hello",
+    ///     Duration::from_secs(10),
+    ///     move |_: &Weechat,
+    ///           _: Cow<str>,
+    ///           status: ProcessStatus,
+    ///           stdout: Cow<str>,
+    ///           _: Cow<str>| {
+    ///         output.push_str(&stdout);
+    ///
+    ///         if status == ProcessStatus::Finished(0) {
+    ///             assert_eq!(output, "hello\n");
+    ///         }
+    ///     },
+    /// )
+    /// .expect("Can't run the process");
+    /// ```
+    ///
+    /// [documentation]: https://weechat.org/files/doc/stable/weechat_plugin_api.en.html#_hook_process
+    pub fn new(
+        command: &str,
+        timeout: Duration,
+        callback: impl ProcessCallback + 'static,
+    ) -> Result<ProcessHook, ()> {
+        unsafe extern "C" fn c_hook_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            command: *const c_char,
+            return_code: c_int,
+            out: *const c_char,
+            err: *const c_char,
+        ) -> c_int {
+            let hook_data: &mut ProcessHookData = { &mut *(pointer as *mut ProcessHookData) };
+            let cb = &mut hook_data.callback;
+
+            let status = ProcessStatus::from(return_code);
+
+            if status != ProcessStatus::Running {
+                hook_data.finished = true;
+            }
+
+            let to_string = |string: *const c_char| {
+                if string.is_null() {
+                    Cow::from("")
+                } else {
+                    CStr::from_ptr(string).to_string_lossy()
+                }
+            };
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+
+            run_callback(|| {
+                cb.callback(
+                    &weechat,
+                    to_string(command),
+                    status,
+                    to_string(out),
+                    to_string(err),
+                )
+            });
+
+            WEECHAT_RC_OK
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let timeout = i32::try_from(timeout.as_millis()).map_err(|_| ())?;
+
+        let data = Box::new(ProcessHookData {
+            callback: Box::new(callback),
+            weechat_ptr: weechat.ptr,
+            finished: false,
+        });
+
+        let data_ref = Box::leak(data);
+        let hook_process = weechat.get().hook_process.unwrap();
+
+        let command = LossyCString::new(command);
+
+        let hook_ptr = unsafe {
+            hook_process(
+                weechat.ptr,
+                command.as_ptr(),
+                timeout,
+                Some(c_hook_cb),
+                data_ref as *const _ as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
+
+        if hook_ptr.is_null() {
+            Err(())
+        } else {
+            Ok(ProcessHook {
+                hook: Hook {
+                    ptr: hook_ptr,
+                    weechat_ptr: weechat.ptr,
+                },
+                hook_data,
+            })
+        }
+    }
+}
@@ -1,18 +1,38 @@
 use libc::{c_char, c_int};
-use std::{borrow::Cow, cell::Cell, ffi::CStr, os::raw::c_void, ptr, rc::Rc};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::CStr,
+    os::raw::c_void,
+    ptr,
+    rc::Rc,
+};
+
+#[cfg(feature = "async")]
+use futures::{
+    channel::mpsc::{channel, Receiver},
+    task::{Context, Poll},
+    Stream,
+};
+#[cfg(feature = "async")]
+use std::pin::Pin;
 
-use weechat_sys::{t_gui_buffer, t_weechat_plugin};
+use weechat_sys::{t_gui_buffer, t_weechat_plugin, WEECHAT_RC_OK};
 
-use super::Hook;
+use super::{run_callback, Hook, HookData};
 use crate::{
     buffer::{Buffer, InnerBuffer, InnerBuffers},
     LossyCString, ReturnCode, Weechat,
 };
 
 /// Hook for a signal, the hook is removed when the object is dropped.
+///
+/// The hook may be dropped inside of its own callback, e.g. to only react to
+/// the first time a signal is sent.
 pub struct SignalHook {
     _hook: Hook,
-    _hook_data: Box<SignalHookData>,
+    _hook_data: HookData<SignalHookData>,
 }
 
 struct SignalHookData {
@@ -29,6 +49,10 @@ pub enum SignalData<'a> {
     Integer(i32),
     /// Buffer that was sent with the signal.
     Buffer(Buffer<'a>),
+    /// A pointer that was sent with the signal and that isn't known to point
+    /// to a buffer, e.g. a window or an infolist. The pointer is only valid
+    /// during the signal callback.
+    Pointer(*mut c_void),
 }
 
 impl<'a> Into<SignalData<'a>> for &'a str {
@@ -145,7 +169,7 @@ impl<'a> SignalData<'a> {
                         weechat.buffer_from_ptr(data as *mut t_gui_buffer),
                     ))
                 } else {
-                    None
+                    Some(SignalData::Pointer(data))
                 }
             }
             _ => None,
@@ -222,7 +246,29 @@ impl SignalHook {
     ///         ReturnCode::Ok
     ///     },
     /// );
+    /// ```
+    ///
+    /// Signals that are sent using `Weechat::hook_signal_send()` run the
+    /// callback before the send call returns:
+    ///
+    /// ```no_run
+    /// # use std::{cell::Cell, rc::Rc};
+    /// # use weechat::{Weechat, ReturnCode};
+    /// # use weechat::hooks::{SignalData, SignalHook};
+    /// let called = Rc::new(Cell::new(false));
+    /// let called_clone = called.clone();
+    ///
+    /// let signal_hook = SignalHook::new(
+    ///     "input_text_changed",
+    ///     move |_: &Weechat, _: &str, _: Option<SignalData>| {
+    ///         called_clone.set(true);
+    ///         ReturnCode::Ok
+    ///     },
+    /// )
+    /// .unwrap();
     ///
+    /// Weechat::hook_signal_send("input_text_changed", "");
+    /// assert!(called.get());
     /// ```
     pub fn new(signal_name: &str, callback: impl SignalCallback + 'static) -> Result<Self, ()> {
         unsafe extern "C" fn c_hook_cb(
@@ -242,7 +288,7 @@ impl SignalHook {
             let data =
                 SignalData::from_type_and_name(&weechat, signal_name, data_type, signal_data);
 
-            cb.callback(&weechat, signal_name, data) as i32
+            run_callback(|| cb.callback(&weechat, signal_name, data)) as i32
         }
 
         Weechat::check_thread();
@@ -268,7 +314,7 @@ impl SignalHook {
             )
         };
 
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
         let hook = Hook {
             ptr: hook_ptr,
             weechat_ptr: weechat.ptr,
@@ -341,16 +387,616 @@ impl Weechat {
                     buffer.ptr() as *mut _,
                     weechat_sys::WEECHAT_HOOK_SIGNAL_POINTER as *const u8,
                 ),
+                SignalData::Pointer(pointer) => (
+                    pointer,
+                    weechat_sys::WEECHAT_HOOK_SIGNAL_POINTER as *const u8,
+                ),
                 SignalData::String(_) => unreachable!(),
             };
             unsafe { signal_send(signal_name.as_ptr(), data_type as *const i8, ptr) }
         };
 
-        match ret {
-            weechat_sys::WEECHAT_RC_OK => ReturnCode::Ok,
-            weechat_sys::WEECHAT_RC_OK_EAT => ReturnCode::OkEat,
-            weechat_sys::WEECHAT_RC_ERROR => ReturnCode::Error,
-            _ => ReturnCode::Error,
+        ReturnCode::from_raw(ret)
+    }
+
+    /// Send a signal on the next iteration of the Weechat main loop.
+    ///
+    /// `hook_signal_send()` runs the callbacks that listen to the signal
+    /// before it returns, if it's called from a callback that holds a borrow
+    /// of some shared state, e.g. a `RefCell`, and one of the signal
+    /// callbacks borrows the same state the plugin will panic. This method
+    /// queues the signal instead, it is sent once the current callback
+    /// returned. Prefer this method when sending signals from callbacks that
+    /// mutate plugin state.
+    ///
+    /// If a buffer is passed as the signal data and the buffer gets closed
+    /// before the signal is sent, the signal is dropped.
+    ///
+    /// Returns an error if the data is a `SignalData::Pointer`, the object
+    /// behind the pointer might be freed before the signal is sent, or if the
+    /// signal couldn't be queued.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_name` - The name of the signal that should be sent out.
+    ///
+    /// * `data` - Data that should be provided to the signal callback. This can
+    ///     be a string, an i32 number, or a buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// # use weechat::Weechat;
+    /// # let state = Rc::new(RefCell::new(0));
+    /// let mut selected = state.borrow_mut();
+    /// *selected += 1;
+    ///
+    /// // The input bar item callback can borrow the state once the signal
+    /// // gets sent, the mutable borrow will be gone by then.
+    /// Weechat::send_signal_deferred("input_text_changed", "")
+    ///     .expect("Can't queue the signal");
+    /// ```
+    pub fn send_signal_deferred<'a, D: Into<SignalData<'a>>>(
+        signal_name: &str,
+        data: D,
+    ) -> Result<(), ()> {
+        unsafe extern "C" fn c_timer_cb(
+            pointer: *const c_void,
+            _data: *mut c_void,
+            _remaining: i32,
+        ) -> c_int {
+            // The timer only fires once, Weechat removes the hook after this
+            // call so the signal is taken out of the queue and freed here.
+            let signal = match take_deferred_signal(pointer as usize) {
+                Some(signal) => signal,
+                None => return WEECHAT_RC_OK,
+            };
+            let weechat = Weechat::from_ptr(signal.weechat_ptr);
+
+            match signal.data {
+                OwnedSignalData::String(string) => {
+                    Weechat::hook_signal_send(&signal.name, string);
+                }
+                OwnedSignalData::Integer(number) => {
+                    Weechat::hook_signal_send(&signal.name, number);
+                }
+                OwnedSignalData::Buffer(buffer_name) => {
+                    if let Some(buffer) = weechat.buffer_search("==", &buffer_name) {
+                        Weechat::hook_signal_send(&signal.name, buffer);
+                    }
+                }
+                // Pointers are refused when the signal gets queued.
+                OwnedSignalData::Pointer(_) => unreachable!(),
+            }
+
+            WEECHAT_RC_OK
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let data = data.into();
+
+        if let SignalData::Pointer(_) = data {
+            return Err(());
+        }
+
+        let signal_id = queue_deferred_signal(DeferredSignal {
+            name: signal_name.to_owned(),
+            data: OwnedSignalData::from(data),
+            weechat_ptr: weechat.ptr,
+        });
+
+        let hook_timer = weechat.get().hook_timer.unwrap();
+
+        let hook_ptr = unsafe {
+            hook_timer(
+                weechat.ptr,
+                1,
+                0,
+                1,
+                Some(c_timer_cb),
+                signal_id as *const c_void,
+                ptr::null_mut(),
+            )
+        };
+
+        if hook_ptr.is_null() {
+            take_deferred_signal(signal_id);
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct DeferredSignal {
+    name: String,
+    data: OwnedSignalData,
+    weechat_ptr: *mut t_weechat_plugin,
+}
+
+thread_local! {
+    // Signals that were queued using `send_signal_deferred()` but not sent
+    // yet, they are owned here so they can be freed if the plugin is
+    // unloaded before their timer fires. The timer gets the ID of the signal
+    // as its pointer.
+    static DEFERRED_SIGNALS: RefCell<HashMap<usize, DeferredSignal>> =
+        RefCell::new(HashMap::new());
+    static NEXT_DEFERRED_SIGNAL_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Queue a deferred signal, returns the ID of the signal.
+fn queue_deferred_signal(signal: DeferredSignal) -> usize {
+    let id = NEXT_DEFERRED_SIGNAL_ID.with(|id| {
+        let current = id.get();
+        id.set(current.wrapping_add(1));
+        current
+    });
+
+    DEFERRED_SIGNALS.with(|signals| signals.borrow_mut().insert(id, signal));
+
+    id
+}
+
+/// Remove a queued deferred signal.
+fn take_deferred_signal(id: usize) -> Option<DeferredSignal> {
+    DEFERRED_SIGNALS.with(|signals| signals.borrow_mut().remove(&id))
+}
+
+/// Free the deferred signals that weren't sent yet.
+///
+/// This is called when the plugin is unloaded, Weechat removes the timers of
+/// the signals itself.
+pub(crate) fn free_deferred_signals() {
+    let signals = DEFERRED_SIGNALS.with(|signals| signals.replace(HashMap::new()));
+    drop(signals);
+}
+
+/// An owned version of `SignalData`.
+///
+/// This is used where the data of a signal needs to outlive the signal
+/// callback, buffers are referenced by their full name since the buffer might
+/// be closed in the meantime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OwnedSignalData {
+    /// String data
+    String(String),
+    /// Integer data
+    Integer(i32),
+    /// The full name of the buffer that was sent with the signal.
+    Buffer(String),
+    /// The address of a pointer that was sent with the signal, the pointer
+    /// might be dangling once the signal callback returned.
+    Pointer(usize),
+}
+
+impl From<SignalData<'_>> for OwnedSignalData {
+    fn from(data: SignalData<'_>) -> Self {
+        match data {
+            SignalData::String(string) => OwnedSignalData::String(string.into_owned()),
+            SignalData::Integer(number) => OwnedSignalData::Integer(number),
+            SignalData::Buffer(buffer) => OwnedSignalData::Buffer(buffer.full_name().into_owned()),
+            SignalData::Pointer(pointer) => OwnedSignalData::Pointer(pointer as usize),
+        }
+    }
+}
+
+/// A stream of signals, the signal is unhooked when the stream is dropped.
+///
+/// The stream is created by `Weechat::signal_stream()`.
+#[cfg(feature = "async")]
+#[cfg_attr(feature = "docs", doc(cfg(r#async)))]
+pub struct SignalStream {
+    _hook: SignalHook,
+    receiver: Receiver<(String, Option<OwnedSignalData>)>,
+}
+
+#[cfg(feature = "async")]
+impl Stream for SignalStream {
+    type Item = (String, Option<OwnedSignalData>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Weechat {
+    /// Hook a signal and receive the signals as a stream.
+    ///
+    /// The stream yields the name of the signal that was sent and its data.
+    /// Signals are buffered until they are taken out of the stream, if the
+    /// buffer is full new signals are dropped until there is room again.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_name` - The signal to hook (wildcard `*` is allowed).
+    ///
+    /// * `capacity` - The number of signals that are buffered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use weechat::Weechat;
+    /// # use weechat::hooks::OwnedSignalData;
+    /// Weechat::spawn(async {
+    ///     let mut connected = Weechat::signal_stream("irc_server_connected", 10)
+    ///         .expect("Can't hook the signal");
+    ///
+    ///     while let Some((_, data)) = connected.next().await {
+    ///         if let Some(OwnedSignalData::String(server)) = data {
+    ///             Weechat::print(&format!("Connected to {}", server));
+    ///         }
+    ///     }
+    /// })
+    /// .detach();
+    /// ```
+    pub fn signal_stream(signal_name: &str, capacity: usize) -> Result<SignalStream, ()> {
+        let (mut sender, receiver) = channel(capacity);
+
+        let hook = SignalHook::new(
+            signal_name,
+            move |_: &Weechat, signal_name: &str, data: Option<SignalData>| {
+                // The buffer is full or the stream was dropped, drop the signal.
+                let _ = sender.try_send((signal_name.to_owned(), data.map(OwnedSignalData::from)));
+                ReturnCode::Ok
+            },
+        )?;
+
+        Ok(SignalStream {
+            _hook: hook,
+            receiver,
+        })
+    }
+}
+
+/// Trait for the away status callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait AwayStatusCallback {
+    /// Callback that will be called when the away status on an IRC server
+    /// changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `server` - The name of the IRC server on which the status changed.
+    ///
+    /// * `away` - True if the user is now marked as away, false otherwise.
+    fn callback(&mut self, weechat: &Weechat, server: &str, away: bool);
+}
+
+impl<T: FnMut(&Weechat, &str, bool) + 'static> AwayStatusCallback for T {
+    fn callback(&mut self, weechat: &Weechat, server: &str, away: bool) {
+        self(weechat, server, away)
+    }
+}
+
+/// Hook for away status changes on IRC servers, the hooks are removed when the
+/// object is dropped.
+///
+/// Weechat doesn't send out a dedicated signal when the away status changes,
+/// instead the IRC plugin sends out a signal for every message it receives.
+/// The server confirms an away status change with a `305` (`RPL_UNAWAY`) or a
+/// `306` (`RPL_NOWAWAY`) numeric reply, this hooks the
+/// `*,irc_in2_305` and `*,irc_in2_306` signals. The signal names have the
+/// form `server,irc_in2_xxx`, the server name is taken from them.
+pub struct AwayStatusHook {
+    _unaway: SignalHook,
+    _now_away: SignalHook,
+}
+
+impl AwayStatusHook {
+    /// Hook the away status change of IRC servers.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function or a struct that implements
+    /// AwayStatusCallback, the callback method of the trait will be called
+    /// when the away status on a server changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::hooks::AwayStatusHook;
+    /// let hook = AwayStatusHook::new(|_: &Weechat, server: &str, away: bool| {
+    ///     let status = if away { "away" } else { "back" };
+    ///     Weechat::print(&format!("You are {} on {}", status, server));
+    /// })
+    /// .expect("Can't hook the away status signals");
+    /// ```
+    pub fn new(callback: impl AwayStatusCallback + 'static) -> Result<Self, ()> {
+        let callback: Rc<RefCell<dyn AwayStatusCallback>> = Rc::new(RefCell::new(callback));
+
+        let hook = |away: bool, callback: Rc<RefCell<dyn AwayStatusCallback>>| {
+            let signal_name = if away {
+                "*,irc_in2_306"
+            } else {
+                "*,irc_in2_305"
+            };
+
+            SignalHook::new(
+                signal_name,
+                move |weechat: &Weechat, signal_name: &str, _: Option<SignalData>| {
+                    let server = signal_name.split(',').next().unwrap_or_default();
+                    callback.borrow_mut().callback(weechat, server, away);
+
+                    ReturnCode::Ok
+                },
+            )
+        };
+
+        Ok(AwayStatusHook {
+            _unaway: hook(false, callback.clone())?,
+            _now_away: hook(true, callback)?,
+        })
+    }
+}
+
+/// A buffer property that can be watched with
+/// `Weechat::watch_buffer_property()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BufferProperty {
+    /// The title of the buffer, watched using the `buffer_title_changed`
+    /// signal.
+    Title,
+    /// The name of the buffer, watched using the `buffer_renamed` signal.
+    Name,
+    /// The short name of the buffer, watched using the `buffer_renamed`
+    /// signal.
+    ShortName,
+    /// The local variable with the given name, watched using the
+    /// `buffer_localvar_added`, `buffer_localvar_changed` and
+    /// `buffer_localvar_removed` signals.
+    LocalVariable(String),
+}
+
+impl BufferProperty {
+    fn signals(&self) -> &'static [&'static str] {
+        match self {
+            BufferProperty::Title => &["buffer_title_changed"],
+            BufferProperty::Name | BufferProperty::ShortName => &["buffer_renamed"],
+            BufferProperty::LocalVariable(_) => &[
+                "buffer_localvar_added",
+                "buffer_localvar_changed",
+                "buffer_localvar_removed",
+            ],
         }
     }
+
+    fn value(&self, buffer: &Buffer) -> Option<String> {
+        match self {
+            BufferProperty::Title => Some(buffer.title().into_owned()),
+            BufferProperty::Name => Some(buffer.name().into_owned()),
+            BufferProperty::ShortName => Some(buffer.short_name().into_owned()),
+            BufferProperty::LocalVariable(name) => {
+                buffer.get_localvar(name).map(|value| value.into_owned())
+            }
+        }
+    }
+}
+
+/// Trait for the buffer property callback.
+///
+/// A blanket implementation for pure `FnMut` functions exists, if data needs to
+/// be passed to the callback implement this over your struct.
+pub trait BufferPropertyCallback {
+    /// Callback that will be called when the watched property of a buffer
+    /// changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `weechat` - A Weechat context.
+    ///
+    /// * `buffer` - The buffer whose property changed.
+    ///
+    /// * `old_value` - The previous value of the property, `None` if a local
+    ///     variable wasn't set.
+    ///
+    /// * `new_value` - The new value of the property, `None` if a local
+    ///     variable was removed.
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        buffer: &Buffer,
+        old_value: Option<&str>,
+        new_value: Option<&str>,
+    );
+}
+
+impl<T: FnMut(&Weechat, &Buffer, Option<&str>, Option<&str>) + 'static> BufferPropertyCallback
+    for T
+{
+    fn callback(
+        &mut self,
+        weechat: &Weechat,
+        buffer: &Buffer,
+        old_value: Option<&str>,
+        new_value: Option<&str>,
+    ) {
+        self(weechat, buffer, old_value, new_value)
+    }
+}
+
+struct BufferPropertyWatch {
+    property: BufferProperty,
+    values: RefCell<HashMap<*mut t_gui_buffer, Option<String>>>,
+    callback: RefCell<Box<dyn BufferPropertyCallback>>,
+}
+
+impl BufferPropertyWatch {
+    fn update(&self, weechat: &Weechat, buffer: &Buffer) {
+        let new_value = self.property.value(buffer);
+        let old_value = self
+            .values
+            .borrow_mut()
+            .insert(buffer.ptr(), new_value.clone());
+
+        // Changes the callback itself makes to the property are only
+        // recorded, the callback isn't called recursively.
+        if let (Some(old_value), Ok(mut callback)) = (old_value, self.callback.try_borrow_mut()) {
+            if old_value != new_value {
+                callback.callback(weechat, buffer, old_value.as_deref(), new_value.as_deref());
+            }
+        }
+    }
+}
+
+/// Hook for changes of a property of all buffers, the hooks are removed when
+/// the object is dropped.
+///
+/// The hook is created by `Weechat::watch_buffer_property()`.
+pub struct BufferPropertyHook {
+    _hooks: Vec<SignalHook>,
+}
+
+impl Weechat {
+    /// Watch a property of all buffers for changes.
+    ///
+    /// The watcher keeps the current value of the property for every buffer,
+    /// so the callback receives the old and the new value. The signals
+    /// Weechat sends out don't always mean that the watched property changed,
+    /// e.g. `buffer_renamed` is sent for changes of the name and the short
+    /// name, the callback is only called if the value actually changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `property` - The property that should be watched.
+    ///
+    /// * `callback` - A function or a struct that implements
+    /// BufferPropertyCallback, the callback method of the trait will be
+    /// called when the property of a buffer changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::Buffer;
+    /// # use weechat::hooks::BufferProperty;
+    /// let hook = Weechat::watch_buffer_property(
+    ///     BufferProperty::Title,
+    ///     |_: &Weechat, buffer: &Buffer, old: Option<&str>, new: Option<&str>| {
+    ///         Weechat::print(&format!(
+    ///             "The title of {} changed from {:?} to {:?}",
+    ///             buffer.full_name(),
+    ///             old,
+    ///             new
+    ///         ));
+    ///     },
+    /// )
+    /// .expect("Can't watch the buffer titles");
+    /// ```
+    pub fn watch_buffer_property(
+        property: BufferProperty,
+        callback: impl BufferPropertyCallback + 'static,
+    ) -> Result<BufferPropertyHook, ()> {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let values = weechat
+            .buffers()
+            .map(|buffer| (buffer.ptr(), property.value(&buffer)))
+            .collect();
+
+        let signals = property.signals();
+
+        let watch = Rc::new(BufferPropertyWatch {
+            property,
+            values: RefCell::new(values),
+            callback: RefCell::new(Box::new(callback)),
+        });
+
+        let mut hooks = Vec::new();
+
+        for signal_name in signals.iter().chain(&["buffer_opened", "buffer_closing"]) {
+            let watch = watch.clone();
+
+            let hook = SignalHook::new(
+                signal_name,
+                move |weechat: &Weechat, signal_name: &str, data: Option<SignalData>| {
+                    if let Some(SignalData::Buffer(buffer)) = data {
+                        match signal_name {
+                            "buffer_opened" => {
+                                let value = watch.property.value(&buffer);
+                                watch.values.borrow_mut().insert(buffer.ptr(), value);
+                            }
+                            "buffer_closing" => {
+                                watch.values.borrow_mut().remove(&buffer.ptr());
+                            }
+                            _ => watch.update(weechat, &buffer),
+                        }
+                    }
+
+                    ReturnCode::Ok
+                },
+            )?;
+
+            hooks.push(hook);
+        }
+
+        Ok(BufferPropertyHook { _hooks: hooks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(name: &str) -> DeferredSignal {
+        DeferredSignal {
+            name: name.to_owned(),
+            data: OwnedSignalData::Integer(1),
+            weechat_ptr: ptr::null_mut(),
+        }
+    }
+
+    fn pending() -> usize {
+        DEFERRED_SIGNALS.with(|signals| signals.borrow().len())
+    }
+
+    #[test]
+    fn deferred_signals_are_taken_once() {
+        let first = queue_deferred_signal(signal("first"));
+        let second = queue_deferred_signal(signal("second"));
+
+        assert_eq!(take_deferred_signal(second).unwrap().name, "second");
+        assert!(take_deferred_signal(second).is_none());
+        assert_eq!(pending(), 1);
+
+        assert_eq!(take_deferred_signal(first).unwrap().name, "first");
+        assert_eq!(pending(), 0);
+    }
+
+    #[test]
+    fn pending_deferred_signals_are_freed() {
+        let id = queue_deferred_signal(signal("pending"));
+
+        free_deferred_signals();
+
+        assert_eq!(pending(), 0);
+        assert!(take_deferred_signal(id).is_none());
+    }
 }
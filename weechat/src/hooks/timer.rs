@@ -3,16 +3,21 @@ use std::{os::raw::c_void, ptr, time::Duration};
 
 use weechat_sys::{t_weechat_plugin, WEECHAT_RC_OK};
 
-use super::Hook;
+use super::{run_callback, Hook, HookData};
 use crate::Weechat;
 
 /// A hook for a timer, the hook will be removed when the object is dropped.
+///
+/// A timer with a limited number of calls is removed by Weechat after the
+/// last call, the object can still be dropped safely afterwards. Dropping the
+/// hook inside of its own callback is safe as well.
 pub struct TimerHook {
-    _hook: Hook,
-    _hook_data: Box<TimerHookData>,
+    hook: Hook,
+    hook_data: HookData<TimerHookData>,
 }
 
 /// Enum representing how many calls a timer still has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RemainingCalls {
     /// Infinitely many remaining calls.
     Infinite,
@@ -53,6 +58,17 @@ impl<T: FnMut(&Weechat, RemainingCalls) + 'static> TimerCallback for T {
 struct TimerHookData {
     callback: Box<dyn TimerCallback>,
     weechat_ptr: *mut t_weechat_plugin,
+    finished: bool,
+}
+
+impl Drop for TimerHook {
+    fn drop(&mut self) {
+        // Weechat already removed the hook after the last call, make sure
+        // it's not unhooked a second time.
+        if self.hook_data.finished {
+            self.hook.ptr = ptr::null_mut();
+        }
+    }
 }
 
 impl TimerHook {
@@ -60,7 +76,8 @@ impl TimerHook {
     ///
     /// # Arguments
     ///
-    /// * `interval` - The delay between calls in milliseconds.
+    /// * `interval` - The delay between calls, the precision of the timer is
+    ///     a millisecond.
     ///
     /// * `align_second` - The alignment on a second. For example, if the
     ///     current time is 09:00, if the interval = 60000 (60 seconds), and
@@ -71,7 +88,8 @@ impl TimerHook {
     ///     means it's called forever.
     ///
     /// * `callback` - A function that will be called when the timer fires, the
-    ///     `remaining` argument will be -1 if the timer has no end.
+    ///     `remaining` argument will be `RemainingCalls::Infinite` if the
+    ///     timer has no end and `RemainingCalls::Finite(0)` on the last call.
     ///
     /// # Panics
     ///
@@ -84,11 +102,24 @@ impl TimerHook {
     /// # use weechat::hooks::{TimerHook, RemainingCalls};
     ///
     /// let timer = TimerHook::new(
-    ///     Duration::from_secs(1), 0, -1,
+    ///     Duration::from_secs(1), 0, 0,
     ///     |_: &Weechat, _: RemainingCalls| {
     ///         Weechat::print("Running timer hook");
     ///     }
     /// ).expect("Can't create timer hook");
+    ///
+    /// // A timer that fires three times, once every 100 milliseconds.
+    /// let mut calls = 0;
+    /// let timer = TimerHook::new(
+    ///     Duration::from_millis(100), 0, 3,
+    ///     move |_: &Weechat, remaining: RemainingCalls| {
+    ///         calls += 1;
+    ///
+    ///         if remaining == RemainingCalls::Finite(0) {
+    ///             Weechat::print(&format!("The timer fired {} times", calls));
+    ///         }
+    ///     }
+    /// ).expect("Can't create timer hook");
     /// ```
     pub fn new(
         interval: Duration,
@@ -104,10 +135,13 @@ impl TimerHook {
             let hook_data: &mut TimerHookData = { &mut *(pointer as *mut TimerHookData) };
             let cb = &mut hook_data.callback;
 
-            cb.callback(
-                &Weechat::from_ptr(hook_data.weechat_ptr),
-                RemainingCalls::from(remaining),
-            );
+            if remaining == 0 {
+                hook_data.finished = true;
+            }
+
+            let weechat = Weechat::from_ptr(hook_data.weechat_ptr);
+
+            run_callback(|| cb.callback(&weechat, RemainingCalls::from(remaining)));
 
             WEECHAT_RC_OK
         }
@@ -118,6 +152,7 @@ impl TimerHook {
         let data = Box::new(TimerHookData {
             callback: Box::new(callback),
             weechat_ptr: weechat.ptr,
+            finished: false,
         });
 
         let data_ref = Box::leak(data);
@@ -134,17 +169,17 @@ impl TimerHook {
                 ptr::null_mut(),
             )
         };
-        let hook_data = unsafe { Box::from_raw(data_ref) };
+        let hook_data = HookData::new(unsafe { Box::from_raw(data_ref) });
 
         if hook_ptr.is_null() {
             Err(())
         } else {
             Ok(TimerHook {
-                _hook: Hook {
+                hook: Hook {
                     ptr: hook_ptr,
                     weechat_ptr: weechat.ptr,
                 },
-                _hook_data: hook_data,
+                hook_data,
             })
         }
     }
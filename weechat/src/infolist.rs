@@ -25,18 +25,19 @@ use std::{
         hash_map::{IntoIter as IterHashmap, Keys},
         HashMap,
     },
-    ffi::CStr,
+    ffi::{c_void, CStr},
     fmt::Debug,
     marker::PhantomData,
     ptr,
     rc::Rc,
-    time::{Duration, SystemTime},
+    time::SystemTime,
 };
 
 use weechat_sys::{t_gui_buffer, t_infolist};
 
 use crate::{
     buffer::{Buffer, InnerBuffer, InnerBuffers},
+    time::FromWeechatTime,
     LossyCString, Weechat,
 };
 
@@ -47,6 +48,10 @@ pub struct Infolist<'a> {
     ptr: *mut t_infolist,
     infolist_name: String,
     weechat: &'a Weechat,
+    /// The number of items the iterator returned since the cursor was last
+    /// reset.
+    position: Cell<usize>,
+    len: Cell<Option<usize>>,
 }
 
 /// The type of an infolist variable.
@@ -139,10 +144,7 @@ impl<'a> InfolistItem<'a> {
 
         let time = unsafe { infolist_time(self.ptr, name.as_ptr()) };
 
-        let unix = SystemTime::UNIX_EPOCH;
-        let duration = Duration::from_secs(time as u64);
-
-        unix.checked_add(duration)
+        Some(SystemTime::from_weechat_time(time))
     }
 
     /// Get a variable from the current infolist item.
@@ -163,11 +165,99 @@ impl<'a> InfolistItem<'a> {
         Some(variable)
     }
 
+    /// Get an integer variable from the current infolist item.
+    ///
+    /// Returns `None` if the item has no variable with the given name or if
+    /// the variable isn't an integer.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the variable that should be fetched.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let infolist = weechat.get_infolist("buffer", None).unwrap();
+    ///
+    /// let hidden_buffers: Vec<i32> = infolist
+    ///     .filter(|item| item.get_integer("hidden") == Some(1))
+    ///     .filter_map(|item| item.get_integer("number"))
+    ///     .collect();
+    /// ```
+    pub fn get_integer(&self, key: &str) -> Option<i32> {
+        match self.fields.get(key)? {
+            InfolistType::Integer => Some(self.integer(key)),
+            _ => None,
+        }
+    }
+
+    /// Get a string variable from the current infolist item.
+    ///
+    /// Returns `None` if the item has no variable with the given name or if
+    /// the variable isn't a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the variable that should be fetched.
+    pub fn get_string(&'a self, key: &str) -> Option<Cow<str>> {
+        match self.fields.get(key)? {
+            InfolistType::String => self.string(key),
+            _ => None,
+        }
+    }
+
+    /// Get a time variable from the current infolist item.
+    ///
+    /// Returns `None` if the item has no variable with the given name or if
+    /// the variable isn't a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the variable that should be fetched.
+    pub fn get_time(&self, key: &str) -> Option<SystemTime> {
+        match self.fields.get(key)? {
+            InfolistType::Time => self.time(key),
+            _ => None,
+        }
+    }
+
     /// Get the list of infolist variables that this item has.
+    ///
+    /// Only variables that can be fetched using `get()` are listed, i.e.
+    /// pointers that don't point to a buffer and buffers of raw data are
+    /// skipped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let infolist = weechat.get_infolist("buffer", None).unwrap();
+    ///
+    /// for item in infolist {
+    ///     let mut names: Vec<&String> = item.keys().collect();
+    ///     names.sort();
+    ///
+    ///     for name in names {
+    ///         println!("{}: {:?}", name, item.field_type(name));
+    ///     }
+    /// }
+    /// ```
     pub fn keys(&self) -> Keys<'_, String, InfolistType> {
         self.fields.keys()
     }
 
+    /// Get the type of a variable of this item.
+    ///
+    /// Returns `None` if the item has no variable with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the variable.
+    pub fn field_type(&self, key: &str) -> Option<InfolistType> {
+        self.fields.get(key).cloned()
+    }
+
     /// An iterator visiting all variables in an infolist item.
     /// The iterator element type a tuple of a string containing the variable
     /// name and the variable itself.
@@ -255,6 +345,86 @@ impl<'a> Infolist<'a> {
         )
     }
 
+    /// Get the number of items in the infolist.
+    ///
+    /// Weechat doesn't store the number of items, the items are counted the
+    /// first time this is called. Counting doesn't change which item the
+    /// iterator returns next.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let infolist = weechat.get_infolist("buffer", None).unwrap();
+    /// let mut names = Vec::with_capacity(infolist.len());
+    ///
+    /// for item in infolist {
+    ///     if let Some(name) = item.get_string("full_name") {
+    ///         names.push(name.into_owned());
+    ///     }
+    /// }
+    /// ```
+    pub fn len(&self) -> usize {
+        if let Some(len) = self.len.get() {
+            return len;
+        }
+
+        let infolist_next = self.weechat.get().infolist_next.unwrap();
+        let infolist_reset_item_cursor = self.weechat.get().infolist_reset_item_cursor.unwrap();
+
+        let mut len = 0;
+
+        unsafe {
+            infolist_reset_item_cursor(self.ptr);
+
+            while infolist_next(self.ptr) == 1 {
+                len += 1;
+            }
+
+            // Move the cursor back to the item it pointed to.
+            infolist_reset_item_cursor(self.ptr);
+
+            for _ in 0..self.position.get() {
+                infolist_next(self.ptr);
+            }
+        }
+
+        self.len.set(Some(len));
+
+        len
+    }
+
+    /// Returns true if the infolist contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move the cursor back to the start of the infolist.
+    ///
+    /// The next call to `next()` returns the first item again, this allows
+    /// the infolist to be iterated multiple times.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let mut infolist = weechat.get_infolist("buffer", None).unwrap();
+    ///
+    /// let hidden = infolist
+    ///     .by_ref()
+    ///     .filter(|item| item.get_integer("hidden") == Some(1))
+    ///     .count();
+    ///
+    /// infolist.reset();
+    /// let total = infolist.count();
+    /// ```
+    pub fn reset(&mut self) {
+        let infolist_reset_item_cursor = self.weechat.get().infolist_reset_item_cursor.unwrap();
+
+        unsafe { infolist_reset_item_cursor(self.ptr) };
+        self.position.set(0);
+    }
+
     fn get_fields(&self) -> HashMap<String, InfolistType> {
         let infolist_fields = self.weechat.get().infolist_fields.unwrap();
         let mut fields: HashMap<String, InfolistType> = HashMap::new();
@@ -270,21 +440,16 @@ impl<'a> Infolist<'a> {
             let infolist_type = split[0];
             let name = split[1];
 
-            // Skip the buffer, we can't safely expose them
-            // without knowing the size of the buffer. (Note the buffer here
-            // isn't a GUI buffer but a vector like thing.
-            if infolist_type == "b" {
-                continue;
-            }
-
-            let field = if infolist_type == "p" {
-                if Infolist::is_pointer_buffer(&self.infolist_name, name) {
+            // Buffers are skipped, we can't safely expose them without
+            // knowing the size of the buffer. (Note the buffer here isn't a
+            // GUI buffer but a vector like thing.) Pointers are only exposed
+            // if we know that they point to a GUI buffer.
+            let field = match infolist_type {
+                "i" | "s" | "t" => InfolistType::from(infolist_type),
+                "p" if Infolist::is_pointer_buffer(&self.infolist_name, name) => {
                     InfolistType::Buffer
-                } else {
-                    continue;
                 }
-            } else {
-                InfolistType::from(infolist_type)
+                _ => continue,
             };
 
             fields.insert(name.to_owned(), field);
@@ -338,6 +503,57 @@ impl Weechat {
         &self,
         infolist_name: &str,
         arguments: Option<&str>,
+    ) -> Result<Infolist, ()> {
+        self.infolist_get(infolist_name, ptr::null_mut(), arguments)
+    }
+
+    /// Get the infolist with the given name for a single buffer.
+    ///
+    /// Infolists that accept a buffer pointer only contain the items that
+    /// belong to the given buffer, e.g. the `buffer` infolist only contains
+    /// the buffer itself and the `nicklist` infolist only the nicks of the
+    /// buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `infolist_name` - The name of the infolist to fetch, it needs to
+    /// accept a buffer pointer.
+    ///
+    /// * `buffer` - The buffer the infolist should be fetched for.
+    ///
+    /// * `arguments` - Arguments that should be passed to Weechat while
+    /// fetching the infolist.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # let weechat = unsafe { weechat::Weechat::weechat() };
+    /// let buffer = weechat.current_buffer();
+    /// let infolist = weechat
+    ///     .get_infolist_for_buffer("nicklist", &buffer, None)
+    ///     .unwrap();
+    ///
+    /// for item in infolist {
+    ///     if item.get_string("type").as_deref() == Some("nick") {
+    ///         Weechat::print(&format!("{:?}", item.get_string("name")));
+    ///     }
+    /// }
+    /// ```
+    pub fn get_infolist_for_buffer(
+        &self,
+        infolist_name: &str,
+        buffer: &Buffer,
+        arguments: Option<&str>,
+    ) -> Result<Infolist, ()> {
+        self.infolist_get(infolist_name, buffer.ptr() as *mut c_void, arguments)
+    }
+
+    fn infolist_get(
+        &self,
+        infolist_name: &str,
+        pointer: *mut c_void,
+        arguments: Option<&str>,
     ) -> Result<Infolist, ()> {
         let infolist_get = self.get().infolist_get.unwrap();
 
@@ -352,7 +568,7 @@ impl Weechat {
             infolist_get(
                 self.ptr,
                 name.as_ptr(),
-                ptr::null_mut(),
+                pointer,
                 arguments.map_or(ptr::null_mut(), |a| a.as_ptr()),
             )
         };
@@ -364,6 +580,8 @@ impl Weechat {
                 ptr: infolist_ptr,
                 infolist_name: infolist_name.to_owned(),
                 weechat: &self,
+                position: Cell::new(0),
+                len: Cell::new(None),
             })
         }
     }
@@ -378,6 +596,7 @@ impl<'a> Iterator for Infolist<'a> {
         let ret = unsafe { infolist_next(self.ptr) };
 
         if ret == 1 {
+            self.position.set(self.position.get() + 1);
             let fields = self.get_fields();
 
             Some(InfolistItem {
@@ -387,6 +606,8 @@ impl<'a> Iterator for Infolist<'a> {
                 infolist: PhantomData,
             })
         } else {
+            // The cursor of Weechat is back at the start of the list.
+            self.position.set(0);
             None
         }
     }
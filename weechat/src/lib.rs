@@ -34,6 +34,35 @@
 //!     license: "MIT"
 //! );
 //! ```
+//!
+//! Plugins can also change the way Weechat behaves using modifiers. The
+//! following example, which requires the `unsound` feature, colors every nick
+//! in a rainbow color chosen by the nick's hash.
+//!
+//! ```no_run
+//! # #[cfg(feature = "unsound")]
+//! # fn main() {
+//! use std::{
+//!     collections::hash_map::DefaultHasher,
+//!     hash::{Hash, Hasher},
+//! };
+//!
+//! use weechat::{hooks::NickColorModifier, Weechat};
+//!
+//! const RAINBOW: &[&str] = &["red", "yellow", "green", "cyan", "blue", "magenta"];
+//!
+//! let modifier = NickColorModifier::new(|_: &Weechat, nick: &str| {
+//!     let mut hasher = DefaultHasher::new();
+//!     nick.hash(&mut hasher);
+//!     let index = hasher.finish() as usize % RAINBOW.len();
+//!
+//!     Some(RAINBOW[index].to_owned())
+//! })
+//! .expect("Can't hook the nick color modifiers");
+//! # }
+//! # #[cfg(not(feature = "unsound"))]
+//! # fn main() {}
+//! ```
 
 #![deny(missing_docs)]
 #![allow(clippy::result_unit_err)]
@@ -41,10 +70,12 @@
 
 use std::ffi::CString;
 
+mod eval;
 #[cfg(feature = "async")]
 mod executor;
 mod hashtable;
 mod hdata;
+mod secure;
 mod weechat;
 
 #[cfg(feature = "config_macro")]
@@ -60,7 +91,13 @@ pub mod buffer;
 pub mod config;
 pub mod hooks;
 pub mod infolist;
+pub mod time;
+pub mod trigger;
+pub mod util;
 
+pub use crate::eval::EvalExpression;
+pub use crate::hashtable::Hashtable;
+pub use crate::hdata::{HData, HDataPointer};
 pub use crate::weechat::{Args, Prefix, Weechat};
 
 pub use libc;
@@ -102,6 +139,16 @@ pub enum ReturnCode {
     Error = weechat_sys::WEECHAT_RC_ERROR as isize,
 }
 
+impl ReturnCode {
+    pub(crate) fn from_raw(return_code: i32) -> Self {
+        match return_code {
+            weechat_sys::WEECHAT_RC_OK => ReturnCode::Ok,
+            weechat_sys::WEECHAT_RC_OK_EAT => ReturnCode::OkEat,
+            _ => ReturnCode::Error,
+        }
+    }
+}
+
 pub(crate) struct LossyCString;
 
 impl LossyCString {
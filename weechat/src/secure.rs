@@ -0,0 +1,135 @@
+//! Access to the Weechat secured data.
+//!
+//! Secured data is set by the user with the `/secure` command and is usually
+//! referenced in options using the `${sec.data.name}` syntax. The values are
+//! passwords and other secrets, plugins should take care to never log or
+//! print them, which is why the values can only be fetched one at a time.
+
+use libc::c_char;
+use std::{ffi::CStr, os::raw::c_void, ptr};
+
+use weechat_sys::t_hashtable;
+
+use crate::{LossyCString, Weechat};
+
+/// A hashtable returned by Weechat that gets freed when dropped.
+struct SecuredData<'a> {
+    weechat: &'a Weechat,
+    ptr: *mut t_hashtable,
+}
+
+impl<'a> SecuredData<'a> {
+    fn new(weechat: &'a Weechat) -> Option<Self> {
+        let info_get_hashtable = weechat.get().info_get_hashtable.unwrap();
+        let info_name = LossyCString::new("secured_data");
+
+        let ptr = unsafe { info_get_hashtable(weechat.ptr, info_name.as_ptr(), ptr::null_mut()) };
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Self { weechat, ptr })
+        }
+    }
+}
+
+impl Drop for SecuredData<'_> {
+    fn drop(&mut self) {
+        let hashtable_free = self.weechat.get().hashtable_free.unwrap();
+        unsafe { hashtable_free(self.ptr) };
+    }
+}
+
+impl Weechat {
+    /// Get the names of all the secured data entries.
+    ///
+    /// Only the names of the entries are returned, the values need to be
+    /// fetched one by one using `secure_get()`. This is useful to offer
+    /// completion of `${sec.data.name}` references or to migrate credentials.
+    ///
+    /// The names are returned in sorted order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn secure_data_keys() -> Vec<String> {
+        unsafe extern "C" fn c_map_cb(
+            data: *mut c_void,
+            _hashtable: *mut t_hashtable,
+            key: *const c_char,
+            _value: *const c_char,
+        ) {
+            let keys: &mut Vec<String> = &mut *(data as *mut Vec<String>);
+            keys.push(CStr::from_ptr(key).to_string_lossy().to_string());
+        }
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let mut keys = Vec::new();
+
+        if let Some(secured_data) = SecuredData::new(weechat) {
+            let hashtable_map_string = weechat.get().hashtable_map_string.unwrap();
+
+            unsafe {
+                hashtable_map_string(
+                    secured_data.ptr,
+                    Some(c_map_cb),
+                    &mut keys as *mut _ as *mut c_void,
+                )
+            };
+        }
+
+        keys.sort();
+        keys
+    }
+
+    /// Get the value of a single secured data entry.
+    ///
+    /// Returns `None` if no entry with the given name exists.
+    ///
+    /// **Note**: The returned value is a secret, don't log it or print it to a
+    /// buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secured data entry, this is the `name` part
+    /// of a `${sec.data.name}` reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// for name in Weechat::secure_data_keys() {
+    ///     let password = Weechat::secure_get(&name);
+    ///     // Use the password but never print it.
+    /// }
+    /// ```
+    pub fn secure_get(name: &str) -> Option<String> {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let secured_data = SecuredData::new(weechat)?;
+        let hashtable_get = weechat.get().hashtable_get.unwrap();
+
+        let name = LossyCString::new(name);
+
+        unsafe {
+            let value = hashtable_get(secured_data.ptr, name.as_ptr() as *const c_void);
+
+            if value.is_null() {
+                None
+            } else {
+                Some(
+                    CStr::from_ptr(value as *const c_char)
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Conversions between Rust time types and the timestamps Weechat uses.
+//!
+//! Weechat represents dates as a unix timestamp (`time_t`), be it the date of
+//! a printed line, a time variable of an infolist or a hdata time field. The
+//! traits in this module convert from and to those timestamps, the crate
+//! accepts and returns the Rust types instead of bare integers.
+//!
+//! Conversions for the `chrono` types are available if the `chrono` feature
+//! is enabled.
+
+use std::time::{Duration, SystemTime};
+
+use crate::Weechat;
+
+/// Trait for types that can be converted into a Weechat timestamp.
+pub trait ToWeechatTime {
+    /// Convert the value into a unix timestamp, the number of seconds since
+    /// the unix epoch. Dates before the unix epoch are negative.
+    ///
+    /// Note that Weechat treats a timestamp of 0, the unix epoch itself, as
+    /// the current date when printing a line.
+    fn to_weechat_time(&self) -> i64;
+}
+
+/// Trait for types that can be created from a Weechat timestamp.
+pub trait FromWeechatTime: Sized {
+    /// Create the value from a unix timestamp.
+    ///
+    /// Timestamps that the type can't represent are clamped to the earliest
+    /// or latest date the type supports.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The number of seconds since the unix epoch.
+    fn from_weechat_time(time: i64) -> Self;
+}
+
+impl ToWeechatTime for SystemTime {
+    fn to_weechat_time(&self) -> i64 {
+        match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        }
+    }
+}
+
+impl FromWeechatTime for SystemTime {
+    fn from_weechat_time(time: i64) -> Self {
+        let duration = Duration::from_secs(time.unsigned_abs());
+
+        let date = if time >= 0 {
+            SystemTime::UNIX_EPOCH.checked_add(duration)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_sub(duration)
+        };
+
+        // The range of a SystemTime depends on the platform, the unix epoch
+        // is the only date that is known to be representable.
+        date.unwrap_or(SystemTime::UNIX_EPOCH)
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(feature = "docs", doc(cfg(chrono)))]
+impl<Tz: chrono::TimeZone> ToWeechatTime for chrono::DateTime<Tz> {
+    fn to_weechat_time(&self) -> i64 {
+        self.timestamp()
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(feature = "docs", doc(cfg(chrono)))]
+impl FromWeechatTime for chrono::DateTime<chrono::Utc> {
+    fn from_weechat_time(time: i64) -> Self {
+        use chrono::TimeZone;
+
+        chrono::Utc
+            .timestamp_opt(time, 0)
+            .single()
+            .unwrap_or(if time < 0 {
+                chrono::DateTime::<chrono::Utc>::MIN_UTC
+            } else {
+                chrono::DateTime::<chrono::Utc>::MAX_UTC
+            })
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(feature = "docs", doc(cfg(chrono)))]
+impl FromWeechatTime for chrono::DateTime<chrono::Local> {
+    fn from_weechat_time(time: i64) -> Self {
+        chrono::DateTime::<chrono::Utc>::from_weechat_time(time).with_timezone(&chrono::Local)
+    }
+}
+
+impl Weechat {
+    /// Format a date relative to the current time.
+    ///
+    /// The date is formatted using the largest unit that fits, e.g. "now",
+    /// "3m ago", "2h ago" or "in 5d" for dates in the future. This is useful
+    /// for bar items that show how long ago something happened.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The date that should be formatted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::{Duration, SystemTime};
+    /// # use weechat::Weechat;
+    /// let three_minutes_ago = SystemTime::now() - Duration::from_secs(180);
+    ///
+    /// assert_eq!(Weechat::format_time_relative(three_minutes_ago), "3m ago");
+    /// ```
+    pub fn format_time_relative(time: impl ToWeechatTime) -> String {
+        let now = SystemTime::now().to_weechat_time();
+        let difference = now - time.to_weechat_time();
+        let seconds = difference.abs();
+
+        let (value, unit) = if seconds < 60 {
+            (seconds, "s")
+        } else if seconds < 60 * 60 {
+            (seconds / 60, "m")
+        } else if seconds < 60 * 60 * 24 {
+            (seconds / (60 * 60), "h")
+        } else {
+            (seconds / (60 * 60 * 24), "d")
+        };
+
+        if seconds == 0 {
+            "now".to_owned()
+        } else if difference > 0 {
+            format!("{}{} ago", value, unit)
+        } else {
+            format!("in {}{}", value, unit)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_time_round_trip() {
+        for &time in &[0, 1_600_000_000, -86_400] {
+            assert_eq!(SystemTime::from_weechat_time(time).to_weechat_time(), time);
+        }
+    }
+
+    #[test]
+    fn out_of_range_timestamps_dont_panic() {
+        SystemTime::from_weechat_time(i64::MIN);
+        SystemTime::from_weechat_time(i64::MAX);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_timestamps_are_clamped() {
+        use chrono::{DateTime, Local, Utc};
+
+        assert_eq!(
+            DateTime::<Utc>::from_weechat_time(i64::MIN),
+            DateTime::<Utc>::MIN_UTC
+        );
+        assert_eq!(
+            DateTime::<Utc>::from_weechat_time(i64::MAX),
+            DateTime::<Utc>::MAX_UTC
+        );
+        assert_eq!(
+            DateTime::<Local>::from_weechat_time(1_600_000_000).timestamp(),
+            1_600_000_000
+        );
+    }
+}
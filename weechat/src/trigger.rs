@@ -0,0 +1,561 @@
+//! Create and remove triggers of the trigger plugin.
+//!
+//! Triggers are stored in the configuration of the user, unlike hooks they
+//! persist after the plugin that created them was unloaded. A trigger is
+//! described by a [`TriggerSpec`] which is turned into a `/trigger` command,
+//! the fields of the spec are escaped so they arrive at the trigger plugin
+//! unchanged.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use weechat::Weechat;
+//! # use weechat::trigger::{TriggerHook, TriggerRegex, TriggerSpec};
+//! # let weechat = unsafe { Weechat::weechat() };
+//! // Show the names of users relayed by a bridge bot in bold.
+//! let spec = TriggerSpec::new("bridge_prefix", TriggerHook::Modifier)
+//!     .add_argument("weechat_print")
+//!     .conditions("${tg_tag_nick} == bridge")
+//!     .add_regex(
+//!         TriggerRegex::new("^<([^>]+)> ", "${color:bold}${re:1}${color:-bold}: ")
+//!             .variable("tg_message"),
+//!     );
+//!
+//! if !weechat.trigger_exists("bridge_prefix") {
+//!     weechat.trigger_add(&spec).unwrap();
+//! }
+//! ```
+
+use crate::{buffer::CommandError, infolist::InfolistVariable, Weechat};
+
+/// The characters that are tried, in order, as the delimiter of a regex.
+const REGEX_DELIMITERS: &[char] = &[
+    '/', '|', '!', '#', '%', ',', ':', '@', '~', '+', '-', '_', '=', '&', '^',
+];
+
+/// The kind of hook a trigger uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TriggerHook {
+    Signal,
+    Hsignal,
+    Modifier,
+    Line,
+    Print,
+    Command,
+    CommandRun,
+    Timer,
+    Config,
+    Focus,
+    Info,
+    InfoHashtable,
+}
+
+impl TriggerHook {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TriggerHook::Signal => "signal",
+            TriggerHook::Hsignal => "hsignal",
+            TriggerHook::Modifier => "modifier",
+            TriggerHook::Line => "line",
+            TriggerHook::Print => "print",
+            TriggerHook::Command => "command",
+            TriggerHook::CommandRun => "command_run",
+            TriggerHook::Timer => "timer",
+            TriggerHook::Config => "config",
+            TriggerHook::Focus => "focus",
+            TriggerHook::Info => "info",
+            TriggerHook::InfoHashtable => "info_hashtable",
+        }
+    }
+}
+
+/// The return code of the trigger callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TriggerReturnCode {
+    /// Continue normally, this is the default.
+    #[default]
+    Ok,
+    /// Stop the processing of the hooked event, e.g. to hide a message.
+    OkEat,
+    /// Signal an error.
+    Error,
+}
+
+impl TriggerReturnCode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TriggerReturnCode::Ok => "ok",
+            TriggerReturnCode::OkEat => "ok_eat",
+            TriggerReturnCode::Error => "error",
+        }
+    }
+}
+
+/// The action that is taken after the trigger ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TriggerPostAction {
+    /// Keep the trigger, this is the default.
+    #[default]
+    None,
+    /// Disable the trigger.
+    Disable,
+    /// Delete the trigger.
+    Delete,
+}
+
+impl TriggerPostAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TriggerPostAction::None => "none",
+            TriggerPostAction::Disable => "disable",
+            TriggerPostAction::Delete => "delete",
+        }
+    }
+}
+
+/// Error type for triggers that couldn't be created or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggerError {
+    /// The name of the trigger is empty, starts with a `-` or contains a
+    /// space or a dot.
+    InvalidName(String),
+    /// A hook argument contains a `;`, hook arguments are separated by
+    /// semicolons and can't be escaped.
+    InvalidArgument(String),
+    /// A field of the trigger contains a newline or a carriage return.
+    InvalidCharacter(char),
+    /// Every possible delimiter appears in the regex, its replacement or its
+    /// variable.
+    NoRegexDelimiter(String),
+    /// Weechat failed to run the `/trigger` command.
+    Command(CommandError),
+    /// The trigger doesn't exist after it should have been created or still
+    /// exists after it should have been deleted.
+    NotApplied(String),
+}
+
+impl From<CommandError> for TriggerError {
+    fn from(error: CommandError) -> Self {
+        TriggerError::Command(error)
+    }
+}
+
+/// A regex of a trigger, it replaces the text it matches in one of the
+/// variables of the trigger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerRegex {
+    regex: String,
+    replacement: String,
+    variable: Option<String>,
+}
+
+impl TriggerRegex {
+    /// Create a new trigger regex.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex` - The POSIX extended regular expression that should be
+    ///     matched.
+    ///
+    /// * `replacement` - The replacement for the matched text, it is
+    ///     evaluated and may refer to groups of the regex using `${re:N}`.
+    pub fn new<R: Into<String>, T: Into<String>>(regex: R, replacement: T) -> Self {
+        TriggerRegex {
+            regex: regex.into(),
+            replacement: replacement.into(),
+            variable: None,
+        }
+    }
+
+    /// Set the variable the regex should be applied to.
+    ///
+    /// If no variable is set the trigger plugin picks the default variable
+    /// of the hook, e.g. `tg_message` for the `print` hook.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The name of the variable.
+    pub fn variable<V: Into<String>>(mut self, variable: V) -> Self {
+        self.variable = Some(variable.into());
+        self
+    }
+
+    fn format(&self) -> Result<String, TriggerError> {
+        let variable = self.variable.as_deref().unwrap_or("");
+
+        if variable.is_empty() && self.variable.is_some() || variable.contains(char::is_whitespace)
+        {
+            return Err(TriggerError::InvalidArgument(variable.to_owned()));
+        }
+
+        let delimiter = REGEX_DELIMITERS
+            .iter()
+            .find(|d| {
+                !self.regex.contains(**d)
+                    && !self.replacement.contains(**d)
+                    && !variable.contains(**d)
+            })
+            .ok_or_else(|| TriggerError::NoRegexDelimiter(self.regex.clone()))?;
+
+        let mut regex = format!("{d}{}{d}{}", self.regex, self.replacement, d = delimiter);
+
+        if let Some(variable) = &self.variable {
+            regex.push(*delimiter);
+            regex.push_str(variable);
+        }
+
+        Ok(regex)
+    }
+}
+
+/// Description of a trigger that should be created.
+///
+/// The fields accept the same formats that are described in the
+/// documentation of the `/trigger` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerSpec {
+    name: String,
+    hook: TriggerHook,
+    arguments: Vec<String>,
+    conditions: String,
+    regexes: Vec<TriggerRegex>,
+    commands: Vec<String>,
+    return_code: TriggerReturnCode,
+    post_action: TriggerPostAction,
+    enabled: bool,
+    replace: bool,
+}
+
+impl TriggerSpec {
+    /// Create a new trigger description.
+    ///
+    /// The trigger is enabled and doesn't replace an existing trigger with
+    /// the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the trigger.
+    ///
+    /// * `hook` - The kind of hook the trigger should use.
+    pub fn new<N: Into<String>>(name: N, hook: TriggerHook) -> Self {
+        TriggerSpec {
+            name: name.into(),
+            hook,
+            arguments: Vec::new(),
+            conditions: String::new(),
+            regexes: Vec::new(),
+            commands: Vec::new(),
+            return_code: TriggerReturnCode::default(),
+            post_action: TriggerPostAction::default(),
+            enabled: true,
+            replace: false,
+        }
+    }
+
+    /// Get the name of the trigger.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Add an argument for the hook, e.g. the name of a signal.
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - The argument that should be added, it may not contain
+    ///     a `;`.
+    pub fn add_argument<T: Into<String>>(mut self, argument: T) -> Self {
+        self.arguments.push(argument.into());
+        self
+    }
+
+    /// Set the conditions of the trigger.
+    ///
+    /// # Arguments
+    ///
+    /// * `conditions` - An expression that is evaluated each time the
+    ///     trigger runs, the trigger continues only if it evaluates to true.
+    pub fn conditions<T: Into<String>>(mut self, conditions: T) -> Self {
+        self.conditions = conditions.into();
+        self
+    }
+
+    /// Add a regex to the trigger, regexes are applied in the order they are
+    /// added.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex` - The regex that should be added.
+    pub fn add_regex(mut self, regex: TriggerRegex) -> Self {
+        self.regexes.push(regex);
+        self
+    }
+
+    /// Add a command to the trigger, commands are run in the order they are
+    /// added.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command that should be added, it is evaluated
+    ///     before it runs.
+    pub fn add_command<T: Into<String>>(mut self, command: T) -> Self {
+        self.commands.push(command.into());
+        self
+    }
+
+    /// Set the return code of the trigger callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `return_code` - The return code that should be used.
+    pub fn return_code(mut self, return_code: TriggerReturnCode) -> Self {
+        self.return_code = return_code;
+        self
+    }
+
+    /// Set the action that is taken after the trigger ran.
+    ///
+    /// # Arguments
+    ///
+    /// * `post_action` - The action that should be taken.
+    pub fn post_action(mut self, post_action: TriggerPostAction) -> Self {
+        self.post_action = post_action;
+        self
+    }
+
+    /// Set if the trigger should be enabled after it was created.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - True if the trigger should be enabled.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Set if the trigger should replace an existing trigger with the same
+    /// name.
+    ///
+    /// # Arguments
+    ///
+    /// * `replace` - True if an existing trigger should be replaced.
+    pub fn replace(mut self, replace: bool) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    /// Get the `/trigger` command that creates the trigger.
+    ///
+    /// Every field is quoted, backslashes and double quotes inside of fields
+    /// are escaped with a backslash. Semicolons inside of commands are
+    /// escaped as well, the trigger plugin uses them to separate commands.
+    /// Each regex uses the first delimiter that doesn't appear in it.
+    ///
+    /// A replacing trigger is always created enabled, `Weechat::trigger_add()`
+    /// disables it afterwards if needed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use weechat::trigger::{
+    /// #     TriggerError, TriggerHook, TriggerPostAction, TriggerRegex, TriggerReturnCode,
+    /// #     TriggerSpec,
+    /// # };
+    /// let spec = TriggerSpec::new("beep", TriggerHook::Print)
+    ///     .conditions("${tg_highlight}")
+    ///     .add_command("/print -beep");
+    ///
+    /// assert_eq!(
+    ///     spec.to_command().unwrap(),
+    ///     r#"/trigger add beep print "" "${tg_highlight}" "" "/print -beep" "ok" "none""#
+    /// );
+    ///
+    /// // Quotes and backslashes are escaped, semicolons inside of commands
+    /// // don't start a new command.
+    /// let spec = TriggerSpec::new("quote", TriggerHook::Signal)
+    ///     .add_argument("*,irc_in_privmsg")
+    ///     .add_argument("day_changed")
+    ///     .conditions(r#"${tg_signal_data} =~ "\d""#)
+    ///     .add_command("/print a;b")
+    ///     .add_command(r#"/print "quoted""#)
+    ///     .return_code(TriggerReturnCode::OkEat)
+    ///     .post_action(TriggerPostAction::Delete)
+    ///     .enabled(false);
+    ///
+    /// assert_eq!(
+    ///     spec.to_command().unwrap(),
+    ///     r#"/trigger addoff quote signal "*,irc_in_privmsg;day_changed" "${tg_signal_data} =~ \"\\d\"" "" "/print a\\;b;/print \"quoted\"" "ok_eat" "delete""#
+    /// );
+    ///
+    /// // The regex delimiter is chosen so it doesn't clash with the regex.
+    /// let spec = TriggerSpec::new("url", TriggerHook::Modifier)
+    ///     .add_argument("weechat_print")
+    ///     .add_regex(TriggerRegex::new("https?://", "<url>").variable("tg_string"))
+    ///     .add_regex(TriggerRegex::new("a|b", "c"))
+    ///     .replace(true);
+    ///
+    /// assert_eq!(
+    ///     spec.to_command().unwrap(),
+    ///     r#"/trigger addreplace url modifier "weechat_print" "" "|https?://|<url>|tg_string /a|b/c" "" "ok" "none""#
+    /// );
+    ///
+    /// // Invalid specs are rejected.
+    /// assert_eq!(
+    ///     TriggerSpec::new("my.trigger", TriggerHook::Timer).to_command(),
+    ///     Err(TriggerError::InvalidName("my.trigger".to_owned()))
+    /// );
+    /// assert_eq!(
+    ///     TriggerSpec::new("signals", TriggerHook::Signal)
+    ///         .add_argument("a;b")
+    ///         .to_command(),
+    ///     Err(TriggerError::InvalidArgument("a;b".to_owned()))
+    /// );
+    /// assert_eq!(
+    ///     TriggerSpec::new("lines", TriggerHook::Print)
+    ///         .add_command("/print a\n/print b")
+    ///         .to_command(),
+    ///     Err(TriggerError::InvalidCharacter('\n'))
+    /// );
+    /// ```
+    pub fn to_command(&self) -> Result<String, TriggerError> {
+        if !is_valid_name(&self.name) {
+            return Err(TriggerError::InvalidName(self.name.clone()));
+        }
+
+        if let Some(argument) = self.arguments.iter().find(|a| a.contains(';')) {
+            return Err(TriggerError::InvalidArgument(argument.clone()));
+        }
+
+        let regexes = self
+            .regexes
+            .iter()
+            .map(TriggerRegex::format)
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" ");
+
+        let commands = self
+            .commands
+            .iter()
+            .map(|c| c.replace(';', "\\;"))
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let fields = [
+            self.arguments.join(";"),
+            self.conditions.clone(),
+            regexes,
+            commands,
+            self.return_code.as_str().to_owned(),
+            self.post_action.as_str().to_owned(),
+        ];
+
+        if let Some(c) = fields
+            .iter()
+            .flat_map(|f| f.chars())
+            .find(|c| *c == '\n' || *c == '\r')
+        {
+            return Err(TriggerError::InvalidCharacter(c));
+        }
+
+        let action = match (self.replace, self.enabled) {
+            (true, _) => "addreplace",
+            (false, true) => "add",
+            (false, false) => "addoff",
+        };
+
+        let mut command = format!("/trigger {} {} {}", action, self.name, self.hook.as_str());
+
+        for field in &fields {
+            command.push(' ');
+            command.push_str(&quote(field));
+        }
+
+        Ok(command)
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !(name.is_empty() || name.starts_with('-') || name.contains(' ') || name.contains('.'))
+}
+
+fn quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Weechat {
+    /// Create a new trigger.
+    ///
+    /// Returns an error if the spec is invalid, if Weechat failed to run the
+    /// `/trigger` command or if the trigger doesn't exist afterwards, e.g.
+    /// because a trigger with the same name already exists or the trigger
+    /// plugin isn't loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The description of the trigger.
+    pub fn trigger_add(&self, spec: &TriggerSpec) -> Result<(), TriggerError> {
+        let command = spec.to_command()?;
+        let core = self.core_buffer();
+
+        if !spec.replace && self.trigger_exists(&spec.name) {
+            return Err(TriggerError::NotApplied(spec.name.clone()));
+        }
+
+        core.run_command(&command)?;
+
+        if !self.trigger_exists(&spec.name) {
+            return Err(TriggerError::NotApplied(spec.name.clone()));
+        }
+
+        if spec.replace && !spec.enabled {
+            core.run_command(&format!("/trigger disable {}", spec.name))?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete a trigger.
+    ///
+    /// Returns an error if the trigger still exists afterwards, e.g. because
+    /// it didn't exist in the first place.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the trigger.
+    pub fn trigger_del(&self, name: &str) -> Result<(), TriggerError> {
+        if !is_valid_name(name) {
+            return Err(TriggerError::InvalidName(name.to_owned()));
+        }
+
+        if !self.trigger_exists(name) {
+            return Err(TriggerError::NotApplied(name.to_owned()));
+        }
+
+        self.core_buffer()
+            .run_command(&format!("/trigger del {}", name))?;
+
+        if self.trigger_exists(name) {
+            Err(TriggerError::NotApplied(name.to_owned()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check if a trigger with the given name exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the trigger.
+    pub fn trigger_exists(&self, name: &str) -> bool {
+        self.get_infolist("trigger", Some(name))
+            .map(|infolist| {
+                infolist.into_iter().any(|item| {
+                    matches!(
+                        item.get("name"),
+                        Some(InfolistVariable::String(trigger)) if trigger == name
+                    )
+                })
+            })
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,293 @@
+//! Miscellaneous helpers for plugins.
+//!
+//! The name validation helpers check names of buffers, bar items and
+//! completions before they are created.
+//!
+//! The fuzzy matching helpers use the [fuzzy-matcher] crate to provide
+//! fzf-like matching, they are available if the `fuzzy` feature is enabled.
+//!
+//! [fuzzy-matcher]: https://docs.rs/fuzzy-matcher/
+
+use std::ffi::CStr;
+
+#[cfg(feature = "fuzzy")]
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+use crate::Weechat;
+
+/// The kind of object a name belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    /// The name of a buffer created by the plugin.
+    Buffer,
+    /// The name of a bar item.
+    BarItem,
+    /// The name of a completion item.
+    Completion,
+}
+
+impl NameKind {
+    fn is_invalid_char(self, c: char) -> bool {
+        // Names are passed to Weechat as C strings, Weechat additionally
+        // refuses completion items that contain a space.
+        c == '\0' || (self == NameKind::Completion && c == ' ')
+    }
+}
+
+/// Error type for invalid names, returned by `Weechat::validate_name()` and
+/// `Buffer::set_name()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameError {
+    /// The name is empty.
+    Empty,
+    /// The name contains a character that Weechat doesn't accept, the kind
+    /// of the name, the name and the first invalid character are attached.
+    InvalidCharacter(NameKind, String, char),
+    /// An object of the same kind with the same name already exists.
+    AlreadyExists(String),
+}
+
+impl NameError {
+    /// Get a valid name that can be used instead of the invalid one.
+    ///
+    /// Every character that isn't valid for the kind of the name is replaced
+    /// with an underscore.
+    /// Returns `None` if the name was empty or already exists, in which case
+    /// no suggestion can be made.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use weechat::util::{NameError, NameKind};
+    /// let error = NameError::InvalidCharacter(
+    ///     NameKind::Completion,
+    ///     "rust lang".to_owned(),
+    ///     ' ',
+    /// );
+    ///
+    /// assert_eq!(error.sanitized_suggestion(), Some("rust_lang".to_owned()));
+    /// ```
+    pub fn sanitized_suggestion(&self) -> Option<String> {
+        match self {
+            NameError::InvalidCharacter(kind, name, _) => Some(
+                name.chars()
+                    .map(|c| if kind.is_invalid_char(c) { '_' } else { c })
+                    .collect(),
+            ),
+            NameError::Empty | NameError::AlreadyExists(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for NameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameError::Empty => write!(f, "the name is empty"),
+            NameError::InvalidCharacter(_, name, c) => {
+                write!(
+                    f,
+                    "the name {:?} contains the invalid character {:?}",
+                    name, c
+                )
+            }
+            NameError::AlreadyExists(name) => write!(f, "the name {} is already in use", name),
+        }
+    }
+}
+
+/// Check a name against the rules Weechat enforces for the given kind of
+/// object, without looking for existing objects.
+pub(crate) fn check_name(kind: NameKind, name: &str) -> Result<(), NameError> {
+    if name.is_empty() {
+        return Err(NameError::Empty);
+    }
+
+    match name.chars().find(|c| kind.is_invalid_char(*c)) {
+        Some(c) => Err(NameError::InvalidCharacter(kind, name.to_owned(), c)),
+        None => Ok(()),
+    }
+}
+
+impl Weechat {
+    /// Check if a name can be used to create a new object of the given kind.
+    ///
+    /// This checks the same rules Weechat does: a name needs to be non empty
+    /// and may not contain NUL bytes, completion items may additionally not
+    /// contain spaces. Buffer names need to be unique across the plugin.
+    ///
+    /// Weechat refuses a second bar item with the same name only if it was
+    /// created by the same plugin, `BarItem::new()` reports this as
+    /// `NameError::AlreadyExists`.
+    ///
+    /// The constructors of buffers, bar items and completions call this
+    /// method and fail if the name is invalid, plugins that generate names,
+    /// e.g. from user data, can call it beforehand to find out why a name
+    /// was rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of object the name is meant for.
+    ///
+    /// * `name` - The name that should be checked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::BufferBuilder;
+    /// # use weechat::util::NameKind;
+    /// let room_name = "#rust/general";
+    ///
+    /// let name = match Weechat::validate_name(NameKind::Buffer, room_name) {
+    ///     Ok(()) => room_name.to_owned(),
+    ///     Err(e) => e.sanitized_suggestion().expect("Can't create a buffer name"),
+    /// };
+    ///
+    /// let buffer_handle = BufferBuilder::new(&name)
+    ///     .build()
+    ///     .expect("Can't create new buffer");
+    /// ```
+    pub fn validate_name(kind: NameKind, name: &str) -> Result<(), NameError> {
+        check_name(kind, name)?;
+
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        if kind == NameKind::Buffer {
+            let plugin_name = unsafe { CStr::from_ptr(weechat.get().name) };
+
+            if weechat
+                .buffer_search(&plugin_name.to_string_lossy(), name)
+                .is_some()
+            {
+                return Err(NameError::AlreadyExists(name.to_owned()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Match a string against a pattern using fuzzy matching.
+///
+/// The pattern is matched smart case, it's case insensitive unless the pattern
+/// contains an upper case character.
+///
+/// Returns the score of the match, a higher score means a better match, and
+/// the indices of the characters of the string that matched the pattern.
+/// Returns `None` if the string doesn't match the pattern.
+///
+/// # Arguments
+///
+/// * `pattern` - The pattern that should be matched.
+///
+/// * `text` - The string that should be matched against the pattern.
+///
+/// # Example
+///
+/// ```
+/// # use weechat::util::fuzzy_indices;
+/// let (_, indices) = fuzzy_indices("wlk", "weechat.look").unwrap();
+/// assert_eq!(indices, vec![0, 8, 11]);
+///
+/// assert!(fuzzy_indices("xyz", "weechat.look").is_none());
+/// ```
+#[cfg(feature = "fuzzy")]
+#[cfg_attr(feature = "docs", doc(cfg(fuzzy)))]
+pub fn fuzzy_indices(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let matcher = SkimMatcherV2::default().smart_case();
+    matcher.fuzzy_indices(text, pattern)
+}
+
+/// Get the score of a fuzzy match of a string against a pattern.
+///
+/// This is the same as `fuzzy_indices()` but only returns the score.
+///
+/// # Arguments
+///
+/// * `pattern` - The pattern that should be matched.
+///
+/// * `text` - The string that should be matched against the pattern.
+///
+/// # Example
+///
+/// ```
+/// # use weechat::util::fuzzy_score;
+/// let exact = fuzzy_score("look", "weechat.look").unwrap();
+/// let scattered = fuzzy_score("look", "weechat.color.bar_more_kick").unwrap();
+///
+/// assert!(exact > scattered);
+/// ```
+#[cfg(feature = "fuzzy")]
+#[cfg_attr(feature = "docs", doc(cfg(fuzzy)))]
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let matcher = SkimMatcherV2::default().smart_case();
+    matcher.fuzzy_match(text, pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_with_spaces_and_slashes_are_valid() {
+        assert_eq!(check_name(NameKind::Buffer, "Test buffer"), Ok(()));
+        assert_eq!(check_name(NameKind::Buffer, "#rust/general,2"), Ok(()));
+        assert_eq!(check_name(NameKind::BarItem, "buffer plugin"), Ok(()));
+        assert_eq!(check_name(NameKind::Buffer, "Café"), Ok(()));
+    }
+
+    #[test]
+    fn empty_names_are_invalid() {
+        assert_eq!(check_name(NameKind::Buffer, ""), Err(NameError::Empty));
+        assert_eq!(check_name(NameKind::BarItem, ""), Err(NameError::Empty));
+        assert_eq!(check_name(NameKind::Completion, ""), Err(NameError::Empty));
+    }
+
+    #[test]
+    fn nul_bytes_are_invalid() {
+        assert_eq!(
+            check_name(NameKind::Buffer, "a\0b"),
+            Err(NameError::InvalidCharacter(
+                NameKind::Buffer,
+                "a\0b".to_owned(),
+                '\0'
+            ))
+        );
+    }
+
+    #[test]
+    fn completion_items_cant_contain_spaces() {
+        assert_eq!(check_name(NameKind::Completion, "room_names"), Ok(()));
+        assert_eq!(
+            check_name(NameKind::Completion, "room names"),
+            Err(NameError::InvalidCharacter(
+                NameKind::Completion,
+                "room names".to_owned(),
+                ' '
+            ))
+        );
+    }
+
+    #[test]
+    fn suggestions_replace_invalid_characters() {
+        let error = check_name(NameKind::Completion, "room names\0").unwrap_err();
+        assert_eq!(error.sanitized_suggestion(), Some("room_names_".to_owned()));
+
+        let error = check_name(NameKind::Completion, "a\0 b").unwrap_err();
+        assert_eq!(error.sanitized_suggestion(), Some("a__b".to_owned()));
+
+        let error = check_name(NameKind::Buffer, "a\0 b").unwrap_err();
+        assert_eq!(error.sanitized_suggestion(), Some("a_ b".to_owned()));
+
+        assert_eq!(NameError::Empty.sanitized_suggestion(), None);
+        assert_eq!(
+            NameError::AlreadyExists("core".to_owned()).sanitized_suggestion(),
+            None
+        );
+    }
+}
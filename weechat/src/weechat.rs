@@ -4,7 +4,7 @@ use backtrace::Backtrace;
 
 use weechat_sys::t_weechat_plugin;
 
-use crate::LossyCString;
+use crate::{Hashtable, LossyCString};
 use libc::{c_char, c_int};
 use std::{
     ffi::{CStr, CString},
@@ -111,6 +111,7 @@ impl DoubleEndedIterator for Args {
 
 static mut WEECHAT: Option<Weechat> = None;
 static mut WEECHAT_THREAD_ID: Option<std::thread::ThreadId> = None;
+static mut WEECHAT_UNLOADING: bool = false;
 
 /// Main Weechat struct that encapsulates common weechat API functions.
 /// It has a similar API as the weechat script API.
@@ -134,6 +135,7 @@ impl Weechat {
 
         WEECHAT = Some(Weechat { ptr });
         WEECHAT_THREAD_ID = Some(std::thread::current().id());
+        WEECHAT_UNLOADING = false;
 
         std::panic::set_hook(Box::new(Weechat::panic_hook));
 
@@ -218,12 +220,33 @@ impl Weechat {
         }
     }
 
+    /// Mark the plugin as being unloaded.
+    ///
+    /// Weechat removes all the hooks, bar items and config files of a plugin
+    /// after the plugin is unloaded, objects that are dropped after this is
+    /// called only free their Rust side data and leave the Weechat side to
+    /// Weechat.
+    ///
+    /// # Safety
+    ///
+    /// This should never be called by the user. This is called internally.
+    #[doc(hidden)]
+    pub unsafe fn plugin_unloading() {
+        WEECHAT_UNLOADING = true;
+    }
+
+    /// Is the plugin being unloaded, see `plugin_unloading()`.
+    pub(crate) fn is_unloading() -> bool {
+        unsafe { WEECHAT_UNLOADING }
+    }
+
     /// Free internal plugin data.
     /// # Safety
     ///
     /// This should never be called by the user. This is called internally.
     #[doc(hidden)]
     pub unsafe fn free() {
+        crate::hooks::free_deferred_signals();
         #[cfg(feature = "async")]
         WeechatExecutor::free();
     }
@@ -393,11 +416,28 @@ impl Weechat {
 
     /// Get some info from Weechat or a plugin.
     ///
+    /// Returns `None` if no info with the given name exists or the info has
+    /// no value for the given arguments.
+    ///
     /// # Arguments
     ///
     /// * `name` - name the info
     ///
     /// * `arguments` - arguments for the info
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// let version = Weechat::info_get("version", "").expect("Can't get the version");
+    /// assert!(!version.is_empty());
+    ///
+    /// assert_eq!(Weechat::info_get("no_such_info", ""), None);
+    /// ```
     pub fn info_get(name: &str, arguments: &str) -> Option<String> {
         Weechat::check_thread();
         let weechat = unsafe { Weechat::weechat() };
@@ -412,11 +452,159 @@ impl Weechat {
             if info.is_null() {
                 None
             } else {
-                Some(CStr::from_ptr(info).to_string_lossy().to_string())
+                let string = CStr::from_ptr(info).to_string_lossy().to_string();
+                libc::free(info as *mut libc::c_void);
+
+                Some(string)
             }
         }
     }
 
+    /// Get some info from Weechat or a plugin as a hashtable.
+    ///
+    /// Returns `None` if no info with the given name exists or the info
+    /// failed to produce a result.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the info, e.g. `irc_message_parse`.
+    ///
+    /// * `arguments` - The arguments for the info, the expected keys depend
+    ///     on the info.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::{Hashtable, Weechat};
+    /// let mut arguments = Hashtable::new();
+    /// arguments
+    ///     .set("message", ":nick!user@host PRIVMSG #rust :Hello")
+    ///     .unwrap();
+    ///
+    /// let parsed = Weechat::info_get_hashtable("irc_message_parse", &arguments)
+    ///     .expect("Can't parse the message");
+    ///
+    /// assert_eq!(parsed.get("nick").as_deref(), Some("nick"));
+    /// assert_eq!(parsed.get("channel").as_deref(), Some("#rust"));
+    /// ```
+    pub fn info_get_hashtable(name: &str, arguments: &Hashtable) -> Option<Hashtable<'static>> {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let info_get_hashtable = weechat.get().info_get_hashtable.unwrap();
+
+        let info_name = LossyCString::new(name);
+
+        let info = unsafe { info_get_hashtable(weechat.ptr, info_name.as_ptr(), arguments.ptr()) };
+
+        if info.is_null() {
+            None
+        } else {
+            Some(Hashtable::from_raw(weechat, info))
+        }
+    }
+
+    /// Get the version of Weechat, e.g. `2.9`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    pub fn version() -> String {
+        Weechat::info_get("version", "").unwrap_or_default()
+    }
+
+    /// Get the version of Weechat as a number, e.g. `0x02090000` for
+    /// version 2.9.
+    ///
+    /// The number can be used to check if a feature is available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// if Weechat::version_number() >= 0x03020000 {
+    ///     let data_dir = Weechat::info_get("weechat_data_dir", "");
+    /// }
+    /// ```
+    pub fn version_number() -> u32 {
+        Weechat::info_get("version_number", "")
+            .and_then(|number| number.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Get the color code of a nick.
+    ///
+    /// The color is picked the same way Weechat colors nicks, it respects
+    /// the `weechat.look.nick_color_*` options. The color code can be
+    /// embedded in a printed line.
+    ///
+    /// # Arguments
+    ///
+    /// * `nick` - The nick that should be colored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::BufferBuilder;
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// let nick = "poljar";
+    ///
+    /// buffer.print(&format!(
+    ///     "{}{}{}\tHello",
+    ///     Weechat::nick_color(nick),
+    ///     nick,
+    ///     Weechat::color("reset")
+    /// ));
+    /// ```
+    pub fn nick_color(nick: &str) -> String {
+        Weechat::info_get("nick_color", nick).unwrap_or_default()
+    }
+
+    /// Get the color name of a nick.
+    ///
+    /// This is the same color `nick_color()` returns, as a color name that
+    /// can be passed to `Weechat::color()` or used as the color of a nick in
+    /// the nicklist.
+    ///
+    /// # Arguments
+    ///
+    /// * `nick` - The nick that should be colored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// # use weechat::buffer::{BufferBuilder, NickSettings};
+    /// # let buffer_handle = BufferBuilder::new("test").build().unwrap();
+    /// # let buffer = buffer_handle.upgrade().unwrap();
+    /// let color = Weechat::nick_color_name("poljar");
+    ///
+    /// buffer
+    ///     .add_nick(NickSettings::new("poljar").set_color(&color))
+    ///     .expect("Can't add nick to buffer");
+    /// ```
+    pub fn nick_color_name(nick: &str) -> String {
+        Weechat::info_get("nick_color_name", nick).unwrap_or_default()
+    }
+
     /// Remove WeeChat colors from a string.
     ///
     /// # Arguments
@@ -434,12 +622,118 @@ impl Weechat {
 
         let remove_color = weechat.get().string_remove_color.unwrap();
 
-        let string = unsafe {
+        unsafe {
             let ptr = remove_color(string.as_ptr(), ptr::null());
-            CString::from_raw(ptr)
-        };
 
-        string.to_string_lossy().to_string()
+            if ptr.is_null() {
+                return String::new();
+            }
+
+            // Weechat allocates the string with malloc.
+            let string = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            libc::free(ptr as *mut libc::c_void);
+
+            string
+        }
+    }
+
+    /// Convert a string from the given charset to the internal Weechat
+    /// charset (UTF-8).
+    ///
+    /// Weechat doesn't report conversion errors, if the string can't be
+    /// converted, e.g. because the charset is unknown, the string is returned
+    /// unconverted with invalid UTF-8 sequences replaced by `?`. Strings that
+    /// already are valid UTF-8 are returned unconverted as well.
+    ///
+    /// Returns an error if the string contains a NUL byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `charset` - The charset the string is encoded in, e.g. `iso-8859-1`.
+    ///
+    /// * `string` - The bytes of the string that should be converted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// let string = Weechat::iconv_to_internal("iso-8859-1", b"caf\xe9").unwrap();
+    /// assert_eq!(string, "café");
+    /// ```
+    pub fn iconv_to_internal(charset: &str, string: &[u8]) -> Result<String, ()> {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let charset = LossyCString::new(charset);
+        let string = CString::new(string).map_err(|_| ())?;
+
+        let iconv_to_internal = weechat.get().iconv_to_internal.unwrap();
+
+        unsafe {
+            let ptr = iconv_to_internal(charset.as_ptr(), string.as_ptr());
+
+            if ptr.is_null() {
+                Err(())
+            } else {
+                // Weechat allocates the string with malloc.
+                let string = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+                libc::free(ptr as *mut libc::c_void);
+
+                Ok(string)
+            }
+        }
+    }
+
+    /// Convert a string from the internal Weechat charset (UTF-8) to the given
+    /// charset.
+    ///
+    /// Weechat doesn't report conversion errors, if the string can't be
+    /// converted the UTF-8 bytes of the string are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `charset` - The charset the string should be converted to, e.g.
+    ///     `iso-8859-1`.
+    ///
+    /// * `string` - The string that should be converted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// let bytes = Weechat::iconv_from_internal("iso-8859-1", "café").unwrap();
+    /// assert_eq!(bytes, b"caf\xe9");
+    /// ```
+    pub fn iconv_from_internal(charset: &str, string: &str) -> Result<Vec<u8>, ()> {
+        Weechat::check_thread();
+        let weechat = unsafe { Weechat::weechat() };
+
+        let charset = LossyCString::new(charset);
+        let string = LossyCString::new(string);
+
+        let iconv_from_internal = weechat.get().iconv_from_internal.unwrap();
+
+        unsafe {
+            let ptr = iconv_from_internal(charset.as_ptr(), string.as_ptr());
+
+            if ptr.is_null() {
+                Err(())
+            } else {
+                // Weechat allocates the string with malloc.
+                let bytes = CStr::from_ptr(ptr).to_bytes().to_vec();
+                libc::free(ptr as *mut libc::c_void);
+
+                Ok(bytes)
+            }
+        }
     }
 
     /// Evaluate a Weechat expression and return the result.
@@ -448,6 +742,9 @@ impl Weechat {
     ///
     /// * `expression` - The expression that should be evaluated.
     ///
+    /// Use an `EvalExpression` if the same expression is evaluated
+    /// repeatedly.
+    ///
     /// # Panics
     ///
     /// Panics if the method is not called from the main Weechat thread.
@@ -473,11 +770,45 @@ impl Weechat {
             if result.is_null() {
                 Err(())
             } else {
-                Ok(CStr::from_ptr(result).to_string_lossy().to_string())
+                let string = CStr::from_ptr(result).to_string_lossy().to_string();
+                libc::free(result as *mut libc::c_void);
+
+                Ok(string)
             }
         }
     }
 
+    /// Run a string through a modifier.
+    ///
+    /// This is a shorthand for `execute_modifier()` that returns the input
+    /// string unchanged instead of an error if the modifier couldn't be
+    /// executed, e.g. because the modifier name is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `modifier` - The name of the modifier, e.g. `irc_color_decode`.
+    ///
+    /// * `modifier_data` - Data that will be passed to the modifier.
+    ///
+    /// * `input_string` - The string that should be modified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method is not called from the main Weechat thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use weechat::Weechat;
+    /// // Remove the IRC color codes from a message.
+    /// let message = Weechat::exec_modifier("irc_color_decode", "0", "\x02bold\x02 text");
+    /// assert_eq!(message, "bold text");
+    /// ```
+    pub fn exec_modifier(modifier: &str, modifier_data: &str, input_string: &str) -> String {
+        Weechat::execute_modifier(modifier, modifier_data, input_string)
+            .unwrap_or_else(|_| input_string.to_owned())
+    }
+
     /// Get the Weechat homedir.
     pub fn home_dir() -> PathBuf {
         Weechat::check_thread();
@@ -498,7 +829,9 @@ impl Weechat {
             if result.is_null() {
                 panic!("Returned null while evaluating the Weechat home dir");
             } else {
-                CStr::from_ptr(result).to_string_lossy().to_string()
+                let path = CStr::from_ptr(result).to_string_lossy().to_string();
+                libc::free(result as *mut libc::c_void);
+                path
             }
         };
 
@@ -534,8 +867,14 @@ impl Weechat {
     /// list of defined modifiers. For example to parse a string with some color
     /// format (ansi, irc...) and to convert it to another format.
     ///
-    /// Returns the modified string or an empty error if the string couldn't be
-    /// modified.
+    /// Returns the modified string. If no modifier with the given name exists
+    /// or none of the modifiers changed the string, a copy of the input string
+    /// is returned. An empty string is returned if a modifier dropped the
+    /// string.
+    ///
+    /// An empty error is returned only if the arguments are invalid, e.g. the
+    /// modifier name is empty, or if Weechat failed to allocate the result.
+    /// Use `exec_modifier()` to get the input string back in that case.
     ///
     /// # Arguments
     ///
@@ -577,7 +916,10 @@ impl Weechat {
             if result.is_null() {
                 Err(())
             } else {
-                Ok(CStr::from_ptr(result).to_string_lossy().to_string())
+                let string = CStr::from_ptr(result).to_string_lossy().to_string();
+                libc::free(result as *mut libc::c_void);
+
+                Ok(string)
             }
         }
     }